@@ -1,5 +1,6 @@
 //! Authentication utilities
 
+use reqwest::header::HeaderValue;
 use secrecy::{ExposeSecret, SecretString};
 
 /// Personal access token for authenticating with Everruns.
@@ -23,15 +24,74 @@ impl ApiKey {
     pub(crate) fn expose(&self) -> &str {
         self.0.expose_secret()
     }
+
+    /// Build the `Authorization` header value, marked sensitive so HTTP
+    /// tooling never logs it.
+    pub(crate) fn header_value(&self) -> Result<HeaderValue, crate::Error> {
+        let mut value = HeaderValue::from_str(self.expose()).map_err(|_| {
+            crate::Error::Auth("API key contains characters not allowed in a header".to_string())
+        })?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
 }
 
 impl std::fmt::Debug for ApiKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let key = self.expose();
-        if key.len() > 8 {
-            write!(f, "ApiKey({}...)", &key[..8])
-        } else {
-            write!(f, "ApiKey(***)")
+        match key.get(..8) {
+            Some(prefix) if key.len() > 8 => write!(f, "ApiKey({prefix}...)"),
+            _ => write!(f, "ApiKey(***)"),
         }
     }
 }
+
+/// Wrapper that keeps a value out of `{:?}` output.
+///
+/// Serializes and deserializes as the inner value, so it can sit in request
+/// and response models; only `Debug` is redacted. Use it for fields holding
+/// credentials, so logging a whole request never leaks them.
+#[derive(Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Sensitive<T>(T);
+
+impl<T> Sensitive<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Borrow the wrapped value
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Sensitive<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> std::fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitive_redacts_debug_but_serializes_value() {
+        let secret = Sensitive::new("sk-live-123".to_string());
+        assert_eq!(format!("{secret:?}"), "[redacted]");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), r#""sk-live-123""#);
+        let back: Sensitive<String> = serde_json::from_str(r#""sk-live-123""#).unwrap();
+        assert_eq!(back, secret);
+    }
+}
@@ -0,0 +1,64 @@
+//! In-memory cache for GET responses.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct Entry {
+    body: Arc<[u8]>,
+    etag: Option<String>,
+    fetched_at: Instant,
+}
+
+/// Response bodies keyed by request URL.
+///
+/// Entries younger than the TTL are served without a request. Older entries
+/// that carried an `ETag` are revalidated with `If-None-Match`.
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Body cached for `key` if it is still within the TTL
+    pub(crate) fn fresh(&self, key: &str) -> Option<Arc<[u8]>> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+        (entry.fetched_at.elapsed() < self.ttl).then(|| entry.body.clone())
+    }
+
+    /// Validator to send with a conditional request for `key`
+    pub(crate) fn etag(&self, key: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(key)?.etag.clone()
+    }
+
+    /// Mark `key` as confirmed unchanged by the server and return its body
+    pub(crate) fn revalidate(&self, key: &str) -> Option<Arc<[u8]>> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(key)?;
+        entry.fetched_at = Instant::now();
+        Some(entry.body.clone())
+    }
+
+    pub(crate) fn store(&self, key: String, etag: Option<String>, body: Arc<[u8]>) {
+        self.entries.lock().unwrap().insert(
+            key,
+            Entry {
+                body,
+                etag,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
@@ -3,18 +3,61 @@
 use crate::auth::ApiKey;
 use crate::error::{Error, Result};
 use crate::models::*;
+use crate::progress::{Progress, SharedProgress, Tracker};
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
 use url::Url;
 
 const DEFAULT_BASE_URL: &str = "https://custom.example.com/api";
 
+/// Overall timeout for non-streaming requests unless the builder sets one
+const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// `User-Agent` sent on every request, before any configured suffix
+const USER_AGENT: &str = concat!("everruns-sdk-rust/", env!("CARGO_PKG_VERSION"));
+
+/// Chunk size for uploads that report progress
+const UPLOAD_CHUNK_BYTES: usize = 64 * 1024;
+
 /// Main client for interacting with the Everruns API
 #[derive(Clone)]
 pub struct Everruns {
     http: reqwest::Client,
     base_url: Url,
+    /// `base_url` joined with the API version prefix, computed once so
+    /// per-request URL building only appends path segments.
+    api_base: Url,
     api_key: ApiKey,
+    /// `Authorization` header built from `api_key`, validated at construction.
+    authorization: HeaderValue,
     org_id: Option<HeaderValue>,
+    /// `User-Agent` and builder-configured headers sent on every request.
+    default_headers: HeaderMap,
+    /// Connect timeout from the builder, also applied to SSE connections.
+    #[cfg(feature = "sse")]
+    connect_timeout: Option<std::time::Duration>,
+    /// Server clock minus local clock in milliseconds, learned from the
+    /// `Date` header of API responses and shared across clones.
+    clock_skew_ms: std::sync::Arc<std::sync::atomic::AtomicI64>,
+    /// GET response cache, enabled with [`EverrunsBuilder::cache_ttl`].
+    cache: Option<std::sync::Arc<crate::cache::ResponseCache>>,
+    /// In-flight request tracking for [`Everruns::shutdown`], shared across clones.
+    lifecycle: std::sync::Arc<crate::shutdown::Lifecycle>,
+    /// Retry budget, enabled with [`EverrunsBuilder::retry_budget`].
+    retry_budget: Option<std::sync::Arc<crate::retry::BudgetTracker>>,
+    /// Request retries, enabled with [`EverrunsBuilder::retry_policy`].
+    retry_policy: Option<crate::retry::RetryPolicy>,
+    /// Outgoing content hook, set with [`EverrunsBuilder::redactor`].
+    redactor: Option<crate::redact::SharedRedactor>,
+    /// Request hooks, added with [`EverrunsBuilder::interceptor`].
+    interceptors: crate::intercept::Interceptors,
+}
+
+/// Options for [`Everruns::warm_up_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct WarmUpOptions {
+    /// Also make an authenticated request, so an invalid API key fails here
+    /// rather than on first use.
+    pub validate_auth: bool,
 }
 
 /// Builder for configuring an Everruns client.
@@ -23,6 +66,15 @@ pub struct EverrunsBuilder {
     api_key: Option<ApiKey>,
     base_url: String,
     org_id: Option<String>,
+    cache_ttl: Option<std::time::Duration>,
+    retry_budget: Option<crate::retry::RetryBudget>,
+    retry_policy: Option<crate::retry::RetryPolicy>,
+    redactor: Option<crate::redact::SharedRedactor>,
+    interceptors: crate::intercept::Interceptors,
+    timeout: std::time::Duration,
+    connect_timeout: Option<std::time::Duration>,
+    user_agent_suffix: Option<String>,
+    default_headers: Vec<(String, String)>,
 }
 
 impl Default for EverrunsBuilder {
@@ -33,6 +85,15 @@ impl Default for EverrunsBuilder {
             org_id: std::env::var("EVERRUNS_ORG_ID")
                 .ok()
                 .filter(|org_id| !org_id.is_empty()),
+            cache_ttl: None,
+            retry_budget: None,
+            retry_policy: None,
+            redactor: None,
+            interceptors: Default::default(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: None,
+            user_agent_suffix: None,
+            default_headers: Vec::new(),
         }
     }
 }
@@ -56,13 +117,112 @@ impl EverrunsBuilder {
         self
     }
 
+    /// Cache GET responses in memory for `ttl`.
+    ///
+    /// Cached entries that carried an `ETag` are revalidated once stale. Any
+    /// write made through the client clears the cache.
+    pub fn cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Limit SDK-initiated retries (SSE reconnects, bulk-delete 429 retries,
+    /// session capacity waits) to a share of recent requests, across all
+    /// clones of the client.
+    ///
+    /// When the budget is spent, SSE reconnects wait the maximum backoff and
+    /// other retries give up with the last error.
+    pub fn retry_budget(mut self, budget: crate::retry::RetryBudget) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
+    /// Retry requests that fail with a transient status (by default 429,
+    /// 502, 503 and 504) or cannot connect, per `policy`.
+    ///
+    /// Off by default. Retries also spend from the
+    /// [`retry_budget`](Self::retry_budget) when one is set. Multipart
+    /// uploads and SSE streams are not covered.
+    pub fn retry_policy(mut self, policy: crate::retry::RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Scrub the content of every outgoing message and tool result with
+    /// `redactor` before it is sent. See [`crate::redact`].
+    pub fn redactor(mut self, redactor: impl crate::redact::Redactor + 'static) -> Self {
+        self.redactor = Some(crate::redact::SharedRedactor::new(redactor));
+        self
+    }
+
+    /// Run `interceptor` around every request the client sends, after any
+    /// added before it. See [`crate::intercept`].
+    pub fn interceptor(
+        mut self,
+        interceptor: impl crate::intercept::Interceptor + 'static,
+    ) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Set the overall timeout for non-streaming requests (default 30 seconds).
+    ///
+    /// SSE streams are long-lived and not bound by it.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for establishing a connection, including SSE streams.
+    pub fn connect_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Append `suffix` to the SDK's `User-Agent`, e.g. `"my-app/1.2"`.
+    pub fn user_agent_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.user_agent_suffix = Some(suffix.into());
+        self
+    }
+
+    /// Send an extra header on every request.
+    ///
+    /// Auth and org headers set by the client take precedence over a header
+    /// of the same name. Invalid names or values fail [`build`](Self::build).
+    pub fn default_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.push((name.into(), value.into()));
+        self
+    }
+
     /// Build the client.
     pub fn build(self) -> Result<Everruns> {
-        let api_key = match self.api_key {
+        let api_key = match self.api_key.clone() {
             Some(api_key) => api_key,
             None => ApiKey::from_env()?,
         };
-        Everruns::with_api_key_url_and_org_id(api_key, &self.base_url, self.org_id)
+        Everruns::from_builder(api_key, self)
+    }
+
+    /// `User-Agent` plus the configured default headers.
+    fn header_map(&self) -> Result<HeaderMap> {
+        let user_agent = match &self.user_agent_suffix {
+            Some(suffix) => format!("{USER_AGENT} {suffix}"),
+            None => USER_AGENT.to_string(),
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            reqwest::header::USER_AGENT,
+            HeaderValue::from_str(&user_agent)
+                .map_err(|err| Error::Validation(format!("invalid user agent: {err}")))?,
+        );
+        for (name, value) in &self.default_headers {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|err| Error::Validation(format!("invalid header name {name}: {err}")))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|err| Error::Validation(format!("invalid value for {name}: {err}")))?;
+            headers.append(name, value);
+        }
+        Ok(headers)
     }
 }
 
@@ -111,21 +271,17 @@ impl Everruns {
 
     /// Create a new client with an ApiKey instance
     pub fn with_api_key(api_key: ApiKey) -> Result<Self> {
-        Self::with_api_key_url_and_org_id(
-            api_key,
-            DEFAULT_BASE_URL,
-            EverrunsBuilder::default().org_id,
-        )
+        Self::from_builder(api_key, EverrunsBuilder::default())
     }
 
-    fn with_api_key_url_and_org_id(
-        api_key: ApiKey,
-        base_url: &str,
-        org_id: Option<String>,
-    ) -> Result<Self> {
-        let http = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+    fn from_builder(api_key: ApiKey, builder: EverrunsBuilder) -> Result<Self> {
+        let mut http = reqwest::Client::builder().timeout(builder.timeout);
+        if let Some(timeout) = builder.connect_timeout {
+            http = http.connect_timeout(timeout);
+        }
+        let http = http.build()?;
+        let default_headers = builder.header_map()?;
+        let base_url = builder.base_url.as_str();
 
         // Ensure base URL has trailing slash for correct URL joining.
         // Url::join follows RFC 3986: without trailing slash, relative paths
@@ -138,7 +294,15 @@ impl Everruns {
             format!("{}/", base_url)
         };
         let base_url = Url::parse(&normalized)?;
-        let org_id = org_id
+        if base_url.cannot_be_a_base() {
+            return Err(Error::Validation(format!(
+                "base URL cannot be used as a base: {base_url}"
+            )));
+        }
+        let api_base = base_url.join("v1/")?;
+        let authorization = api_key.header_value()?;
+        let org_id = builder
+            .org_id
             .map(|org_id| {
                 if org_id.is_empty() {
                     return Err(Error::Validation("org_id cannot be empty".to_string()));
@@ -151,11 +315,180 @@ impl Everruns {
         Ok(Self {
             http,
             base_url,
+            api_base,
             api_key,
+            authorization,
             org_id,
+            default_headers,
+            #[cfg(feature = "sse")]
+            connect_timeout: builder.connect_timeout,
+            clock_skew_ms: Default::default(),
+            cache: builder
+                .cache_ttl
+                .map(|ttl| std::sync::Arc::new(crate::cache::ResponseCache::new(ttl))),
+            lifecycle: Default::default(),
+            retry_budget: builder
+                .retry_budget
+                .map(|budget| std::sync::Arc::new(crate::retry::BudgetTracker::new(budget))),
+            retry_policy: builder.retry_policy,
+            redactor: builder.redactor,
+            interceptors: builder.interceptors,
         })
     }
 
+    /// Compare the org's agents with `manifest` without changing anything.
+    pub async fn plan(
+        &self,
+        manifest: &crate::manifest::Manifest,
+    ) -> Result<crate::manifest::Plan> {
+        crate::manifest::plan(self, manifest).await
+    }
+
+    /// Create, update and archive agents so the org matches `manifest`.
+    ///
+    /// Returns the plan that was applied. Changes are made one at a time; on
+    /// failure, earlier changes stay applied and running again resumes.
+    pub async fn apply(
+        &self,
+        manifest: &crate::manifest::Manifest,
+    ) -> Result<crate::manifest::Plan> {
+        crate::manifest::apply(self, manifest).await
+    }
+
+    /// Drop all cached GET responses.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Shut the client down gracefully.
+    ///
+    /// New requests on this client and its clones fail with
+    /// [`Error::ShuttingDown`], open [`EventStream`](crate::sse::EventStream)s end
+    /// after the event they are delivering (their
+    /// [`last_event_id`](crate::sse::EventStream::last_event_id)
+    /// stays available for resuming), and in-flight requests are given up to
+    /// `timeout` to finish. Returns `false` if some were still running when
+    /// the timeout elapsed.
+    pub async fn shutdown(&self, timeout: std::time::Duration) -> bool {
+        self.lifecycle.shutdown(timeout).await
+    }
+
+    /// Register an outgoing request for shutdown and retry accounting.
+    pub(crate) fn start_request(&self) -> Result<crate::shutdown::InFlight> {
+        let request = self.lifecycle.begin()?;
+        if let Some(budget) = &self.retry_budget {
+            budget.record_request();
+        }
+        Ok(request)
+    }
+
+    /// Run the configured redactor, if any, over outgoing content parts.
+    pub(crate) fn redact(&self, session_id: &str, parts: &mut [ContentPart]) {
+        if let Some(redactor) = &self.redactor {
+            redactor.apply(session_id, parts);
+        }
+    }
+
+    /// Spend one retry from the budget; always allowed without a budget.
+    pub(crate) fn try_retry(&self) -> bool {
+        self.retry_budget
+            .as_ref()
+            .is_none_or(|budget| budget.try_retry())
+    }
+
+    #[cfg(feature = "sse")]
+    pub(crate) fn connect_timeout(&self) -> Option<std::time::Duration> {
+        self.connect_timeout
+    }
+
+    #[cfg(feature = "sse")]
+    pub(crate) fn lifecycle(&self) -> &std::sync::Arc<crate::shutdown::Lifecycle> {
+        &self.lifecycle
+    }
+
+    /// Fetch the server's current time and update the tracked clock skew.
+    pub async fn server_time(&self) -> Result<std::time::SystemTime> {
+        let _request = self.start_request()?;
+        let resp = self
+            .send(|| {
+                self.http
+                    .get(self.url("/durable/health"))
+                    .headers(self.headers())
+            })
+            .await?;
+        self.observe_date(&resp).ok_or_else(|| Error::Api {
+            code: "missing_date".to_string(),
+            message: "response has no Date header".to_string(),
+            status: resp.status().as_u16(),
+        })
+    }
+
+    /// Connect to the API ahead of first use.
+    ///
+    /// Resolves DNS and completes the TCP and TLS handshakes with a cheap
+    /// health request, leaving the connection pooled for the first real
+    /// call. Also updates the tracked clock skew. The health status itself
+    /// is ignored; only transport failures are returned.
+    pub async fn warm_up(&self) -> Result<()> {
+        self.warm_up_with_options(&WarmUpOptions::default()).await
+    }
+
+    /// Connect ahead of first use, optionally checking the API key as well.
+    pub async fn warm_up_with_options(&self, options: &WarmUpOptions) -> Result<()> {
+        {
+            let _request = self.start_request()?;
+            let resp = self
+                .send(|| {
+                    self.http
+                        .get(self.url("/durable/health"))
+                        .headers(self.headers())
+                })
+                .await?;
+            self.observe_date(&resp);
+            // Read the body so the connection goes back to the pool.
+            resp.bytes().await?;
+        }
+        if options.validate_auth {
+            let mut url = self.url("/agents");
+            url.query_pairs_mut().append_pair("limit", "1");
+            self.get_url::<serde::de::IgnoredAny>(url).await?;
+        }
+        Ok(())
+    }
+
+    /// Current time corrected by the clock skew observed on API responses.
+    ///
+    /// Falls back to the local clock until a response with a `Date` header
+    /// has been seen.
+    pub fn now(&self) -> std::time::SystemTime {
+        let now = std::time::SystemTime::now();
+        let skew = self.clock_skew_millis();
+        let offset = std::time::Duration::from_millis(skew.unsigned_abs());
+        if skew < 0 { now - offset } else { now + offset }
+    }
+
+    /// Server clock minus local clock in milliseconds (zero until measured).
+    pub fn clock_skew_millis(&self) -> i64 {
+        self.clock_skew_ms
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Record the skew between the response `Date` header and the local clock.
+    fn observe_date(&self, resp: &reqwest::Response) -> Option<std::time::SystemTime> {
+        let date = resp.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+        let server = httpdate::parse_http_date(date).ok()?;
+        let local = std::time::SystemTime::now();
+        let skew_ms = match server.duration_since(local) {
+            Ok(ahead) => ahead.as_millis() as i64,
+            Err(behind) => -(behind.duration().as_millis() as i64),
+        };
+        self.clock_skew_ms
+            .store(skew_ms, std::sync::atomic::Ordering::Relaxed);
+        Some(server)
+    }
+
     /// Get the agents client
     pub fn agents(&self) -> AgentsClient<'_> {
         AgentsClient { client: self }
@@ -196,6 +529,36 @@ impl Everruns {
         MemoriesClient { client: self }
     }
 
+    /// Get the images client
+    pub fn images(&self) -> ImagesClient<'_> {
+        ImagesClient { client: self }
+    }
+
+    /// Get the models client
+    pub fn models(&self) -> ModelsClient<'_> {
+        ModelsClient { client: self }
+    }
+
+    /// Get the knowledge bases client
+    pub fn knowledge_bases(&self) -> KnowledgeBasesClient<'_> {
+        KnowledgeBasesClient { client: self }
+    }
+
+    /// Get the janitor client for bulk cleanup
+    pub fn janitor(&self) -> JanitorClient<'_> {
+        JanitorClient { client: self }
+    }
+
+    /// Get the users client (members of the current organization)
+    pub fn users(&self) -> UsersClient<'_> {
+        UsersClient { client: self }
+    }
+
+    /// Get the voice client
+    pub fn voice(&self) -> VoiceClient<'_> {
+        VoiceClient { client: self }
+    }
+
     /// Get the connections client
     pub fn connections(&self) -> ConnectionsClient<'_> {
         ConnectionsClient { client: self }
@@ -206,20 +569,33 @@ impl Everruns {
         BudgetsClient { client: self }
     }
 
+    /// Get the token counting client
+    pub fn tokens(&self) -> TokensClient<'_> {
+        TokensClient { client: self }
+    }
+
+    /// Get the raw client, for endpoints the SDK does not wrap yet
+    pub fn raw(&self) -> RawClient<'_> {
+        RawClient { client: self }
+    }
+
     pub(crate) fn url(&self, path: &str) -> Url {
-        // Use relative path (no leading slash) for correct joining with base URL.
+        // Append segments to the precomputed versioned base instead of
+        // formatting and re-parsing a full URL string for every request.
         // The path parameter starts with "/" (e.g., "/agents"), so we strip it.
         let path_without_slash = path.strip_prefix('/').unwrap_or(path);
-        let full_path = format!("v1/{}", path_without_slash);
-        self.base_url.join(&full_path).expect("valid URL")
+        let mut url = self.api_base.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments
+                .pop_if_empty()
+                .extend(path_without_slash.split('/'));
+        }
+        url
     }
 
     pub(crate) fn auth_headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(self.api_key.expose()).expect("valid header"),
-        );
+        let mut headers = self.default_headers.clone();
+        headers.insert(AUTHORIZATION, self.authorization.clone());
         if let Some(org_id) = &self.org_id {
             headers.insert("X-Org-Id", org_id.clone());
         }
@@ -233,20 +609,97 @@ impl Everruns {
     }
 
     pub(crate) async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.get_url(self.url(path)).await
+    }
+
+    pub(crate) async fn get_url<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<T> {
+        let _request = self.start_request()?;
+        if let Some(cache) = &self.cache {
+            return self.get_cached(cache, url).await;
+        }
         let resp = self
-            .http
-            .get(self.url(path))
-            .headers(self.headers())
-            .send()
+            .send(|| self.http.get(url.clone()).headers(self.headers()))
             .await?;
 
         self.handle_response(resp).await
     }
 
-    pub(crate) async fn get_url<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<T> {
-        let resp = self.http.get(url).headers(self.headers()).send().await?;
+    async fn get_cached<T: serde::de::DeserializeOwned>(
+        &self,
+        cache: &crate::cache::ResponseCache,
+        url: Url,
+    ) -> Result<T> {
+        let key = url.to_string();
+        if let Some(body) = cache.fresh(&key) {
+            return Ok(serde_json::from_slice(&body)?);
+        }
 
-        self.handle_response(resp).await
+        let mut headers = self.headers();
+        if let Some(etag) = cache
+            .etag(&key)
+            .and_then(|e| HeaderValue::from_str(&e).ok())
+        {
+            headers.insert(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        let mut resp = self
+            .send(|| self.http.get(url.clone()).headers(headers.clone()))
+            .await?;
+        self.observe_date(&resp);
+
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(body) = cache.revalidate(&key) {
+                return Ok(serde_json::from_slice(&body)?);
+            }
+            // The entry was evicted while the request was in flight; fetch
+            // the full body unconditionally.
+            headers.remove(reqwest::header::IF_NONE_MATCH);
+            resp = self
+                .send(|| self.http.get(url.clone()).headers(headers.clone()))
+                .await?;
+            self.observe_date(&resp);
+        }
+        if !resp.status().is_success() {
+            return Err(Error::from_response(resp).await);
+        }
+
+        let etag = resp
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body: std::sync::Arc<[u8]> = resp.bytes().await?.to_vec().into();
+        let value = serde_json::from_slice(&body)?;
+        cache.store(key, etag, body);
+        Ok(value)
+    }
+
+    /// GET a `{"data": [...]}` list and yield its items as they are parsed.
+    pub(crate) fn get_list_stream<T>(&self, url: Url) -> crate::pagination::PageStream<T>
+    where
+        T: serde::de::DeserializeOwned + Send + 'static,
+    {
+        let client = self.clone();
+        Box::pin(async_stream::try_stream! {
+            use futures::StreamExt;
+
+            let _request = client.start_request()?;
+            let resp = client
+                .send(|| client.http.get(url.clone()).headers(client.headers()))
+                .await?;
+            if !resp.status().is_success() {
+                Err(Error::from_response(resp).await)?;
+                return;
+            }
+
+            let mut scanner = crate::json_stream::DataArrayScanner::new();
+            let mut body = resp.bytes_stream();
+            while let Some(chunk) = body.next().await {
+                for element in scanner.feed(&chunk?)? {
+                    yield serde_json::from_slice::<T>(&element)?;
+                }
+            }
+            scanner.finish()?;
+        })
     }
 
     pub(crate) async fn post<T: serde::de::DeserializeOwned, B: serde::Serialize>(
@@ -254,12 +707,23 @@ impl Everruns {
         path: &str,
         body: &B,
     ) -> Result<T> {
+        self.post_url(self.url(path), body).await
+    }
+
+    pub(crate) async fn post_url<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        url: Url,
+        body: &B,
+    ) -> Result<T> {
+        let _request = self.start_request()?;
+        self.clear_cache();
         let resp = self
-            .http
-            .post(self.url(path))
-            .headers(self.headers())
-            .json(body)
-            .send()
+            .send(|| {
+                self.http
+                    .post(url.clone())
+                    .headers(self.headers())
+                    .json(body)
+            })
             .await?;
 
         self.handle_response(resp).await
@@ -270,12 +734,23 @@ impl Everruns {
         path: &str,
         body: &B,
     ) -> Result<T> {
+        self.patch_url(self.url(path), body).await
+    }
+
+    pub(crate) async fn patch_url<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        url: Url,
+        body: &B,
+    ) -> Result<T> {
+        let _request = self.start_request()?;
+        self.clear_cache();
         let resp = self
-            .http
-            .patch(self.url(path))
-            .headers(self.headers())
-            .json(body)
-            .send()
+            .send(|| {
+                self.http
+                    .patch(url.clone())
+                    .headers(self.headers())
+                    .json(body)
+            })
             .await?;
 
         self.handle_response(resp).await
@@ -286,14 +761,18 @@ impl Everruns {
         path: &str,
         body: &str,
     ) -> Result<T> {
+        let _request = self.start_request()?;
+        self.clear_cache();
         let mut headers = self.headers();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        let url = self.url(path);
         let resp = self
-            .http
-            .post(self.url(path))
-            .headers(headers)
-            .body(body.to_string())
-            .send()
+            .send(|| {
+                self.http
+                    .post(url.clone())
+                    .headers(headers.clone())
+                    .body(body.to_string())
+            })
             .await?;
 
         self.handle_response(resp).await
@@ -304,34 +783,87 @@ impl Everruns {
         url: Url,
         body: &str,
     ) -> Result<T> {
+        let _request = self.start_request()?;
+        self.clear_cache();
         let mut headers = self.headers();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
         let resp = self
-            .http
-            .post(url)
-            .headers(headers)
-            .body(body.to_string())
-            .send()
+            .send(|| {
+                self.http
+                    .post(url.clone())
+                    .headers(headers.clone())
+                    .body(body.to_string())
+            })
             .await?;
 
         self.handle_response(resp).await
     }
 
     pub(crate) async fn get_text(&self, path: &str) -> Result<String> {
+        let _request = self.start_request()?;
+        let url = self.url(path);
         let resp = self
-            .http
-            .get(self.url(path))
-            .headers(self.headers())
-            .send()
+            .send(|| self.http.get(url.clone()).headers(self.headers()))
             .await?;
 
         if resp.status().is_success() {
             Ok(resp.text().await?)
         } else {
-            let status = resp.status().as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            Err(Error::from_api_response(status, &body))
+            Err(Error::from_response(resp).await)
+        }
+    }
+
+    pub(crate) async fn post_multipart_url<T: serde::de::DeserializeOwned>(
+        &self,
+        url: Url,
+        form: reqwest::multipart::Form,
+    ) -> Result<T> {
+        let _request = self.start_request()?;
+        self.clear_cache();
+        // No JSON content type: reqwest sets the multipart boundary header.
+        let request = self
+            .http
+            .post(url)
+            .headers(self.auth_headers())
+            .multipart(form);
+        let resp = self.execute(&self.http, request).await?;
+
+        self.handle_response(resp).await
+    }
+
+    pub(crate) async fn get_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        self.get_bytes_with_progress(path, None).await
+    }
+
+    pub(crate) async fn get_bytes_with_progress(
+        &self,
+        path: &str,
+        progress: Option<SharedProgress>,
+    ) -> Result<Vec<u8>> {
+        use futures::StreamExt;
+
+        let _request = self.start_request()?;
+        let url = self.url(path);
+        let resp = self
+            .send(|| self.http.get(url.clone()).headers(self.auth_headers()))
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(Error::from_response(resp).await);
+        }
+        let Some(progress) = progress else {
+            return Ok(resp.bytes().await?.to_vec());
+        };
+        let tracker = Tracker::bytes(progress, resp.content_length());
+        tracker.start();
+        let mut bytes = Vec::new();
+        let mut body = resp.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            tracker.advance(chunk.len() as u64);
+            bytes.extend_from_slice(&chunk);
         }
+        Ok(bytes)
     }
 
     pub(crate) async fn put<T: serde::de::DeserializeOwned, B: serde::Serialize>(
@@ -339,71 +871,142 @@ impl Everruns {
         path: &str,
         body: &B,
     ) -> Result<T> {
+        let _request = self.start_request()?;
+        self.clear_cache();
+        let url = self.url(path);
         let resp = self
-            .http
-            .put(self.url(path))
-            .headers(self.headers())
-            .json(body)
-            .send()
+            .send(|| {
+                self.http
+                    .put(url.clone())
+                    .headers(self.headers())
+                    .json(body)
+            })
             .await?;
 
         self.handle_response(resp).await
     }
 
     pub(crate) async fn put_empty(&self, path: &str) -> Result<()> {
+        let _request = self.start_request()?;
+        self.clear_cache();
+        let url = self.url(path);
         let resp = self
-            .http
-            .put(self.url(path))
-            .headers(self.headers())
-            .send()
+            .send(|| self.http.put(url.clone()).headers(self.headers()))
             .await?;
 
         if resp.status().is_success() {
             Ok(())
         } else {
-            let status = resp.status().as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            Err(Error::from_api_response(status, &body))
+            Err(Error::from_response(resp).await)
         }
     }
 
     pub(crate) async fn delete(&self, path: &str) -> Result<()> {
+        self.delete_empty_url(self.url(path)).await
+    }
+
+    pub(crate) async fn delete_empty_url(&self, url: Url) -> Result<()> {
+        let _request = self.start_request()?;
+        self.clear_cache();
         let resp = self
-            .http
-            .delete(self.url(path))
-            .headers(self.headers())
-            .send()
+            .send(|| self.http.delete(url.clone()).headers(self.headers()))
             .await?;
 
         if resp.status().is_success() {
             Ok(())
         } else {
-            let status = resp.status().as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            Err(Error::from_api_response(status, &body))
+            Err(Error::from_response(resp).await)
         }
     }
 
     pub(crate) async fn delete_url<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<T> {
-        let resp = self.http.delete(url).headers(self.headers()).send().await?;
+        let _request = self.start_request()?;
+        self.clear_cache();
+        let resp = self
+            .send(|| self.http.delete(url.clone()).headers(self.headers()))
+            .await?;
 
         self.handle_response(resp).await
     }
 
+    /// Send the request `build` makes, retrying transient failures per the
+    /// configured [`RetryPolicy`](crate::retry::RetryPolicy).
+    async fn send(&self, build: impl Fn() -> reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let request = build().build()?;
+            let idempotent = request.method().is_idempotent();
+            let result = self.dispatch(&self.http, request).await;
+            let Some(policy) = &self.retry_policy else {
+                return result;
+            };
+            let (transient, requested) = match &result {
+                // The server may have acted on a request that failed with a
+                // 5xx, so only resend it if doing so twice is harmless.
+                Ok(resp) => (
+                    policy.statuses.contains(&resp.status().as_u16())
+                        && (idempotent || !resp.status().is_server_error()),
+                    crate::retry::retry_after(resp.headers()),
+                ),
+                Err(Error::Network(err)) => (err.is_connect(), None),
+                Err(_) => (false, None),
+            };
+            if !transient || attempt >= policy.max_retries {
+                return result;
+            }
+            let wait = match requested {
+                // Retrying sooner than the server asked would only be refused
+                // again, so hand its wait to the caller instead.
+                Some(wait) if wait > policy.max_backoff => return result,
+                Some(wait) => wait,
+                None => policy.backoff(attempt),
+            };
+            if !self.try_retry() {
+                return result;
+            }
+            tracing::debug!("Transient request failure, retrying in {:?}", wait);
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send `request` on `http`, running the configured interceptors around it.
+    pub(crate) async fn execute(
+        &self,
+        http: &reqwest::Client,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        self.dispatch(http, request.build()?).await
+    }
+
+    async fn dispatch(
+        &self,
+        http: &reqwest::Client,
+        mut request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        if self.interceptors.is_empty() {
+            return Ok(http.execute(request).await?);
+        }
+        let parts = self.interceptors.before(&mut request)?;
+        let resp = http.execute(request).await?;
+        self.interceptors.after(&parts, &resp);
+        Ok(resp)
+    }
+
     async fn handle_response<T: serde::de::DeserializeOwned>(
         &self,
         resp: reqwest::Response,
     ) -> Result<T> {
+        self.observe_date(&resp);
         if resp.status().is_success() {
             Ok(resp.json().await?)
         } else {
-            let status = resp.status().as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            Err(Error::from_api_response(status, &body))
+            Err(Error::from_response(resp).await)
         }
     }
 
     /// Get the SSE URL for a session
+    #[cfg(feature = "sse")]
     pub(crate) fn sse_url(
         &self,
         session_id: &str,
@@ -425,6 +1028,78 @@ impl Everruns {
     }
 }
 
+/// Client for calling API endpoints without typed support.
+///
+/// Paths are relative to the base URL and include the version prefix, e.g.
+/// `/v1/sessions`. Requests carry the client's auth and org headers, count
+/// toward shutdown and the retry budget, and fail with the same [`Error`]s as
+/// typed calls.
+pub struct RawClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> RawClient<'a> {
+    /// GET `path` with `query` parameters and deserialize the JSON response.
+    pub async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T> {
+        let mut url = self.url(path);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        self.client.get_url(url).await
+    }
+
+    /// POST `body` as JSON to `path` and deserialize the JSON response.
+    pub async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.client.post_url(self.url(path), body).await
+    }
+
+    /// PATCH `body` as JSON to `path` and deserialize the JSON response.
+    pub async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.client.patch_url(self.url(path), body).await
+    }
+
+    /// DELETE `path`, ignoring any response body.
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        self.client.delete_empty_url(self.url(path)).await
+    }
+
+    fn url(&self, path: &str) -> Url {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let mut url = self.client.base_url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().extend(path.split('/'));
+        }
+        url
+    }
+}
+
+/// Tag prefixes linking a subagent session to the session and turn that
+/// spawned it.
+const PARENT_SESSION_TAG: &str = "parent_session:";
+#[cfg(feature = "sse")]
+const PARENT_TURN_TAG: &str = "parent_turn:";
+
+pub(crate) fn parent_session_tag(session_id: &str) -> String {
+    format!("{PARENT_SESSION_TAG}{session_id}")
+}
+
+#[cfg(feature = "sse")]
+pub(crate) fn parent_turn_tag(turn_id: &str) -> String {
+    format!("{PARENT_TURN_TAG}{turn_id}")
+}
+
 /// Client for agent operations
 pub struct AgentsClient<'a> {
     client: &'a Everruns,
@@ -436,6 +1111,14 @@ impl<'a> AgentsClient<'a> {
         self.client.get("/agents").await
     }
 
+    /// List all active agents, fetching pages on demand.
+    pub fn list_all(
+        &self,
+        pagination: crate::pagination::PaginationOptions,
+    ) -> crate::pagination::PageStream<Agent> {
+        paginate_offset(self.client, |client| client.url("/agents"), pagination)
+    }
+
     /// List agents matching a search query (case-insensitive name/description match)
     pub async fn search(&self, query: &str) -> Result<ListResponse<Agent>> {
         let mut url = self.client.url("/agents");
@@ -560,6 +1243,39 @@ impl<'a> AgentsClient<'a> {
         self.client.post("/agents", &req).await
     }
 
+    /// Create an agent and wait until it can be read back.
+    ///
+    /// Reads can lag behind writes, so a [`get`](Self::get) straight after
+    /// [`create_with_options`](Self::create_with_options) may answer 404.
+    /// This polls `get` until the agent is readable, for up to `max_wait`,
+    /// and returns [`Error::NotYetVisible`] if it never is.
+    pub async fn create_and_confirm(
+        &self,
+        req: CreateAgentRequest,
+        max_wait: std::time::Duration,
+    ) -> Result<Agent> {
+        let agent = self.create_with_options(req).await?;
+        confirm_readable(self.client, &agent.id, max_wait, || self.get(&agent.id)).await
+    }
+
+    /// Update an agent. Only fields set on `req` change.
+    pub async fn update(&self, id: &str, req: UpdateAgentRequest) -> Result<Agent> {
+        if let Some(name) = &req.name {
+            validate_agent_name(name)?;
+        }
+        self.client.patch(&format!("/agents/{}", id), &req).await
+    }
+
+    /// Report what [`update`](Self::update) would change, without changing it.
+    pub async fn update_dry_run(
+        &self,
+        id: &str,
+        req: &UpdateAgentRequest,
+    ) -> Result<crate::diff::AgentDiff> {
+        let existing = self.get(id).await?;
+        Ok(crate::diff::AgentDiff::new(&existing, req))
+    }
+
     /// Create or update an agent with a client-supplied ID (upsert).
     ///
     /// If an agent with the given ID exists, it is updated.
@@ -597,6 +1313,43 @@ impl<'a> AgentsClient<'a> {
         self.client.post("/agents", &req).await
     }
 
+    /// Get the agent named `name`, creating it if there is none.
+    ///
+    /// Unlike [`apply_by_name_with_options`](Self::apply_by_name_with_options),
+    /// an existing agent is returned unchanged, so scripts that run
+    /// repeatedly converge on one agent without rewriting it. `build`
+    /// configures the request used when the agent has to be created; its
+    /// name is always `name`.
+    pub async fn get_or_create(
+        &self,
+        name: &str,
+        build: impl FnOnce(CreateAgentRequest) -> CreateAgentRequest,
+    ) -> Result<Agent> {
+        use futures::TryStreamExt;
+
+        validate_agent_name(name)?;
+        // Search matches substrings of names and descriptions, so the exact
+        // name may be on any page of the results.
+        let query = name.to_string();
+        let mut matches = paginate_offset::<Agent>(
+            self.client,
+            move |client| {
+                let mut url = client.url("/agents");
+                url.query_pairs_mut().append_pair("search", &query);
+                url
+            },
+            crate::pagination::PaginationOptions::new().with_lookahead(0),
+        );
+        while let Some(agent) = matches.try_next().await? {
+            if agent.name == name {
+                return Ok(agent);
+            }
+        }
+        let mut req = build(CreateAgentRequest::new(name, ""));
+        req.name = name.to_string();
+        self.client.post("/agents", &req).await
+    }
+
     /// Copy an agent, creating a new agent with the same configuration
     pub async fn copy(&self, id: &str) -> Result<Agent> {
         self.client
@@ -635,6 +1388,66 @@ impl<'a> AgentsClient<'a> {
     }
 }
 
+/// Poll `get` until it stops answering 404, backing off from 50ms up to one
+/// second between reads, for at most `max_wait`.
+async fn confirm_readable<T, F, Fut>(
+    client: &Everruns,
+    id: &str,
+    max_wait: std::time::Duration,
+    mut get: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let started = tokio::time::Instant::now();
+    let mut wait = std::time::Duration::from_millis(50);
+    loop {
+        match get().await {
+            Err(Error::Api { status: 404, .. }) => {
+                if started.elapsed() + wait > max_wait || !client.try_retry() {
+                    return Err(Error::NotYetVisible {
+                        id: id.to_string(),
+                        waited: started.elapsed(),
+                    });
+                }
+                tracing::debug!("{id} not readable yet, retrying in {:?}", wait);
+                tokio::time::sleep(wait).await;
+                wait = (wait * 2).min(std::time::Duration::from_secs(1));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Stream every item of an offset/limit list endpoint, fetching pages on
+/// demand. `url_builder` returns the endpoint URL; `offset` and `limit` are
+/// appended to it for each page.
+fn paginate_offset<T>(
+    client: &Everruns,
+    url_builder: impl Fn(&Everruns) -> Url + Send + 'static,
+    pagination: crate::pagination::PaginationOptions,
+) -> crate::pagination::PageStream<T>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    let client = client.clone();
+    let page_size = pagination.page_size.max(1);
+    crate::pagination::paginate(0u64, pagination.lookahead, move |offset| {
+        let client = client.clone();
+        let mut url = url_builder(&client);
+        url.query_pairs_mut()
+            .append_pair("offset", &offset.to_string())
+            .append_pair("limit", &page_size.to_string());
+        async move {
+            let page: ListResponse<T> = client.get_url(url).await?;
+            let fetched = page.data.len() as u64;
+            let next = (fetched >= u64::from(page_size)).then_some(offset + fetched);
+            Ok((page.data, next))
+        }
+    })
+}
+
 /// Client for session operations
 pub struct SessionsClient<'a> {
     client: &'a Everruns,
@@ -677,6 +1490,137 @@ impl<'a> SessionsClient<'a> {
         self.client.post("/sessions", &req).await
     }
 
+    /// Create a session, waiting out capacity limits for up to `max_wait`.
+    ///
+    /// While creation fails with [`Error::CapacityExceeded`], waits the
+    /// server's `retry_after` hint (or an exponential backoff from one
+    /// second when there is none) and tries again. Returns the capacity
+    /// error once the next wait would pass `max_wait` or the client's retry
+    /// budget is spent.
+    pub async fn create_with_wait(
+        &self,
+        req: CreateSessionRequest,
+        max_wait: std::time::Duration,
+    ) -> Result<Session> {
+        let deadline = tokio::time::Instant::now() + max_wait;
+        let mut attempt = 0;
+        loop {
+            match self.create_with_options(req.clone()).await {
+                Err(Error::CapacityExceeded {
+                    message,
+                    retry_after,
+                }) => {
+                    let wait = retry_after
+                        .unwrap_or_else(|| std::time::Duration::from_secs(1 << attempt.min(5)));
+                    if tokio::time::Instant::now() + wait > deadline || !self.client.try_retry() {
+                        return Err(Error::CapacityExceeded {
+                            message,
+                            retry_after,
+                        });
+                    }
+                    tracing::debug!("Session capacity exceeded, retrying in {:?}", wait);
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Create a session and wait until it can be read back.
+    ///
+    /// See [`AgentsClient::create_and_confirm`].
+    pub async fn create_and_confirm(
+        &self,
+        req: CreateSessionRequest,
+        max_wait: std::time::Duration,
+    ) -> Result<Session> {
+        let session = self.create_with_options(req).await?;
+        confirm_readable(self.client, &session.id, max_wait, || self.get(&session.id)).await
+    }
+
+    /// Update a session
+    pub async fn update(&self, id: &str, req: UpdateSessionRequest) -> Result<Session> {
+        self.client.patch(&format!("/sessions/{}", id), &req).await
+    }
+
+    /// List all sessions, fetching pages on demand.
+    pub fn list_all(
+        &self,
+        pagination: crate::pagination::PaginationOptions,
+    ) -> crate::pagination::PageStream<Session> {
+        paginate_offset(self.client, |client| client.url("/sessions"), pagination)
+    }
+
+    /// Add tags to a session, keeping existing ones.
+    ///
+    /// Reads the session's tags and writes back the merged list, so it is
+    /// not atomic: when two callers change the same session's tags at once,
+    /// the last write wins and the other caller's change is lost. Coordinate
+    /// concurrent workers yourself, or have a single writer per session.
+    pub async fn add_tags(&self, id: &str, tags: &[&str]) -> Result<Session> {
+        let mut current = self.get(id).await?.tags;
+        for tag in tags {
+            if !current.iter().any(|t| t == tag) {
+                current.push(tag.to_string());
+            }
+        }
+        self.update(id, UpdateSessionRequest::new().tags(current))
+            .await
+    }
+
+    /// Remove tags from a session.
+    ///
+    /// Like [`add_tags`](Self::add_tags), a read followed by a write: a
+    /// concurrent tag change to the same session can be overwritten.
+    pub async fn remove_tags(&self, id: &str, tags: &[&str]) -> Result<Session> {
+        let mut current = self.get(id).await?.tags;
+        current.retain(|t| !tags.contains(&t.as_str()));
+        self.update(id, UpdateSessionRequest::new().tags(current))
+            .await
+    }
+
+    /// Stream all sessions carrying `tag`, paging through the full list.
+    ///
+    /// The sessions endpoint has no tag filter, so pages are filtered
+    /// client-side as they arrive.
+    pub fn list_by_tag(
+        &self,
+        tag: &str,
+        pagination: crate::pagination::PaginationOptions,
+    ) -> crate::pagination::PageStream<Session> {
+        use futures::TryStreamExt;
+
+        let tag = tag.to_string();
+        let sessions = self.list_all(pagination);
+        Box::pin(
+            sessions.try_filter(move |session| std::future::ready(session.tags.contains(&tag))),
+        )
+    }
+
+    /// Stream the subagent sessions spawned from `parent_session_id`.
+    ///
+    /// Matches sessions spawned server-side (`parent_session_id`) and those
+    /// spawned with [`TurnHandle::spawn_subagent`](crate::turn::TurnHandle::spawn_subagent),
+    /// which are linked by tag. Filtered client-side like [`list_by_tag`](Self::list_by_tag).
+    pub fn children(
+        &self,
+        parent_session_id: &str,
+        pagination: crate::pagination::PaginationOptions,
+    ) -> crate::pagination::PageStream<Session> {
+        use futures::TryStreamExt;
+
+        let parent = parent_session_id.to_string();
+        let tag = parent_session_tag(parent_session_id);
+        let sessions = self.list_all(pagination);
+        Box::pin(sessions.try_filter(move |session| {
+            std::future::ready(
+                session.parent_session_id.as_deref() == Some(parent.as_str())
+                    || session.tags.contains(&tag),
+            )
+        }))
+    }
+
     /// Delete a session
     pub async fn delete(&self, id: &str) -> Result<()> {
         self.client.delete(&format!("/sessions/{}", id)).await
@@ -734,6 +1678,20 @@ impl<'a> SessionsClient<'a> {
         Ok(())
     }
 
+    /// List the names of a session's secrets
+    pub async fn list_secrets(&self, id: &str) -> Result<ListResponse<SecretInfo>> {
+        self.client
+            .get(&format!("/sessions/{}/storage/secrets", id))
+            .await
+    }
+
+    /// List a session's key-value storage
+    pub async fn list_storage(&self, id: &str) -> Result<ListResponse<KeyValueInfo>> {
+        self.client
+            .get(&format!("/sessions/{}/storage/keys", id))
+            .await
+    }
+
     /// Export a session's messages as JSONL
     pub async fn export(&self, id: &str) -> Result<String> {
         self.client
@@ -755,9 +1713,27 @@ impl<'a> MessagesClient<'a> {
             .await
     }
 
+    /// List messages with `sequence` greater than `after_sequence`, oldest first.
+    ///
+    /// Lets clients that persist history locally sync only what is new. The
+    /// messages endpoint has no server-side cursor yet, so the filtering
+    /// happens client-side after the list is fetched.
+    pub async fn list_since(&self, session_id: &str, after_sequence: u64) -> Result<Vec<Message>> {
+        let mut messages: Vec<Message> = self
+            .list(session_id)
+            .await?
+            .data
+            .into_iter()
+            .filter(|message| message.sequence > after_sequence)
+            .collect();
+        messages.sort_by_key(|message| message.sequence);
+        Ok(messages)
+    }
+
     /// Create a new message (send text)
     pub async fn create(&self, session_id: &str, text: &str) -> Result<Message> {
-        let req = CreateMessageRequest::user_text(text);
+        let mut req = CreateMessageRequest::user_text(text);
+        self.client.redact(session_id, &mut req.message.content);
         self.client
             .post(&format!("/sessions/{}/messages", session_id), &req)
             .await
@@ -770,8 +1746,9 @@ impl<'a> MessagesClient<'a> {
     pub async fn create_tool_results(
         &self,
         session_id: &str,
-        results: Vec<ContentPart>,
+        mut results: Vec<ContentPart>,
     ) -> Result<SubmitToolResultsResponse> {
+        self.client.redact(session_id, &mut results);
         let tool_results = results
             .into_iter()
             .map(|part| match part {
@@ -805,15 +1782,152 @@ impl<'a> MessagesClient<'a> {
     }
 
     /// Create a message with full options
+    ///
+    /// Controls are validated client-side before the request is sent.
     pub async fn create_with_options(
         &self,
         session_id: &str,
-        req: CreateMessageRequest,
+        mut req: CreateMessageRequest,
     ) -> Result<Message> {
+        req.validate()?;
+        self.client.redact(session_id, &mut req.message.content);
         self.client
             .post(&format!("/sessions/{}/messages", session_id), &req)
             .await
     }
+
+    /// Send a message and track the turn it triggers.
+    ///
+    /// The returned [`TurnHandle`](crate::turn::TurnHandle) follows the
+    /// session's event stream from just before the message was created.
+    #[cfg(feature = "sse")]
+    pub async fn send(
+        &self,
+        session_id: &str,
+        req: CreateMessageRequest,
+    ) -> Result<crate::turn::TurnHandle> {
+        self.send_with_stream_options(session_id, req, crate::sse::StreamOptions::default())
+            .await
+    }
+
+    /// Send a message and track its turn, with custom stream options.
+    ///
+    /// `since_id` in `options` is overridden with the session's latest event
+    /// at send time so that no event of the new turn is missed.
+    #[cfg(feature = "sse")]
+    #[tracing::instrument(name = "everruns.send", skip(self, req, options))]
+    pub async fn send_with_stream_options(
+        &self,
+        session_id: &str,
+        req: CreateMessageRequest,
+        mut options: crate::sse::StreamOptions,
+    ) -> Result<crate::turn::TurnHandle> {
+        let latest = self
+            .client
+            .events()
+            .list_with_options(
+                session_id,
+                &ListEventsOptions {
+                    limit: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        options.since_id = latest.data.last().map(|event| event.id.clone());
+
+        let message = self.create_with_options(session_id, req).await?;
+        let stream = self
+            .client
+            .events()
+            .stream_with_options(session_id, options);
+        Ok(crate::turn::TurnHandle::new(
+            self.client.clone(),
+            stream,
+            session_id.to_string(),
+            message.id,
+        ))
+    }
+
+    /// Send a message and wait for its turn to finish.
+    ///
+    /// Aborting the calling task cancels the turn server-side. To cancel on
+    /// a signal such as ctrl-c, pass it with
+    /// [`RunOptions::cancel_on`](crate::turn::RunOptions::cancel_on).
+    #[cfg(feature = "sse")]
+    pub async fn run(
+        &self,
+        session_id: &str,
+        req: CreateMessageRequest,
+    ) -> Result<crate::turn::TurnOutcome> {
+        self.run_with_options(session_id, req, crate::turn::RunOptions::default())
+            .await
+    }
+
+    /// Like [`run`](Self::run), with automatic continuation of truncated output.
+    ///
+    /// Each continuation is a follow-up user message in the session, sent
+    /// with the original request's controls; the returned outcome merges the
+    /// messages and usage of every turn.
+    #[cfg(feature = "sse")]
+    #[tracing::instrument(name = "everruns.run", skip(self, req, options))]
+    pub async fn run_with_options(
+        &self,
+        session_id: &str,
+        req: CreateMessageRequest,
+        options: crate::turn::RunOptions,
+    ) -> Result<crate::turn::TurnOutcome> {
+        let controls = req.controls.clone();
+        let cancel = options.cancel.clone();
+        let mut outcome = self.run_once(session_id, req, cancel.clone()).await?;
+        for _ in 0..options.max_continuations {
+            if outcome.status != crate::turn::TurnStatus::Completed
+                || outcome.stop_reason != Some(crate::turn::StopReason::MaxTokens)
+            {
+                break;
+            }
+            let mut next = CreateMessageRequest::user_text(options.continue_prompt.as_str());
+            next.controls = controls.clone();
+            outcome.absorb(self.run_once(session_id, next, cancel.clone()).await?);
+        }
+        Ok(outcome)
+    }
+
+    #[cfg(feature = "sse")]
+    async fn run_once(
+        &self,
+        session_id: &str,
+        req: CreateMessageRequest,
+        cancel: Option<crate::turn::CancelSignal>,
+    ) -> Result<crate::turn::TurnOutcome> {
+        let turn = self.send(session_id, req).await?;
+        turn.await_completion_or_cancel(async {
+            match cancel {
+                Some(signal) => signal.await,
+                None => std::future::pending().await,
+            }
+        })
+        .await
+    }
+
+    /// Send many messages with at most `concurrency` requests in flight.
+    ///
+    /// Returns one result per input, in input order. A failure for one item
+    /// does not stop the others. `concurrency` of 0 is treated as 1.
+    pub async fn send_many(
+        &self,
+        requests: Vec<(String, CreateMessageRequest)>,
+        concurrency: usize,
+    ) -> Vec<Result<Message>> {
+        use futures::StreamExt;
+
+        futures::stream::iter(requests)
+            .map(
+                |(session_id, req)| async move { self.create_with_options(&session_id, req).await },
+            )
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
 }
 
 fn is_tool_results_pending_conflict(error: &Error) -> bool {
@@ -883,6 +1997,26 @@ impl<'a> EventsClient<'a> {
         session_id: &str,
         options: &ListEventsOptions,
     ) -> Result<ListResponse<Event>> {
+        self.client
+            .get_url(self.list_url(session_id, options))
+            .await
+    }
+
+    /// List events with options, parsing the response body incrementally.
+    ///
+    /// Events are deserialized one at a time as bytes arrive, so memory use
+    /// stays flat even for very large responses. Prefer this over
+    /// [`list_with_options`](Self::list_with_options) for long sessions.
+    pub fn list_stream(
+        &self,
+        session_id: &str,
+        options: &ListEventsOptions,
+    ) -> crate::pagination::PageStream<Event> {
+        self.client
+            .get_list_stream(self.list_url(session_id, options))
+    }
+
+    fn list_url(&self, session_id: &str, options: &ListEventsOptions) -> Url {
         let mut url = self.client.url(&format!("/sessions/{}/events", session_id));
         if let Some(since_id) = &options.since_id {
             url.query_pairs_mut().append_pair("since_id", since_id);
@@ -940,10 +2074,62 @@ impl<'a> EventsClient<'a> {
             url.query_pairs_mut()
                 .append_pair("order_desc", &order_desc.to_string());
         }
-        self.client.get_url(url).await
+        url
+    }
+
+    /// List all events matching `options`, fetching pages on demand.
+    ///
+    /// Pages are requested backward from the newest event using
+    /// `before_sequence`, so events are yielded newest first. The next page
+    /// is prefetched while the current one is consumed (see
+    /// [`PaginationOptions::lookahead`](crate::pagination::PaginationOptions)).
+    /// Paging continues until a page is empty, since the server may return
+    /// short pages to avoid splitting a turn. `limit` and `before_sequence`
+    /// in `options` are managed by the paginator.
+    pub fn list_all(
+        &self,
+        session_id: &str,
+        options: &ListEventsOptions,
+        pagination: crate::pagination::PaginationOptions,
+    ) -> crate::pagination::PageStream<Event> {
+        let client = self.client.clone();
+        let session_id = session_id.to_string();
+        let options = options.clone();
+        let page_size = pagination.page_size.max(1);
+        crate::pagination::paginate(
+            options.before_sequence,
+            pagination.lookahead,
+            move |before_sequence| {
+                let client = client.clone();
+                let session_id = session_id.clone();
+                let options = ListEventsOptions {
+                    limit: Some(page_size),
+                    before_sequence,
+                    ..options.clone()
+                };
+                async move {
+                    let mut page = client
+                        .events()
+                        .list_with_options(&session_id, &options)
+                        .await?
+                        .data;
+                    page.sort_by_key(|event| std::cmp::Reverse(event.sequence));
+                    // Pages snap to turn boundaries, so a short page is not
+                    // the last one; stop once the oldest sequence stops falling.
+                    let oldest = page.last().and_then(|event| event.sequence);
+                    let next = match (oldest, before_sequence) {
+                        (Some(seq), Some(before)) if seq >= before => None,
+                        (Some(seq), _) => Some(Some(seq)),
+                        (None, _) => None,
+                    };
+                    Ok((page, next))
+                }
+            },
+        )
     }
 
     /// Stream events from a session via SSE
+    #[cfg(feature = "sse")]
     pub fn stream(&self, session_id: &str) -> crate::sse::EventStream {
         crate::sse::EventStream::new(
             self.client.clone(),
@@ -953,12 +2139,101 @@ impl<'a> EventsClient<'a> {
     }
 
     /// Stream events with options
+    #[cfg(feature = "sse")]
     pub fn stream_with_options(
         &self,
         session_id: &str,
         options: crate::sse::StreamOptions,
     ) -> crate::sse::EventStream {
-        crate::sse::EventStream::new(self.client.clone(), session_id.to_string(), options)
+        crate::sse::EventStream::new(self.client.clone(), session_id.to_string(), options)
+    }
+
+    /// Replay a session's history, then follow it live.
+    ///
+    /// Past events are paged in order from the list endpoint, then the
+    /// stream switches to SSE from the last replayed event, so nothing is
+    /// missed or delivered twice across the switch.
+    #[cfg(feature = "sse")]
+    pub fn stream_from_start(&self, session_id: &str) -> crate::pagination::PageStream<Event> {
+        self.stream_from_start_with_options(session_id, crate::sse::StreamOptions::default())
+    }
+
+    /// Replay then follow with options.
+    ///
+    /// `types`, `exclude` and `turn_id` apply to the replay as well;
+    /// `since_id` sets where the replay starts.
+    #[cfg(feature = "sse")]
+    pub fn stream_from_start_with_options(
+        &self,
+        session_id: &str,
+        options: crate::sse::StreamOptions,
+    ) -> crate::pagination::PageStream<Event> {
+        let client = self.client.clone();
+        let session_id = session_id.to_string();
+        Box::pin(async_stream::try_stream! {
+            use futures::StreamExt;
+
+            let tracker = options
+                .backfill_progress
+                .clone()
+                .map(|progress| Tracker::items(progress, None));
+            let mut since_id = options.since_id.clone();
+            loop {
+                let list_options = ListEventsOptions {
+                    since_id: since_id.clone(),
+                    types: options.types.clone(),
+                    exclude: options.exclude.clone(),
+                    turn_id: options.turn_id.clone(),
+                    ..Default::default()
+                };
+                let page = client
+                    .events()
+                    .list_with_options(&session_id, &list_options)
+                    .await?
+                    .data;
+                let Some(last) = page.last() else { break };
+                if since_id.as_deref() == Some(last.id.as_str()) {
+                    break;
+                }
+                since_id = Some(last.id.clone());
+                if let Some(tracker) = &tracker {
+                    tracker.advance(page.len() as u64);
+                }
+                for event in page {
+                    yield event;
+                }
+            }
+
+            let mut live = client.events().stream_with_options(
+                &session_id,
+                crate::sse::StreamOptions { since_id, ..options },
+            );
+            while let Some(event) = live.next().await {
+                match event {
+                    Err(Error::HeartbeatMissed { .. }) => continue,
+                    event => yield event?,
+                }
+            }
+        })
+    }
+
+    /// Resume streaming after the event recorded in `cursor`
+    #[cfg(feature = "sse")]
+    pub fn resume(&self, cursor: &crate::sse::StreamCursor) -> crate::sse::EventStream {
+        self.resume_with_options(cursor, crate::sse::StreamOptions::default())
+    }
+
+    /// Resume streaming with options; `cursor` overrides `options.since_id`.
+    #[cfg(feature = "sse")]
+    pub fn resume_with_options(
+        &self,
+        cursor: &crate::sse::StreamCursor,
+        mut options: crate::sse::StreamOptions,
+    ) -> crate::sse::EventStream {
+        if let Some(last_event_id) = &cursor.last_event_id {
+            options.since_id = Some(last_event_id.clone());
+        }
+        crate::sse::EventStream::new(self.client.clone(), cursor.session_id.clone(), options)
     }
 }
 
@@ -1569,6 +2844,660 @@ impl<'a> ConnectionsClient<'a> {
     }
 }
 
+/// Client for bulk deletion of sessions and agents, e.g. CI teardown
+pub struct JanitorClient<'a> {
+    client: &'a Everruns,
+}
+
+/// Options for [`JanitorClient`] bulk deletes.
+#[derive(Debug, Clone)]
+pub struct CleanupOptions {
+    /// Deletes in flight at once (0 is treated as 1).
+    pub concurrency: usize,
+    /// Upper bound on delete requests started per second.
+    pub max_qps: Option<f64>,
+    /// Retries for a delete rejected with 429, waiting `Retry-After` or an
+    /// exponential backoff.
+    pub max_retries: u32,
+    /// Receives the count of IDs processed, whatever their outcome.
+    pub progress: Option<SharedProgress>,
+}
+
+impl Default for CleanupOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            max_qps: None,
+            max_retries: 3,
+            progress: None,
+        }
+    }
+}
+
+/// Outcome of a bulk delete
+#[derive(Debug, Default)]
+pub struct CleanupReport {
+    pub deleted: Vec<String>,
+    /// IDs that no longer existed
+    pub not_found: Vec<String>,
+    pub failed: Vec<(String, Error)>,
+}
+
+impl CleanupReport {
+    /// Whether every ID was deleted or already gone
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+impl<'a> JanitorClient<'a> {
+    /// Delete sessions concurrently
+    pub async fn delete_sessions(&self, ids: &[String], options: &CleanupOptions) -> CleanupReport {
+        self.delete_all(ids, options, |id| async move {
+            self.client.sessions().delete(&id).await
+        })
+        .await
+    }
+
+    /// Delete (archive) agents concurrently
+    pub async fn delete_agents(&self, ids: &[String], options: &CleanupOptions) -> CleanupReport {
+        self.delete_all(ids, options, |id| async move {
+            self.client.agents().delete(&id).await
+        })
+        .await
+    }
+
+    /// Delete every session for which `filter` returns true.
+    ///
+    /// All sessions are listed before the first delete, so removing items
+    /// cannot shift later list pages and leave matching sessions behind.
+    /// Returns an error only if listing fails.
+    pub async fn delete_sessions_where(
+        &self,
+        filter: impl Fn(&Session) -> bool,
+        options: &CleanupOptions,
+    ) -> Result<CleanupReport> {
+        let sessions = self.client.sessions().list_all(Default::default());
+        let ids = snapshot_ids(sessions, |session| filter(&session).then_some(session.id)).await?;
+        Ok(self.delete_sessions(&ids, options).await)
+    }
+
+    /// Delete (archive) every active agent for which `filter` returns true.
+    ///
+    /// Like [`delete_sessions_where`](Self::delete_sessions_where), IDs are
+    /// snapshotted before any agent is deleted.
+    pub async fn delete_agents_where(
+        &self,
+        filter: impl Fn(&Agent) -> bool,
+        options: &CleanupOptions,
+    ) -> Result<CleanupReport> {
+        let agents = self.client.agents().list_all(Default::default());
+        let ids = snapshot_ids(agents, |agent| filter(&agent).then_some(agent.id)).await?;
+        Ok(self.delete_agents(&ids, options).await)
+    }
+
+    async fn delete_all<F, Fut>(
+        &self,
+        ids: &[String],
+        options: &CleanupOptions,
+        delete: F,
+    ) -> CleanupReport
+    where
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        use futures::StreamExt;
+
+        let interval = options
+            .max_qps
+            .filter(|qps| *qps > 0.0)
+            .map(|qps| std::time::Duration::from_secs_f64(1.0 / qps));
+        let next_slot = tokio::sync::Mutex::new(tokio::time::Instant::now());
+        let pace = || async {
+            let Some(interval) = interval else { return };
+            let at = {
+                let mut next = next_slot.lock().await;
+                let at = (*next).max(tokio::time::Instant::now());
+                *next = at + interval;
+                at
+            };
+            tokio::time::sleep_until(at).await;
+        };
+
+        let tracker = options
+            .progress
+            .clone()
+            .map(|progress| Tracker::items(progress, Some(ids.len() as u64)));
+        if let Some(tracker) = &tracker {
+            tracker.start();
+        }
+
+        let results: Vec<(String, Result<()>)> = futures::stream::iter(ids.iter().cloned())
+            .map(|id| {
+                let delete = &delete;
+                let pace = &pace;
+                let tracker = &tracker;
+                async move {
+                    let mut attempt = 0;
+                    let result = loop {
+                        pace().await;
+                        match delete(id.clone()).await {
+                            Err(Error::RateLimited { retry_after, .. })
+                                if attempt < options.max_retries && self.client.try_retry() =>
+                            {
+                                let backoff = retry_after.unwrap_or_else(|| {
+                                    std::time::Duration::from_millis(250 << attempt)
+                                });
+                                tokio::time::sleep(backoff).await;
+                                attempt += 1;
+                            }
+                            result => break result,
+                        }
+                    };
+                    if let Some(tracker) = tracker {
+                        tracker.advance(1);
+                    }
+                    (id, result)
+                }
+            })
+            .buffer_unordered(options.concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut report = CleanupReport::default();
+        for (id, result) in results {
+            match result {
+                Ok(()) => report.deleted.push(id),
+                Err(Error::Api { status: 404, .. }) => report.not_found.push(id),
+                Err(err) => report.failed.push((id, err)),
+            }
+        }
+        report
+    }
+}
+
+/// Drain a list stream into the IDs `select` keeps.
+async fn snapshot_ids<T>(
+    items: crate::pagination::PageStream<T>,
+    select: impl Fn(T) -> Option<String>,
+) -> Result<Vec<String>> {
+    use futures::TryStreamExt;
+
+    items
+        .try_filter_map(|item| futures::future::ready(Ok(select(item))))
+        .try_collect()
+        .await
+}
+
+/// Client for organization member operations
+pub struct UsersClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> UsersClient<'a> {
+    /// List users in the current organization
+    pub async fn list(&self) -> Result<ListResponse<User>> {
+        self.client.get("/users").await
+    }
+
+    /// List users whose name or email matches `query`
+    pub async fn search(&self, query: &str) -> Result<ListResponse<User>> {
+        let mut url = self.client.url("/users");
+        url.query_pairs_mut().append_pair("search", query);
+        self.client.get_url(url).await
+    }
+}
+
+/// Client for realtime voice connections
+///
+/// Audio flows directly between the caller and the realtime provider over
+/// WebRTC; these calls negotiate, attach and end the connection. Transcripts
+/// arrive on the session event stream as `voice.*` events.
+pub struct VoiceClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> VoiceClient<'a> {
+    /// Start a voice call on a session from a WebRTC SDP offer
+    pub async fn call(&self, session_id: &str, req: VoiceCallRequest) -> Result<VoiceCall> {
+        self.client
+            .post(&format!("/sessions/{}/voice/calls", session_id), &req)
+            .await
+    }
+
+    /// Create a session for an agent and start a voice call on it
+    pub async fn call_agent(&self, agent_id: &str, req: VoiceCallRequest) -> Result<VoiceSession> {
+        self.client
+            .post(&format!("/agents/{}/voice/sessions", agent_id), &req)
+            .await
+    }
+
+    /// Start a voice call on the user's global chat session
+    pub async fn call_chat(&self, req: VoiceCallRequest) -> Result<VoiceSession> {
+        self.client.post("/sessions/chat/voice", &req).await
+    }
+
+    /// Mint an ephemeral credential for connecting to the realtime provider directly
+    pub async fn client_secret(
+        &self,
+        session_id: &str,
+        options: VoiceSessionOptions,
+    ) -> Result<VoiceClientSecret> {
+        self.client
+            .post(
+                &format!("/sessions/{}/voice/client-secret", session_id),
+                &options,
+            )
+            .await
+    }
+
+    /// Attach an externally established provider call to a voice connection
+    pub async fn attach(
+        &self,
+        session_id: &str,
+        voice_connection_id: &str,
+        req: VoiceAttachRequest,
+    ) -> Result<VoiceAttachment> {
+        self.client
+            .post(
+                &format!(
+                    "/sessions/{}/voice/{}/attach",
+                    session_id, voice_connection_id
+                ),
+                &req,
+            )
+            .await
+    }
+
+    /// End an in-flight voice connection
+    pub async fn end(
+        &self,
+        session_id: &str,
+        voice_connection_id: &str,
+        req: VoiceEndRequest,
+    ) -> Result<VoiceEndResponse> {
+        self.client
+            .post(
+                &format!("/sessions/{}/voice/{}/end", session_id, voice_connection_id),
+                &req,
+            )
+            .await
+    }
+}
+
+/// Client for knowledge base operations
+pub struct KnowledgeBasesClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> KnowledgeBasesClient<'a> {
+    /// List knowledge bases.
+    pub async fn list(&self) -> Result<ListResponse<KnowledgeBase>> {
+        self.client.get("/knowledge-bases").await
+    }
+
+    /// List knowledge bases with search and archived filtering.
+    pub async fn list_with_options(
+        &self,
+        search: Option<&str>,
+        include_archived: Option<bool>,
+    ) -> Result<ListResponse<KnowledgeBase>> {
+        let mut url = self.client.url("/knowledge-bases");
+        if let Some(search) = search {
+            url.query_pairs_mut().append_pair("search", search);
+        }
+        if let Some(include_archived) = include_archived {
+            url.query_pairs_mut()
+                .append_pair("include_archived", &include_archived.to_string());
+        }
+        self.client.get_url(url).await
+    }
+
+    /// Create a knowledge base.
+    pub async fn create(&self, req: CreateKnowledgeBaseRequest) -> Result<KnowledgeBase> {
+        self.client.post("/knowledge-bases", &req).await
+    }
+
+    /// Get a knowledge base by ID.
+    pub async fn get(&self, kb_id: &str) -> Result<KnowledgeBase> {
+        self.client
+            .get(&format!("/knowledge-bases/{}", kb_id))
+            .await
+    }
+
+    /// Update a knowledge base.
+    pub async fn update(
+        &self,
+        kb_id: &str,
+        req: UpdateKnowledgeBaseRequest,
+    ) -> Result<KnowledgeBase> {
+        self.client
+            .patch(&format!("/knowledge-bases/{}", kb_id), &req)
+            .await
+    }
+
+    /// Delete a knowledge base.
+    pub async fn delete(&self, kb_id: &str) -> Result<()> {
+        self.client
+            .delete(&format!("/knowledge-bases/{}", kb_id))
+            .await
+    }
+
+    /// List entries in a knowledge base, optionally filtered by text and kind.
+    pub async fn list_entries(
+        &self,
+        kb_id: &str,
+        search: Option<&str>,
+        kind: Option<&str>,
+    ) -> Result<ListResponse<KnowledgeEntry>> {
+        let mut url = self
+            .client
+            .url(&format!("/knowledge-bases/{}/entries", kb_id));
+        if let Some(search) = search {
+            url.query_pairs_mut().append_pair("search", search);
+        }
+        if let Some(kind) = kind {
+            url.query_pairs_mut().append_pair("kind", kind);
+        }
+        self.client.get_url(url).await
+    }
+
+    /// Create an entry in a knowledge base.
+    pub async fn create_entry(
+        &self,
+        kb_id: &str,
+        req: CreateKnowledgeEntryRequest,
+    ) -> Result<KnowledgeEntry> {
+        self.client
+            .post(&format!("/knowledge-bases/{}/entries", kb_id), &req)
+            .await
+    }
+
+    /// Get a knowledge base entry by ID.
+    pub async fn get_entry(&self, kb_id: &str, entry_id: &str) -> Result<KnowledgeEntry> {
+        self.client
+            .get(&format!("/knowledge-bases/{}/entries/{}", kb_id, entry_id))
+            .await
+    }
+
+    /// Update a knowledge base entry.
+    pub async fn update_entry(
+        &self,
+        kb_id: &str,
+        entry_id: &str,
+        req: UpdateKnowledgeEntryRequest,
+    ) -> Result<KnowledgeEntry> {
+        self.client
+            .patch(
+                &format!("/knowledge-bases/{}/entries/{}", kb_id, entry_id),
+                &req,
+            )
+            .await
+    }
+
+    /// Delete a knowledge base entry.
+    pub async fn delete_entry(&self, kb_id: &str, entry_id: &str) -> Result<()> {
+        self.client
+            .delete(&format!("/knowledge-bases/{}/entries/{}", kb_id, entry_id))
+            .await
+    }
+}
+
+/// Client for image operations
+pub struct ImagesClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> ImagesClient<'a> {
+    /// Upload image bytes, optionally tagging the upload with a session ID
+    pub async fn upload(
+        &self,
+        filename: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+        session_id: Option<&str>,
+    ) -> Result<ImageUploadResponse> {
+        self.upload_part(
+            filename,
+            content_type,
+            reqwest::multipart::Part::bytes(bytes),
+            session_id,
+        )
+        .await
+    }
+
+    /// Like [`upload`](Self::upload), reporting bytes as they are sent
+    pub async fn upload_with_progress(
+        &self,
+        filename: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+        session_id: Option<&str>,
+        progress: impl Progress + 'static,
+    ) -> Result<ImageUploadResponse> {
+        use futures::StreamExt;
+
+        let len = bytes.len() as u64;
+        let tracker = Tracker::bytes(SharedProgress::new(progress), Some(len));
+        tracker.start();
+        let chunks: Vec<std::io::Result<Vec<u8>>> = bytes
+            .chunks(UPLOAD_CHUNK_BYTES)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        let body = futures::stream::iter(chunks).inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                tracker.advance(chunk.len() as u64);
+            }
+        });
+        let part =
+            reqwest::multipart::Part::stream_with_length(reqwest::Body::wrap_stream(body), len);
+        self.upload_part(filename, content_type, part, session_id)
+            .await
+    }
+
+    async fn upload_part(
+        &self,
+        filename: &str,
+        content_type: &str,
+        part: reqwest::multipart::Part,
+        session_id: Option<&str>,
+    ) -> Result<ImageUploadResponse> {
+        let part = part
+            .file_name(filename.to_string())
+            .mime_str(content_type)
+            .map_err(|err| Error::Validation(format!("invalid content type: {err}")))?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+        let mut url = self.client.url("/images");
+        if let Some(session_id) = session_id {
+            url.query_pairs_mut().append_pair("session_id", session_id);
+        }
+        self.client.post_multipart_url(url, form).await
+    }
+
+    /// Upload an image file from disk, inferring its content type from the extension
+    pub async fn upload_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        session_id: Option<&str>,
+    ) -> Result<ImageUploadResponse> {
+        let path = path.as_ref();
+        let content_type = image_content_type(path)?;
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| Error::Validation(format!("invalid file path: {}", path.display())))?;
+        let bytes = tokio::fs::read(path).await.map_err(|err| {
+            Error::Validation(format!("failed to read {}: {err}", path.display()))
+        })?;
+        self.upload(filename, content_type, bytes, session_id).await
+    }
+
+    /// Upload a file and attach it to a message as an `image_file` content part.
+    ///
+    /// Uploads are processed synchronously by the server, so the returned
+    /// request can be sent immediately.
+    pub async fn attach_file(
+        &self,
+        req: CreateMessageRequest,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<CreateMessageRequest> {
+        let image = self.upload_file(path, None).await?;
+        Ok(req.content_part(ContentPart::ImageFile {
+            image_id: image.id,
+            filename: Some(image.filename),
+        }))
+    }
+
+    /// List uploaded images
+    pub async fn list(&self, limit: Option<i64>, offset: Option<i64>) -> Result<Vec<ImageInfo>> {
+        let mut url = self.client.url("/images");
+        if let Some(l) = limit {
+            url.query_pairs_mut().append_pair("limit", &l.to_string());
+        }
+        if let Some(o) = offset {
+            url.query_pairs_mut().append_pair("offset", &o.to_string());
+        }
+        self.client.get_url(url).await
+    }
+
+    /// Download an image's binary data
+    pub async fn get(&self, id: &str) -> Result<Vec<u8>> {
+        self.client.get_bytes(&format!("/images/{}", id)).await
+    }
+
+    /// Like [`get`](Self::get), reporting bytes as they arrive
+    pub async fn get_with_progress(
+        &self,
+        id: &str,
+        progress: impl Progress + 'static,
+    ) -> Result<Vec<u8>> {
+        self.client
+            .get_bytes_with_progress(
+                &format!("/images/{}", id),
+                Some(SharedProgress::new(progress)),
+            )
+            .await
+    }
+
+    /// Download an image's thumbnail
+    pub async fn thumbnail(&self, id: &str) -> Result<Vec<u8>> {
+        self.client
+            .get_bytes(&format!("/images/{}/thumbnail", id))
+            .await
+    }
+
+    /// Delete an image
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.client.delete(&format!("/images/{}", id)).await
+    }
+}
+
+/// Client for model catalog operations
+pub struct ModelsClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> ModelsClient<'a> {
+    /// List models across all providers
+    pub async fn list(&self) -> Result<ListResponse<ModelWithProvider>> {
+        self.client.get("/models").await
+    }
+
+    /// Get a model by ID
+    pub async fn get(&self, id: &str) -> Result<ModelWithProvider> {
+        self.client.get(&format!("/models/{}", id)).await
+    }
+
+    /// Resolve a model ID or alias to the ID of a concrete, enabled model.
+    ///
+    /// Exact matches on the model ID or the provider-side model name win.
+    /// A `<family>-latest` alias (e.g. [`ModelId::CLAUDE_SONNET_LATEST`])
+    /// picks the enabled model of that family with the newest release date.
+    /// Its provider-side name must be the family, optional numeric version
+    /// segments, and a `YYYYMMDD` or `YYYY-MM-DD` date, such as
+    /// `claude-sonnet-4-5-20250929`. Sibling families like `gpt-4o-mini`
+    /// don't count as `gpt-4o`.
+    pub async fn resolve(&self, model: impl Into<ModelId>) -> Result<ModelId> {
+        let model = model.into();
+        let models = self.list().await?.data;
+        resolve_model(&model, &models)
+    }
+}
+
+/// Client for token counting, see [`crate::tokens`]
+pub struct TokensClient<'a> {
+    client: &'a Everruns,
+}
+
+impl<'a> TokensClient<'a> {
+    /// Count the tokens `content` uses with a model, along with the model's
+    /// limits.
+    ///
+    /// `model` is resolved like [`ModelsClient::resolve`]; its provider-side
+    /// name picks the tokenizer.
+    pub async fn count(
+        &self,
+        model: impl Into<ModelId>,
+        content: &str,
+    ) -> Result<crate::tokens::TokenCount> {
+        let models = self.client.models().list().await?.data;
+        let model = find_model(&model.into(), &models)?;
+        let mut count = crate::tokens::TokenCount::of(&model.model_id, content);
+        if let Some(limits) = model.profile.as_ref().and_then(|p| p.limits) {
+            count.context_window = Some(limits.context.into());
+            count.max_input = Some(limits.max_input().into());
+        }
+        Ok(count)
+    }
+}
+
+fn resolve_model(model: &ModelId, models: &[ModelWithProvider]) -> Result<ModelId> {
+    find_model(model, models).map(|found| ModelId::new(found.id.clone()))
+}
+
+fn find_model<'m>(
+    model: &ModelId,
+    models: &'m [ModelWithProvider],
+) -> Result<&'m ModelWithProvider> {
+    let enabled = || models.iter().filter(|m| m.enabled);
+    let wanted = model.as_str();
+    if let Some(found) = enabled().find(|m| m.id == wanted || m.model_id == wanted) {
+        return Ok(found);
+    }
+    model
+        .alias_family()
+        .and_then(|family| {
+            enabled()
+                .filter_map(|m| Some((family_release(family, &m.model_id)?, m)))
+                .max_by(|a, b| a.0.cmp(&b.0))
+                .map(|(_, m)| m)
+        })
+        .ok_or_else(|| Error::Validation(format!("no enabled model matches '{model}'")))
+}
+
+/// Release date and version of `model_id` if it is a dated release of
+/// `family`: `<family>[-<n>...]-<YYYYMMDD | YYYY-MM-DD>`.
+fn family_release(family: &str, model_id: &str) -> Option<(u32, Vec<u32>)> {
+    let rest = model_id.strip_prefix(family)?.strip_prefix('-')?;
+    let parts: Vec<&str> = rest.split('-').collect();
+    let digits =
+        |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    let (date, version) = match parts.as_slice() {
+        [version @ .., year, month, day]
+            if digits(year, 4) && digits(month, 2) && digits(day, 2) =>
+        {
+            (format!("{year}{month}{day}"), version)
+        }
+        [version @ .., date] if digits(date, 8) => (date.to_string(), version),
+        _ => return None,
+    };
+    let version = version
+        .iter()
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    Some((date.parse().ok()?, version))
+}
+
 impl std::fmt::Debug for Everruns {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Everruns")
@@ -1590,6 +3519,38 @@ mod tests {
         Everruns::with_base_url("test_key", "https://api.example.com").unwrap()
     }
 
+    #[test]
+    fn test_url_appends_segments_to_versioned_base() {
+        let client = Everruns::with_base_url("test_key", "https://api.example.com/api").unwrap();
+        assert_eq!(
+            client.url("/agents/agent_123").as_str(),
+            "https://api.example.com/api/v1/agents/agent_123"
+        );
+        assert_eq!(
+            client.url("sessions").as_str(),
+            "https://api.example.com/api/v1/sessions"
+        );
+    }
+
+    #[test]
+    fn test_url_encodes_reserved_characters_in_segments() {
+        let client = test_client();
+        let url = client.url("/workspaces/ws_1/fs/docs/my file#1?.md");
+        assert_eq!(
+            url.as_str(),
+            "https://api.example.com/v1/workspaces/ws_1/fs/docs/my%20file%231%3F.md"
+        );
+        assert!(url.query().is_none());
+        assert!(url.fragment().is_none());
+    }
+
+    #[test]
+    fn test_cannot_be_a_base_url_rejected() {
+        let result = Everruns::with_base_url("test_key", "mailto:api@example.com");
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_no_params() {
         let client = test_client();
@@ -1611,6 +3572,7 @@ mod tests {
         assert_eq!(headers["X-Org-Id"], "org_123");
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_with_since_id() {
         let client = test_client();
@@ -1621,6 +3583,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_exclude_expands_as_repeated_keys() {
         let client = test_client();
@@ -1654,6 +3617,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_single_exclude() {
         let client = test_client();
@@ -1664,6 +3628,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_combined_since_id_and_exclude() {
         let client = test_client();
@@ -1679,6 +3644,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_three_exclude_values() {
         let client = test_client();
@@ -1696,6 +3662,7 @@ mod tests {
         assert_eq!(url_str.matches("exclude=").count(), 3);
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_since_id_special_chars_encoded() {
         let client = test_client();
@@ -1706,6 +3673,7 @@ mod tests {
         assert!(url_str.contains("since_id=evt%26id%3D1"));
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_with_types() {
         let client = test_client();
@@ -1721,6 +3689,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sse")]
     #[test]
     fn test_sse_url_with_types_and_exclude() {
         let client = test_client();
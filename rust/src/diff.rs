@@ -0,0 +1,290 @@
+//! Structured diffs of agent configuration.
+//!
+//! [`AgentsClient::update_dry_run`](crate::client::AgentsClient::update_dry_run)
+//! and [`Everruns::plan`](crate::Everruns::plan) report what an update
+//! would change as an [`AgentDiff`], for change-review workflows. Its
+//! `Display` output is a readable summary with a line diff of the system
+//! prompt.
+
+use crate::models::{Agent, UpdateAgentRequest};
+use std::collections::HashMap;
+
+/// One line of a [`TextDiff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Unchanged(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Line-by-line diff of two texts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TextDiff {
+    pub lines: Vec<DiffLine>,
+}
+
+impl TextDiff {
+    /// Diff `old` against `new` line by line
+    pub fn new(old: &str, new: &str) -> Self {
+        let lines = similar::TextDiff::from_lines(old, new)
+            .iter_all_changes()
+            .map(|change| {
+                let line = change.value().trim_end_matches('\n').to_string();
+                match change.tag() {
+                    similar::ChangeTag::Equal => DiffLine::Unchanged(line),
+                    similar::ChangeTag::Insert => DiffLine::Added(line),
+                    similar::ChangeTag::Delete => DiffLine::Removed(line),
+                }
+            })
+            .collect();
+        Self { lines }
+    }
+}
+
+impl std::fmt::Display for TextDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line {
+                DiffLine::Unchanged(text) => writeln!(f, "  {text}")?,
+                DiffLine::Added(text) => writeln!(f, "+ {text}")?,
+                DiffLine::Removed(text) => writeln!(f, "- {text}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Change to a single-valued field
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// What an update would change on an agent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AgentDiff {
+    /// Line diff of the system prompt, when it changes
+    pub system_prompt: Option<TextDiff>,
+    /// Changed name, display name, description or default model
+    pub fields: Vec<FieldChange>,
+    pub tags_added: Vec<String>,
+    pub tags_removed: Vec<String>,
+    /// Capability refs enabled by the update
+    pub capabilities_added: Vec<String>,
+    /// Capability refs disabled by the update
+    pub capabilities_removed: Vec<String>,
+    /// Capability refs kept with a different config
+    pub capabilities_reconfigured: Vec<String>,
+    /// Paths of starter files added, removed or modified
+    pub initial_files_changed: Vec<String>,
+}
+
+impl AgentDiff {
+    /// Diff `existing` against the fields `update` sets; unset fields are
+    /// unchanged by an update and never differ.
+    pub fn new(existing: &Agent, update: &UpdateAgentRequest) -> Self {
+        let mut diff = Self::default();
+        if let Some(prompt) = &update.system_prompt
+            && *prompt != existing.system_prompt
+        {
+            diff.system_prompt = Some(TextDiff::new(&existing.system_prompt, prompt));
+        }
+
+        let scalars = [
+            ("name", Some(&existing.name), update.name.as_ref()),
+            (
+                "display_name",
+                existing.display_name.as_ref(),
+                update.display_name.as_ref(),
+            ),
+            (
+                "description",
+                existing.description.as_ref(),
+                update.description.as_ref(),
+            ),
+            (
+                "default_model_id",
+                existing.default_model_id.as_ref(),
+                update.default_model_id.as_ref(),
+            ),
+        ];
+        for (field, old, new) in scalars {
+            if new.is_some() && old != new {
+                diff.fields.push(FieldChange {
+                    field,
+                    old: old.cloned(),
+                    new: new.cloned(),
+                });
+            }
+        }
+
+        if let Some(tags) = &update.tags {
+            diff.tags_added = added(tags, &existing.tags);
+            diff.tags_removed = added(&existing.tags, tags);
+        }
+
+        if let Some(capabilities) = &update.capabilities {
+            let old = configs(
+                existing
+                    .capabilities
+                    .iter()
+                    .map(|c| (&c.capability_ref, &c.config)),
+            );
+            let new = configs(capabilities.iter().map(|c| (&c.capability_ref, &c.config)));
+            diff.capabilities_added = keys_missing_from(&new, &old);
+            diff.capabilities_removed = keys_missing_from(&old, &new);
+            diff.capabilities_reconfigured = changed_values(&old, &new);
+        }
+
+        if let Some(files) = &update.initial_files {
+            let old = configs(existing.initial_files.iter().map(|f| (&f.path, f)));
+            let new = configs(files.iter().map(|f| (&f.path, f)));
+            let mut paths = keys_missing_from(&new, &old);
+            paths.extend(keys_missing_from(&old, &new));
+            paths.extend(changed_values(&old, &new));
+            paths.sort();
+            diff.initial_files_changed = paths;
+        }
+        diff
+    }
+
+    /// Whether the update would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields().is_empty()
+    }
+
+    /// Names of the agent fields that change, e.g. `system_prompt`
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.system_prompt.is_some() {
+            fields.push("system_prompt");
+        }
+        fields.extend(self.fields.iter().map(|change| change.field));
+        if !self.tags_added.is_empty() || !self.tags_removed.is_empty() {
+            fields.push("tags");
+        }
+        if !self.capabilities_added.is_empty()
+            || !self.capabilities_removed.is_empty()
+            || !self.capabilities_reconfigured.is_empty()
+        {
+            fields.push("capabilities");
+        }
+        if !self.initial_files_changed.is_empty() {
+            fields.push("initial_files");
+        }
+        fields
+    }
+}
+
+impl std::fmt::Display for AgentDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.fields {
+            let old = change.old.as_deref().unwrap_or("(none)");
+            let new = change.new.as_deref().unwrap_or("(none)");
+            writeln!(f, "{}: {old} -> {new}", change.field)?;
+        }
+        let lists = [
+            ("tags added", &self.tags_added),
+            ("tags removed", &self.tags_removed),
+            ("capabilities added", &self.capabilities_added),
+            ("capabilities removed", &self.capabilities_removed),
+            ("capabilities reconfigured", &self.capabilities_reconfigured),
+            ("initial files changed", &self.initial_files_changed),
+        ];
+        for (label, items) in lists {
+            if !items.is_empty() {
+                writeln!(f, "{label}: {}", items.join(", "))?;
+            }
+        }
+        if let Some(prompt) = &self.system_prompt {
+            writeln!(f, "system_prompt:")?;
+            write!(f, "{prompt}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Items of `a` missing from `b`, in `a`'s order.
+fn added(a: &[String], b: &[String]) -> Vec<String> {
+    a.iter().filter(|item| !b.contains(item)).cloned().collect()
+}
+
+/// Key to serialized value, for comparing items without `PartialEq`.
+fn configs<'a, T: serde::Serialize + 'a>(
+    items: impl Iterator<Item = (&'a String, T)>,
+) -> HashMap<String, serde_json::Value> {
+    items
+        .map(|(key, value)| {
+            let value = serde_json::to_value(value).unwrap_or_default();
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn keys_missing_from(
+    a: &HashMap<String, serde_json::Value>,
+    b: &HashMap<String, serde_json::Value>,
+) -> Vec<String> {
+    let mut keys: Vec<String> = a.keys().filter(|k| !b.contains_key(*k)).cloned().collect();
+    keys.sort();
+    keys
+}
+
+fn changed_values(
+    a: &HashMap<String, serde_json::Value>,
+    b: &HashMap<String, serde_json::Value>,
+) -> Vec<String> {
+    let mut keys: Vec<String> = a
+        .iter()
+        .filter(|(k, v)| b.get(*k).is_some_and(|other| other != *v))
+        .map(|(k, _)| k.clone())
+        .collect();
+    keys.sort();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AgentCapabilityConfig;
+
+    #[test]
+    fn diff_reports_prompt_lines_and_capability_changes() {
+        let existing: Agent = serde_json::from_value(serde_json::json!({
+            "id": "agent_1",
+            "name": "support",
+            "system_prompt": "Be kind.\nBe brief.",
+            "tags": ["prod"],
+            "capabilities": [{"ref": "current_time"}, {"ref": "web_fetch"}],
+            "status": "active",
+            "created_at": "2026-04-15T00:00:00Z",
+            "updated_at": "2026-04-15T00:00:00Z"
+        }))
+        .unwrap();
+        let update = UpdateAgentRequest::new()
+            .system_prompt("Be kind.\nBe thorough.")
+            .capabilities(vec![
+                AgentCapabilityConfig::new("current_time"),
+                AgentCapabilityConfig::new("session_file_system"),
+            ])
+            .description("Answers tickets");
+
+        let diff = AgentDiff::new(&existing, &update);
+        assert_eq!(
+            diff.changed_fields(),
+            vec!["system_prompt", "description", "capabilities"]
+        );
+        assert_eq!(diff.capabilities_added, vec!["session_file_system"]);
+        assert_eq!(diff.capabilities_removed, vec!["web_fetch"]);
+        assert_eq!(
+            diff.system_prompt.unwrap().to_string(),
+            "  Be kind.\n- Be brief.\n+ Be thorough.\n"
+        );
+        assert!(AgentDiff::new(&existing, &UpdateAgentRequest::new()).is_empty());
+    }
+}
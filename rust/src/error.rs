@@ -5,7 +5,11 @@ use thiserror::Error;
 /// Errors that can occur when using the Everruns SDK
 #[derive(Error, Debug)]
 pub enum Error {
-    /// API returned an error response
+    /// API returned an error response.
+    ///
+    /// Reads are eventually consistent: a `get` issued right after a
+    /// successful create can answer 404 until the new resource replicates.
+    /// Use the `create_and_confirm` variants to wait for it.
     #[error("API error: {code} - {message}")]
     Api {
         code: String,
@@ -13,6 +17,35 @@ pub enum Error {
         status: u16,
     },
 
+    /// API rejected the request because the credentials lack permission
+    #[error("Forbidden: {message}")]
+    Forbidden { code: String, message: String },
+
+    /// API rejected the request because the organization is rate limited
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        code: String,
+        message: String,
+        /// How long the server asked to wait, from `Retry-After` or the body
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// Server answered 503: it is temporarily unable to take the request,
+    /// typically for lack of capacity for a new session
+    #[error("Capacity exceeded: {message}")]
+    CapacityExceeded {
+        message: String,
+        /// How long the server suggests waiting before retrying
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// A created resource was still not readable when confirmation gave up
+    #[error("{id} not readable after {}ms", waited.as_millis())]
+    NotYetVisible {
+        id: String,
+        waited: std::time::Duration,
+    },
+
     /// Network or HTTP error
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
@@ -41,6 +74,23 @@ pub enum Error {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Request payload exceeds a client-side size limit
+    #[error("Payload too large: {size} bytes exceeds limit of {limit} bytes")]
+    PayloadTooLarge { size: usize, limit: usize },
+
+    /// Request made after [`Everruns::shutdown`](crate::Everruns::shutdown)
+    #[error("Client is shutting down")]
+    ShuttingDown,
+
+    /// SSE connection went silent for longer than the expected heartbeat
+    /// interval. The stream reconnects on its own, so this is a notice.
+    #[error("Heartbeat missed: no data for {silent_ms}ms (interval {interval_ms}ms)")]
+    HeartbeatMissed { interval_ms: u64, silent_ms: u64 },
+
+    /// A pipeline step's request failed or its turn did not complete
+    #[error("Pipeline step '{step}' failed: {message}")]
+    StepFailed { step: String, message: String },
+
     /// Server-initiated graceful disconnect with retry hint
     #[error("Graceful disconnect: reason={reason}, retry_ms={retry_ms}")]
     GracefulDisconnect { reason: String, retry_ms: u64 },
@@ -51,6 +101,9 @@ pub enum Error {
 #[non_exhaustive]
 pub struct ApiErrorResponse {
     pub error: ApiErrorDetail,
+    /// Seconds to wait before retrying, for 429 and transient 503 errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
 }
 
 /// Detail of an API error
@@ -61,12 +114,70 @@ pub struct ApiErrorDetail {
     pub message: String,
 }
 
+/// RFC 9457 problem details, the error body documented in the OpenAPI spec
+#[derive(serde::Deserialize)]
+struct ProblemDetails {
+    title: String,
+    #[serde(default)]
+    detail: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    retry_after_seconds: Option<u64>,
+}
+
 impl Error {
+    /// Build the error for a failed response, reading its body.
+    ///
+    /// A `Retry-After` header fills in `retry_after` when the body has none.
+    pub(crate) async fn from_response(resp: reqwest::Response) -> Self {
+        let status = resp.status().as_u16();
+        let header_wait = crate::retry::retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        let mut err = Self::from_api_response(status, &body);
+        if let Error::RateLimited { retry_after, .. }
+        | Error::CapacityExceeded { retry_after, .. } = &mut err
+        {
+            *retry_after = retry_after.or(header_wait);
+        }
+        err
+    }
+
     pub(crate) fn from_api_response(status: u16, body: &str) -> Self {
-        if let Ok(err) = serde_json::from_str::<ApiErrorResponse>(body) {
+        let parsed = serde_json::from_str::<ApiErrorResponse>(body)
+            .map(|err| (err.error.code, err.error.message, err.retry_after_seconds))
+            .or_else(|_| {
+                serde_json::from_str::<ProblemDetails>(body).map(|problem| {
+                    (
+                        problem.code.unwrap_or_else(|| "unknown".to_string()),
+                        problem.detail.unwrap_or(problem.title),
+                        problem.retry_after_seconds,
+                    )
+                })
+            });
+        if let Ok((code, message, retry_after_seconds)) = parsed {
+            let retry_after = retry_after_seconds.map(std::time::Duration::from_secs);
+            if status == 429 {
+                return Error::RateLimited {
+                    code,
+                    message,
+                    retry_after,
+                };
+            }
+            // Only the API's own error body marks a 503 as transient; a
+            // proxy's bare 503 stays an `Api` error.
+            if status == 503 {
+                return Error::CapacityExceeded {
+                    message,
+                    retry_after,
+                };
+            }
+            if status == 403 {
+                return Error::Forbidden { code, message };
+            }
             Error::Api {
-                code: err.error.code,
-                message: err.error.message,
+                code,
+                message,
                 status,
             }
         } else {
@@ -76,6 +187,13 @@ impl Error {
             } else {
                 body.to_string()
             };
+            if status == 429 {
+                return Error::RateLimited {
+                    code: "rate_limited".to_string(),
+                    message,
+                    retry_after: None,
+                };
+            }
             Error::Api {
                 code: "unknown".to_string(),
                 message,
@@ -83,6 +201,174 @@ impl Error {
             }
         }
     }
+
+    /// HTTP status a backend proxying Everruns should answer with.
+    ///
+    /// Client errors the caller can fix (400, 404, 409, 422, 429) and
+    /// client-side validation pass through. Failures of the backend's own
+    /// credentials or of the upstream call map to 502 (504 for timeouts), and
+    /// local misconfiguration to 500.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Error::Api { status, .. } if matches!(status, 400 | 404 | 409 | 422 | 429) => *status,
+            Error::RateLimited { .. } => 429,
+            Error::Validation(_) => 400,
+            Error::PayloadTooLarge { .. } => 413,
+            Error::ShuttingDown | Error::CapacityExceeded { .. } | Error::NotYetVisible { .. } => {
+                503
+            }
+            Error::Network(e) if e.is_timeout() => 504,
+            Error::EnvVar(_) | Error::Auth(_) | Error::Url(_) => 500,
+            _ => 502,
+        }
+    }
+
+    /// Error body in the Everruns API's own `{"error": {...}}` shape
+    pub fn to_api_error(&self) -> ApiErrorResponse {
+        let code = match self {
+            Error::Api { code, .. }
+            | Error::Forbidden { code, .. }
+            | Error::RateLimited { code, .. } => code.clone(),
+            Error::Validation(_) => "validation_error".to_string(),
+            Error::PayloadTooLarge { .. } => "payload_too_large".to_string(),
+            Error::ShuttingDown => "unavailable".to_string(),
+            Error::CapacityExceeded { .. } => "unavailable".to_string(),
+            Error::NotYetVisible { .. } => "not_yet_visible".to_string(),
+            Error::Network(e) if e.is_timeout() => "upstream_timeout".to_string(),
+            Error::EnvVar(_) | Error::Auth(_) | Error::Url(_) => "internal_error".to_string(),
+            _ => "upstream_error".to_string(),
+        };
+        ApiErrorResponse {
+            error: ApiErrorDetail {
+                code,
+                message: self.to_string(),
+            },
+            retry_after_seconds: match self {
+                Error::RateLimited { retry_after, .. }
+                | Error::CapacityExceeded { retry_after, .. } => {
+                    retry_after.map(|wait| wait.as_secs())
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl From<Error> for (axum::http::StatusCode, axum::Json<ApiErrorResponse>) {
+    fn from(err: Error) -> Self {
+        let status = axum::http::StatusCode::from_u16(err.http_status())
+            .unwrap_or(axum::http::StatusCode::BAD_GATEWAY);
+        (status, axum::Json(err.to_api_error()))
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for Error {
+    fn into_response(self) -> axum::response::Response {
+        <(axum::http::StatusCode, axum::Json<ApiErrorResponse>)>::from(self).into_response()
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let code = match self {
+            Error::Api { code, .. } => format!("everruns::api::{code}"),
+            Error::Forbidden { code, .. } => format!("everruns::forbidden::{code}"),
+            Error::RateLimited { code, .. } => format!("everruns::rate_limited::{code}"),
+            _ => format!("everruns::{}", self.variant_name().to_lowercase()),
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let help = match self {
+            Error::Api { status: 401, .. } | Error::Auth(_) => {
+                "check that EVERRUNS_API_KEY holds a valid, unrevoked API key".to_string()
+            }
+            Error::Api { status: 404, .. } => {
+                "check that the ID exists and belongs to the client's organization".to_string()
+            }
+            Error::RateLimited {
+                retry_after: Some(wait),
+                ..
+            } => format!(
+                "the organization is rate limited; retry after {}s",
+                wait.as_secs()
+            ),
+            Error::RateLimited { .. } => {
+                "the organization is rate limited; slow down or retry later".to_string()
+            }
+            Error::Api { status, .. } if *status >= 500 => {
+                "the server failed to handle the request; retry later".to_string()
+            }
+            Error::Forbidden { .. } => {
+                "the API key lacks permission for this operation".to_string()
+            }
+            Error::EnvVar(var) if var == "EVERRUNS_API_KEY" => {
+                "set EVERRUNS_API_KEY to an API key from the Everruns dashboard".to_string()
+            }
+            Error::EnvVar(var) => format!("set the {var} environment variable"),
+            Error::Network(_) => "check network connectivity and the client's base URL".to_string(),
+            Error::Json(_) => {
+                "the response did not match the SDK's models; try upgrading everruns-sdk"
+                    .to_string()
+            }
+            Error::Url(_) => "check the client's base URL".to_string(),
+            Error::PayloadTooLarge { .. } => {
+                "split the payload, or upload large content as a file".to_string()
+            }
+            Error::ShuttingDown => "create a new client; this one was shut down".to_string(),
+            Error::CapacityExceeded { .. } => {
+                "retry later, or create sessions with `sessions().create_with_wait`".to_string()
+            }
+            Error::NotYetVisible { .. } => {
+                "the resource was created but has not replicated yet; read it again later"
+                    .to_string()
+            }
+            Error::HeartbeatMissed { .. } => {
+                "the stream reconnects on its own; treat this as a notice".to_string()
+            }
+            Error::GracefulDisconnect { retry_ms, .. } => {
+                format!("reconnect after {retry_ms}ms")
+            }
+            _ => return None,
+        };
+        Some(Box::new(help))
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new(format!(
+            "https://docs.rs/everruns-sdk/latest/everruns_sdk/error/enum.Error.html#variant.{}",
+            self.variant_name()
+        )))
+    }
+}
+
+#[cfg(feature = "miette")]
+impl Error {
+    fn variant_name(&self) -> &'static str {
+        match self {
+            Error::Api { .. } => "Api",
+            Error::Forbidden { .. } => "Forbidden",
+            Error::RateLimited { .. } => "RateLimited",
+            Error::CapacityExceeded { .. } => "CapacityExceeded",
+            Error::NotYetVisible { .. } => "NotYetVisible",
+            Error::Network(_) => "Network",
+            Error::Auth(_) => "Auth",
+            Error::EnvVar(_) => "EnvVar",
+            Error::Json(_) => "Json",
+            Error::Url(_) => "Url",
+            Error::Sse(_) => "Sse",
+            Error::Validation(_) => "Validation",
+            Error::PayloadTooLarge { .. } => "PayloadTooLarge",
+            Error::ShuttingDown => "ShuttingDown",
+            Error::HeartbeatMissed { .. } => "HeartbeatMissed",
+            Error::StepFailed { .. } => "StepFailed",
+            Error::GracefulDisconnect { .. } => "GracefulDisconnect",
+        }
+    }
 }
 
 /// Check if the body looks like an HTML response
@@ -93,3 +379,44 @@ fn is_html_response(body: &str) -> bool {
 
 /// Result type for Everruns SDK operations
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(all(test, any(feature = "axum", feature = "miette")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn axum_response_uses_proxy_status_and_api_error_body() {
+        use axum::response::IntoResponse;
+
+        let response = Error::PayloadTooLarge { size: 2, limit: 1 }.into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn diagnostics_carry_code_help_and_url() {
+        use miette::Diagnostic;
+
+        let err = Error::EnvVar("EVERRUNS_API_KEY".to_string());
+        assert_eq!(err.code().unwrap().to_string(), "everruns::envvar");
+        assert!(
+            err.help()
+                .unwrap()
+                .to_string()
+                .contains("set EVERRUNS_API_KEY")
+        );
+        assert!(err.url().unwrap().to_string().ends_with("#variant.EnvVar"));
+
+        let err = Error::from_api_response(403, r#"{"error":{"code":"forbidden","message":"no"}}"#);
+        assert_eq!(
+            err.code().unwrap().to_string(),
+            "everruns::forbidden::forbidden"
+        );
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            "the API key lacks permission for this operation"
+        );
+        assert!(Error::Validation("bad".into()).help().is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Session event helpers.
+
+/// Event type names, for `StreamOptions` and `ListEventsOptions` filters and
+/// matching on [`Event::event_type`](crate::models::Event::event_type).
+///
+/// ```
+/// use everruns_sdk::client::ListEventsOptions;
+/// use everruns_sdk::events::types;
+///
+/// let options = ListEventsOptions {
+///     exclude: vec![types::REASON_THINKING_DELTA.into()],
+///     ..Default::default()
+/// };
+/// # let _ = options;
+/// ```
+pub mod types {
+    // Input / output
+    pub const INPUT_MESSAGE: &str = "input.message";
+    pub const OUTPUT_MESSAGE_STARTED: &str = "output.message.started";
+    pub const OUTPUT_MESSAGE_DELTA: &str = "output.message.delta";
+    pub const OUTPUT_MESSAGE_COMPLETED: &str = "output.message.completed";
+    pub const OUTPUT_MESSAGE_REPLACED: &str = "output.message.replaced";
+
+    // Turn lifecycle
+    pub const TURN_STARTED: &str = "turn.started";
+    pub const TURN_COMPLETED: &str = "turn.completed";
+    pub const TURN_FAILED: &str = "turn.failed";
+    pub const TURN_CANCELLED: &str = "turn.cancelled";
+
+    // Reasoning
+    pub const REASON_STARTED: &str = "reason.started";
+    pub const REASON_COMPLETED: &str = "reason.completed";
+    pub const REASON_ITEM: &str = "reason.item";
+    pub const REASON_RECOVERED: &str = "reason.recovered";
+    pub const REASON_THINKING_STARTED: &str = "reason.thinking.started";
+    pub const REASON_THINKING_DELTA: &str = "reason.thinking.delta";
+    pub const REASON_THINKING_COMPLETED: &str = "reason.thinking.completed";
+
+    // Acts
+    pub const ACT_STARTED: &str = "act.started";
+    pub const ACT_COMPLETED: &str = "act.completed";
+
+    // Tools
+    pub const TOOL_CALL_REQUESTED: &str = "tool.call_requested";
+    pub const TOOL_STARTED: &str = "tool.started";
+    pub const TOOL_PROGRESS: &str = "tool.progress";
+    pub const TOOL_OUTPUT_DELTA: &str = "tool.output.delta";
+    pub const TOOL_COMPLETED: &str = "tool.completed";
+
+    // Session lifecycle
+    pub const SESSION_STARTED: &str = "session.started";
+    pub const SESSION_ACTIVATED: &str = "session.activated";
+    pub const SESSION_IDLED: &str = "session.idled";
+
+    // Context and files
+    pub const CONTEXT_COMPACTING: &str = "context.compacting";
+    pub const CONTEXT_COMPACTED: &str = "context.compacted";
+    pub const TRANSCRIPT_REPAIRED: &str = "transcript.repaired";
+    pub const FILE_WRITTEN: &str = "file.written";
+
+    // Usage
+    pub const LLM_GENERATION: &str = "llm.generation";
+    pub const CAPABILITY_USAGE: &str = "capability.usage";
+
+    // Tasks
+    pub const TASK_CREATED: &str = "task.created";
+    pub const TASK_UPDATED: &str = "task.updated";
+    pub const TASK_MESSAGE_SENT: &str = "task.message.sent";
+    pub const TASK_MESSAGE_RECEIVED: &str = "task.message.received";
+
+    // Voice
+    pub const VOICE_SESSION_STARTED: &str = "voice.session.started";
+    pub const VOICE_SESSION_ENDED: &str = "voice.session.ended";
+    pub const VOICE_SESSION_FAILED: &str = "voice.session.failed";
+
+    /// Synthetic event yielded by the SDK's event stream, never sent by the
+    /// server; see [`StreamOptions::reconnect_notices`](crate::sse::StreamOptions::reconnect_notices).
+    #[cfg(feature = "sse")]
+    pub const STREAM_RECONNECTED: &str = crate::sse::RECONNECTED_EVENT;
+    /// Synthetic event yielded by the SDK's event stream; see
+    /// [`StreamOptions::detect_gaps`](crate::sse::StreamOptions::detect_gaps).
+    #[cfg(feature = "sse")]
+    pub const STREAM_GAP_DETECTED: &str = crate::sse::GAP_DETECTED_EVENT;
+}
+
+macro_rules! event_kinds {
+    ($($variant:ident => $name:path,)*) => {
+        /// Known event types as an enum, for matching the compiler checks.
+        ///
+        /// A `match` on [`Event::kind`](crate::models::Event::kind) without a
+        /// `_` arm has to name every known kind, so forgetting `turn.failed`
+        /// is a build error rather than a consumer loop that never ends.
+        /// Types this SDK version does not know, and the synthetic `stream.*`
+        /// events, arrive as [`EventKind::Unknown`], the one explicit
+        /// wildcard. Kinds are added
+        /// as the server gains them, so such matches need updating on
+        /// upgrade; matches with a `_` arm keep compiling.
+        ///
+        /// ```
+        /// use everruns_sdk::events::EventKind;
+        ///
+        /// fn ends_turn(kind: EventKind<'_>) -> bool {
+        ///     match kind {
+        ///         EventKind::TurnCompleted | EventKind::TurnFailed | EventKind::TurnCancelled => true,
+        ///         EventKind::Unknown(other) => other.starts_with("turn."),
+        ///         _ => false,
+        ///     }
+        /// }
+        /// assert!(ends_turn(EventKind::parse("turn.failed")));
+        /// ```
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum EventKind<'a> {
+            $($variant,)*
+            /// An event type not listed above
+            Unknown(&'a str),
+        }
+
+        impl<'a> EventKind<'a> {
+            /// Classify an event type name.
+            pub fn parse(event_type: &'a str) -> Self {
+                match event_type {
+                    $($name => Self::$variant,)*
+                    other => Self::Unknown(other),
+                }
+            }
+
+            /// The event type name, as in [`types`].
+            pub fn as_str(&self) -> &'a str {
+                match self {
+                    $(Self::$variant => $name,)*
+                    Self::Unknown(other) => other,
+                }
+            }
+        }
+    };
+}
+
+event_kinds! {
+    InputMessage => types::INPUT_MESSAGE,
+    OutputMessageStarted => types::OUTPUT_MESSAGE_STARTED,
+    OutputMessageDelta => types::OUTPUT_MESSAGE_DELTA,
+    OutputMessageCompleted => types::OUTPUT_MESSAGE_COMPLETED,
+    OutputMessageReplaced => types::OUTPUT_MESSAGE_REPLACED,
+    TurnStarted => types::TURN_STARTED,
+    TurnCompleted => types::TURN_COMPLETED,
+    TurnFailed => types::TURN_FAILED,
+    TurnCancelled => types::TURN_CANCELLED,
+    ReasonStarted => types::REASON_STARTED,
+    ReasonCompleted => types::REASON_COMPLETED,
+    ReasonItem => types::REASON_ITEM,
+    ReasonRecovered => types::REASON_RECOVERED,
+    ReasonThinkingStarted => types::REASON_THINKING_STARTED,
+    ReasonThinkingDelta => types::REASON_THINKING_DELTA,
+    ReasonThinkingCompleted => types::REASON_THINKING_COMPLETED,
+    ActStarted => types::ACT_STARTED,
+    ActCompleted => types::ACT_COMPLETED,
+    ToolCallRequested => types::TOOL_CALL_REQUESTED,
+    ToolStarted => types::TOOL_STARTED,
+    ToolProgress => types::TOOL_PROGRESS,
+    ToolOutputDelta => types::TOOL_OUTPUT_DELTA,
+    ToolCompleted => types::TOOL_COMPLETED,
+    SessionStarted => types::SESSION_STARTED,
+    SessionActivated => types::SESSION_ACTIVATED,
+    SessionIdled => types::SESSION_IDLED,
+    ContextCompacting => types::CONTEXT_COMPACTING,
+    ContextCompacted => types::CONTEXT_COMPACTED,
+    TranscriptRepaired => types::TRANSCRIPT_REPAIRED,
+    FileWritten => types::FILE_WRITTEN,
+    LlmGeneration => types::LLM_GENERATION,
+    CapabilityUsage => types::CAPABILITY_USAGE,
+    TaskCreated => types::TASK_CREATED,
+    TaskUpdated => types::TASK_UPDATED,
+    TaskMessageSent => types::TASK_MESSAGE_SENT,
+    TaskMessageReceived => types::TASK_MESSAGE_RECEIVED,
+    VoiceSessionStarted => types::VOICE_SESSION_STARTED,
+    VoiceSessionEnded => types::VOICE_SESSION_ENDED,
+    VoiceSessionFailed => types::VOICE_SESSION_FAILED,
+}
@@ -0,0 +1,102 @@
+//! Hooks around every API request.
+//!
+//! An [`Interceptor`] installed with
+//! [`EverrunsBuilder::interceptor`](crate::client::EverrunsBuilder::interceptor)
+//! sees each request the client sends, including retries and SSE
+//! connections, and may change its method, URL and headers. It then sees
+//! the status and headers of the response before the SDK reads the body.
+//! Both hooks use the `http` crate's types:
+//!
+//! ```no_run
+//! use everruns_sdk::intercept::Interceptor;
+//!
+//! struct Gateway;
+//!
+//! impl Interceptor for Gateway {
+//!     fn before(&self, request: &mut http::request::Parts) {
+//!         request
+//!             .headers
+//!             .insert("x-gateway-tenant", http::HeaderValue::from_static("acme"));
+//!     }
+//!
+//!     fn after(&self, request: &http::request::Parts, response: &http::response::Parts) {
+//!         println!("{} {} -> {}", request.method, request.uri, response.status);
+//!     }
+//! }
+//!
+//! # fn example() -> Result<(), everruns_sdk::Error> {
+//! let client = everruns_sdk::Everruns::builder().interceptor(Gateway).build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Interceptors run in the order they were added. Request bodies are not
+//! exposed, so content rewriting stays with [`crate::redact`].
+
+use crate::error::{Error, Result};
+use std::sync::Arc;
+
+/// Hook that inspects or changes requests and observes responses.
+pub trait Interceptor: Send + Sync {
+    /// Change the request before it is sent. Does nothing by default.
+    fn before(&self, request: &mut http::request::Parts) {
+        let _ = request;
+    }
+
+    /// Observe the response to `request`, before its body is read. Does
+    /// nothing by default.
+    fn after(&self, request: &http::request::Parts, response: &http::response::Parts) {
+        let _ = (request, response);
+    }
+}
+
+/// Cloneable, type-erased [`Interceptor`]s held by the client.
+#[derive(Clone, Default)]
+pub(crate) struct Interceptors(Vec<Arc<dyn Interceptor>>);
+
+impl Interceptors {
+    pub(crate) fn push(&mut self, interceptor: impl Interceptor + 'static) {
+        self.0.push(Arc::new(interceptor));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Run the `before` hooks over `request`, returning what they saw last.
+    pub(crate) fn before(&self, request: &mut reqwest::Request) -> Result<http::request::Parts> {
+        let uri = request
+            .url()
+            .as_str()
+            .parse::<http::Uri>()
+            .map_err(|err| Error::Validation(format!("invalid request URI: {err}")))?;
+        let (mut parts, ()) = http::Request::new(()).into_parts();
+        parts.method = request.method().clone();
+        parts.uri = uri;
+        parts.headers = std::mem::take(request.headers_mut());
+        for interceptor in &self.0 {
+            interceptor.before(&mut parts);
+        }
+        *request.method_mut() = parts.method.clone();
+        *request.url_mut() = url::Url::parse(&parts.uri.to_string())?;
+        *request.headers_mut() = parts.headers.clone();
+        Ok(parts)
+    }
+
+    /// Run the `after` hooks with the response's status and headers.
+    pub(crate) fn after(&self, request: &http::request::Parts, response: &reqwest::Response) {
+        let (mut parts, ()) = http::Response::new(()).into_parts();
+        parts.status = response.status();
+        parts.version = response.version();
+        parts.headers = response.headers().clone();
+        for interceptor in &self.0 {
+            interceptor.after(request, &parts);
+        }
+    }
+}
+
+impl std::fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Interceptors({})", self.0.len())
+    }
+}
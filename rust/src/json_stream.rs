@@ -0,0 +1,245 @@
+//! Incremental extraction of list items from a `{"data": [...]}` body.
+//!
+//! Used by streaming list calls so only the element currently being parsed
+//! is buffered, rather than the full (possibly tens of MB) response body.
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Scanning top-level keys for `data`
+    SeekingData,
+    /// Saw `"data":`, waiting for the opening `[`
+    AwaitingArray,
+    /// Inside the `data` array, splitting elements
+    InArray,
+    /// The `data` array has been closed; remaining bytes are ignored
+    Done,
+}
+
+/// Byte-level scanner that yields the raw JSON of each `data` array element.
+pub(crate) struct DataArrayScanner {
+    buf: Vec<u8>,
+    scanned: usize,
+    state: State,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    expect_key: bool,
+    key_start: Option<usize>,
+    last_key_is_data: bool,
+    elem_start: Option<usize>,
+}
+
+impl DataArrayScanner {
+    pub(crate) fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            scanned: 0,
+            state: State::SeekingData,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            expect_key: false,
+            key_start: None,
+            last_key_is_data: false,
+            elem_start: None,
+        }
+    }
+
+    /// Feed the next chunk of the body, returning every element completed by it.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Result<Vec<Vec<u8>>> {
+        if self.state == State::Done {
+            return Ok(Vec::new());
+        }
+        self.buf.extend_from_slice(chunk);
+        let mut elements = Vec::new();
+
+        while self.scanned < self.buf.len() && self.state != State::Done {
+            let i = self.scanned;
+            let c = self.buf[i];
+            self.scanned += 1;
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if c == b'\\' {
+                    self.escaped = true;
+                } else if c == b'"' {
+                    self.in_string = false;
+                    if let Some(start) = self.key_start.take() {
+                        self.last_key_is_data = &self.buf[start..i] == b"data";
+                    }
+                }
+                continue;
+            }
+
+            if self.state == State::AwaitingArray && !c.is_ascii_whitespace() && c != b'[' {
+                return Err(invalid("`data` is not an array"));
+            }
+
+            match c {
+                b'"' => {
+                    self.in_string = true;
+                    if self.depth == 1 && self.expect_key {
+                        self.key_start = Some(i + 1);
+                    } else {
+                        self.start_element(i);
+                    }
+                }
+                b'{' | b'[' => {
+                    if self.state == State::AwaitingArray {
+                        self.state = State::InArray;
+                    } else {
+                        self.start_element(i);
+                    }
+                    self.depth += 1;
+                    if c == b'{' && self.depth == 1 {
+                        self.expect_key = true;
+                    }
+                }
+                b'}' | b']' => {
+                    if self.state == State::InArray && self.depth == 2 && c == b']' {
+                        if let Some(start) = self.elem_start.take() {
+                            elements.push(self.buf[start..i].to_vec());
+                        }
+                        self.state = State::Done;
+                        continue;
+                    }
+                    self.depth = self
+                        .depth
+                        .checked_sub(1)
+                        .ok_or_else(|| invalid("unbalanced brackets"))?;
+                    if self.state == State::InArray
+                        && self.depth == 2
+                        && let Some(start) = self.elem_start.take()
+                    {
+                        elements.push(self.buf[start..=i].to_vec());
+                    }
+                }
+                b':' if self.depth == 1 => {
+                    self.expect_key = false;
+                    if self.state == State::SeekingData && self.last_key_is_data {
+                        self.state = State::AwaitingArray;
+                    }
+                }
+                b',' => {
+                    if self.depth == 1 {
+                        self.expect_key = true;
+                    } else if self.state == State::InArray
+                        && self.depth == 2
+                        && let Some(start) = self.elem_start.take()
+                    {
+                        elements.push(self.buf[start..i].to_vec());
+                    }
+                }
+                c if c.is_ascii_whitespace() => {}
+                _ => self.start_element(i),
+            }
+        }
+
+        self.compact();
+        Ok(elements)
+    }
+
+    /// Verify the body contained a complete `data` array.
+    pub(crate) fn finish(&self) -> Result<()> {
+        match self.state {
+            State::Done => Ok(()),
+            State::SeekingData => Err(invalid("missing `data` array")),
+            State::AwaitingArray | State::InArray => Err(invalid("truncated `data` array")),
+        }
+    }
+
+    fn start_element(&mut self, i: usize) {
+        if self.state == State::InArray && self.depth == 2 && self.elem_start.is_none() {
+            self.elem_start = Some(i);
+        }
+    }
+
+    /// Drop bytes that no pending element or key needs anymore.
+    fn compact(&mut self) {
+        let keep_from = self.elem_start.or(self.key_start).unwrap_or(self.scanned);
+        if keep_from == 0 {
+            return;
+        }
+        self.buf.drain(..keep_from);
+        self.scanned -= keep_from;
+        self.elem_start = self.elem_start.map(|s| s - keep_from);
+        self.key_start = self.key_start.map(|s| s - keep_from);
+    }
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::Json(serde::de::Error::custom(format!(
+        "invalid list response: {msg}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_in_chunks(body: &str, chunk_size: usize) -> Vec<serde_json::Value> {
+        let mut scanner = DataArrayScanner::new();
+        let mut values = Vec::new();
+        for chunk in body.as_bytes().chunks(chunk_size) {
+            for element in scanner.feed(chunk).unwrap() {
+                values.push(serde_json::from_slice(&element).unwrap());
+            }
+        }
+        scanner.finish().unwrap();
+        values
+    }
+
+    #[test]
+    fn test_scanner_splits_elements_across_chunk_boundaries() {
+        let body = r#"{"total": 3, "meta": {"data": [9]}, "data": [
+            {"id": "a", "nested": {"x": [1, 2, "]"]}},
+            "str,with\"quote",
+            42
+        ], "offset": 0}"#;
+        for chunk_size in [1, 2, 3, 7, 64, body.len()] {
+            let values = scan_in_chunks(body, chunk_size);
+            assert_eq!(
+                values,
+                vec![
+                    serde_json::json!({"id": "a", "nested": {"x": [1, 2, "]"]}}),
+                    serde_json::json!("str,with\"quote"),
+                    serde_json::json!(42),
+                ],
+                "chunk size {chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_scanner_empty_array() {
+        assert!(scan_in_chunks(r#"{"data": []}"#, 4).is_empty());
+    }
+
+    #[test]
+    fn test_scanner_keeps_buffer_bounded() {
+        let mut scanner = DataArrayScanner::new();
+        scanner.feed(br#"{"data": ["#).unwrap();
+        for _ in 0..1000 {
+            let elements = scanner.feed(br#"{"id": "event_1"},"#).unwrap();
+            assert_eq!(elements.len(), 1);
+            assert!(scanner.buf.len() < 64);
+        }
+    }
+
+    #[test]
+    fn test_scanner_errors_on_truncated_body() {
+        let mut scanner = DataArrayScanner::new();
+        scanner.feed(br#"{"data": [{"id": 1}"#).unwrap();
+        assert!(scanner.finish().is_err());
+    }
+
+    #[test]
+    fn test_scanner_errors_without_data() {
+        let mut scanner = DataArrayScanner::new();
+        scanner.feed(br#"{"items": []}"#).unwrap();
+        assert!(scanner.finish().is_err());
+    }
+}
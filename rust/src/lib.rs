@@ -29,12 +29,42 @@
 //! ```
 
 pub mod auth;
+mod cache;
 pub mod client;
+pub mod diff;
 pub mod error;
+pub mod events;
+pub mod intercept;
+mod json_stream;
+pub mod manifest;
 pub mod models;
+pub mod openai;
+pub mod otel;
+pub mod outbox;
+pub mod pagination;
+#[cfg(feature = "pii")]
+pub mod pii;
+#[cfg(feature = "sse")]
+pub mod pipeline;
+pub mod progress;
+pub mod redact;
+pub mod retry;
+pub mod session_log;
+mod shutdown;
+#[cfg(feature = "sse")]
 pub mod sse;
+#[cfg(feature = "sqlite")]
+pub mod store;
+mod task;
+pub mod terminal;
+pub mod tokens;
+#[cfg(feature = "sse")]
+pub mod turn;
+pub mod view;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
 
-pub use auth::ApiKey;
+pub use auth::{ApiKey, Sensitive};
 pub use client::Everruns;
 pub use error::Error;
 pub use models::*;
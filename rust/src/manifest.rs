@@ -0,0 +1,246 @@
+//! Declarative agent management.
+//!
+//! A [`Manifest`] lists the agents an organization should have.
+//! [`Everruns::plan`] compares it with the agents that exist and reports
+//! what would change, as a dry run; [`Everruns::apply`] makes those changes. Agents are
+//! matched by name, so a manifest kept in version control converges the
+//! org on every run:
+//!
+//! ```no_run
+//! use everruns_sdk::manifest::Manifest;
+//!
+//! # async fn example(client: everruns_sdk::Everruns) -> Result<(), everruns_sdk::Error> {
+//! let manifest = Manifest::from_json(&std::fs::read_to_string("agents.json").unwrap())?;
+//! let plan = client.plan(&manifest).await?;
+//! print!("{plan}");
+//! client.apply(&manifest).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With `prune` set, active agents missing from the manifest are archived.
+//! TOML and YAML manifests need the `toml` and `yaml` features.
+
+use crate::client::Everruns;
+use crate::diff::AgentDiff;
+use crate::error::{Error, Result};
+use crate::models::{Agent, CreateAgentRequest, validate_agent_name};
+use futures::TryStreamExt;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// Desired set of agents
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct Manifest {
+    #[serde(default)]
+    pub agents: Vec<CreateAgentRequest>,
+    /// Archive active agents that the manifest does not list
+    #[serde(default)]
+    pub prune: bool,
+}
+
+impl Manifest {
+    /// Create an empty manifest
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an agent
+    pub fn agent(mut self, agent: CreateAgentRequest) -> Self {
+        self.agents.push(agent);
+        self
+    }
+
+    /// Archive agents the manifest does not list
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+
+    /// Parse a JSON manifest
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parse a TOML manifest, with agents as `[[agents]]` tables
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| Error::Validation(format!("invalid manifest: {e}")))
+    }
+
+    /// Parse a YAML manifest
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        serde_yaml::from_str(yaml).map_err(|e| Error::Validation(format!("invalid manifest: {e}")))
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for agent in &self.agents {
+            validate_agent_name(&agent.name)?;
+            if !names.insert(agent.name.as_str()) {
+                return Err(Error::Validation(format!(
+                    "agent '{}' is listed twice in the manifest",
+                    agent.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One change needed to reconcile the org with a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AgentChange {
+    Create {
+        name: String,
+    },
+    /// An existing agent whose configuration differs from the manifest
+    Update {
+        id: String,
+        name: String,
+        diff: AgentDiff,
+    },
+    /// An agent missing from a pruning manifest
+    Archive {
+        id: String,
+        name: String,
+    },
+}
+
+/// Changes that reconcile the org with a manifest, in the order applied
+///
+/// `Display` renders a preview with one line per change: `+` for creates,
+/// `~` for updates (with the fields that change) and `-` for archives.
+/// Each update's [`AgentDiff`] has the details.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Plan {
+    pub changes: Vec<AgentChange>,
+}
+
+impl Plan {
+    /// Whether the org already matches the manifest
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl std::fmt::Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            match change {
+                AgentChange::Create { name } => writeln!(f, "+ {name}")?,
+                AgentChange::Update { name, diff, .. } => {
+                    writeln!(f, "~ {name} ({})", diff.changed_fields().join(", "))?
+                }
+                AgentChange::Archive { name, .. } => writeln!(f, "- {name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) async fn plan(client: &Everruns, manifest: &Manifest) -> Result<Plan> {
+    manifest.validate()?;
+    let existing: Vec<Agent> = client
+        .agents()
+        .list_all(Default::default())
+        .try_collect()
+        .await?;
+    let by_name: HashMap<&str, &Agent> = existing
+        .iter()
+        .map(|agent| (agent.name.as_str(), agent))
+        .collect();
+
+    let mut changes = Vec::new();
+    for desired in &manifest.agents {
+        match by_name.get(desired.name.as_str()) {
+            None => changes.push(AgentChange::Create {
+                name: desired.name.clone(),
+            }),
+            Some(agent) => {
+                let diff = AgentDiff::new(agent, &desired.clone().into());
+                if !diff.is_empty() {
+                    changes.push(AgentChange::Update {
+                        id: agent.id.clone(),
+                        name: agent.name.clone(),
+                        diff,
+                    });
+                }
+            }
+        }
+    }
+    if manifest.prune {
+        let wanted: HashSet<&str> = manifest.agents.iter().map(|a| a.name.as_str()).collect();
+        changes.extend(
+            existing
+                .iter()
+                .filter(|agent| !wanted.contains(agent.name.as_str()))
+                .map(|agent| AgentChange::Archive {
+                    id: agent.id.clone(),
+                    name: agent.name.clone(),
+                }),
+        );
+    }
+    Ok(Plan { changes })
+}
+
+pub(crate) async fn apply(client: &Everruns, manifest: &Manifest) -> Result<Plan> {
+    let plan = plan(client, manifest).await?;
+    let desired: HashMap<&str, &CreateAgentRequest> = manifest
+        .agents
+        .iter()
+        .map(|agent| (agent.name.as_str(), agent))
+        .collect();
+    for change in &plan.changes {
+        match change {
+            AgentChange::Create { name } | AgentChange::Update { name, .. } => {
+                let req = desired[name.as_str()].clone();
+                client.agents().apply_by_name_with_options(req).await?;
+            }
+            AgentChange::Archive { id, .. } => client.agents().delete(id).await?,
+        }
+    }
+    Ok(plan)
+}
+
+#[cfg(all(test, any(feature = "toml", feature = "yaml")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_manifests_list_agents_as_tables() {
+        let manifest = Manifest::from_toml(
+            r#"
+            prune = true
+
+            [[agents]]
+            name = "support"
+            system_prompt = "Help customers."
+            tags = ["prod"]
+            "#,
+        )
+        .unwrap();
+        assert!(manifest.prune);
+        assert_eq!(manifest.agents[0].name, "support");
+        assert_eq!(manifest.agents[0].tags, vec!["prod"]);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_manifests_parse_and_report_errors_as_validation() {
+        let manifest =
+            Manifest::from_yaml("agents:\n  - name: support\n    system_prompt: Help customers.\n")
+                .unwrap();
+        assert_eq!(manifest.agents[0].system_prompt, "Help customers.");
+        assert!(matches!(
+            Manifest::from_yaml("agents: 3"),
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! These types represent the request and response objects used by the API.
 
+use crate::auth::Sensitive;
 use serde::{Deserialize, Serialize};
 
 /// Per-agent capability configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `Debug` output omits `config`, which may hold credentials.
+#[derive(Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct AgentCapabilityConfig {
     /// Reference to the capability ID
@@ -16,6 +19,15 @@ pub struct AgentCapabilityConfig {
     pub config: Option<serde_json::Value>,
 }
 
+impl std::fmt::Debug for AgentCapabilityConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentCapabilityConfig")
+            .field("capability_ref", &self.capability_ref)
+            .field("config", &self.config.as_ref().map(Sensitive::new))
+            .finish()
+    }
+}
+
 impl AgentCapabilityConfig {
     /// Create a new capability config with just a ref
     pub fn new(capability_ref: impl Into<String>) -> Self {
@@ -30,6 +42,14 @@ impl AgentCapabilityConfig {
         self.config = Some(config);
         self
     }
+
+    /// Attach a `guardrails` capability with the given config.
+    ///
+    /// Use a [`GuardrailExample::config`] as a starting point and check it
+    /// with `capabilities().dry_run_guardrails` before rollout.
+    pub fn guardrails(config: serde_json::Value) -> Self {
+        Self::new("guardrails").config(config)
+    }
 }
 
 /// Client-side tool definition executed by SDK users.
@@ -187,6 +207,9 @@ pub struct Agent {
     pub capabilities: Vec<AgentCapabilityConfig>,
     #[serde(default)]
     pub initial_files: Vec<InitialFile>,
+    /// Maximum number of LLM iterations per turn
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
     pub status: AgentStatus,
     pub created_at: String,
     pub updated_at: String,
@@ -347,7 +370,7 @@ impl RollbackAgentVersionRequest {
 }
 
 /// Request to create an agent
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct CreateAgentRequest {
     /// Client-supplied agent ID (format: agent_{32-hex}).
@@ -365,14 +388,17 @@ pub struct CreateAgentRequest {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_model_id: Option<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub tags: Vec<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub capabilities: Vec<AgentCapabilityConfig>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub initial_files: Vec<InitialFile>,
+    /// Maximum number of LLM iterations per turn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<u32>,
 }
 
 impl CreateAgentRequest {
@@ -389,6 +415,7 @@ impl CreateAgentRequest {
             capabilities: vec![],
             tools: vec![],
             initial_files: vec![],
+            max_iterations: None,
         }
     }
 
@@ -404,6 +431,12 @@ impl CreateAgentRequest {
         self
     }
 
+    /// Set the system prompt
+    pub fn system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = system_prompt.into();
+        self
+    }
+
     /// Set the description
     pub fn description(mut self, description: impl Into<String>) -> Self {
         self.description = Some(description.into());
@@ -439,6 +472,108 @@ impl CreateAgentRequest {
         self.initial_files = initial_files;
         self
     }
+
+    /// Bound the LLM iterations (model calls) of each turn
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+}
+
+/// Request to update an agent. Only provided fields are updated.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct UpdateAgentRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_model_id: Option<String>,
+    /// Replaces the agent's full tag list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Replaces the agent's full capability list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<AgentCapabilityConfig>>,
+    /// Replaces the agent's starter files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_files: Option<Vec<InitialFile>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<u32>,
+}
+
+impl UpdateAgentRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn display_name(mut self, display_name: impl Into<String>) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    pub fn system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn default_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.default_model_id = Some(model_id.into());
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    pub fn capabilities(mut self, capabilities: Vec<AgentCapabilityConfig>) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    pub fn initial_files(mut self, initial_files: Vec<InitialFile>) -> Self {
+        self.initial_files = Some(initial_files);
+        self
+    }
+
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+}
+
+impl From<CreateAgentRequest> for UpdateAgentRequest {
+    /// Update every field a create request sets; unset optional fields are
+    /// left unchanged.
+    fn from(req: CreateAgentRequest) -> Self {
+        Self {
+            name: Some(req.name),
+            display_name: req.display_name,
+            system_prompt: Some(req.system_prompt),
+            description: req.description,
+            default_model_id: req.default_model_id,
+            tags: Some(req.tags),
+            capabilities: Some(req.capabilities),
+            initial_files: Some(req.initial_files),
+            max_iterations: req.max_iterations,
+        }
+    }
 }
 
 /// Generate a random agent ID in the format `agent_<32-hex>`.
@@ -457,6 +592,39 @@ pub fn generate_harness_id() -> String {
     format!("harness_{}", hex)
 }
 
+/// Hosts and URLs a session may reach.
+///
+/// Patterns are exact domains (`example.com`), wildcard subdomains
+/// (`*.example.com`) or URL prefixes (`https://example.com/api/`). Session,
+/// agent and harness lists are merged: allowed lists intersect, blocked
+/// lists union, and blocked always wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct NetworkAccessList {
+    /// If non-empty, only matching URLs are permitted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+    /// Always denied, even if matched by `allowed`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked: Vec<String>,
+}
+
+impl NetworkAccessList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allowed(mut self, patterns: Vec<String>) -> Self {
+        self.allowed = patterns;
+        self
+    }
+
+    pub fn blocked(mut self, patterns: Vec<String>) -> Self {
+        self.blocked = patterns;
+        self
+    }
+}
+
 /// Session representing an active conversation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -490,6 +658,56 @@ pub struct Session {
     /// Whether this session is pinned by the current user
     #[serde(default)]
     pub is_pinned: Option<bool>,
+    /// Session that spawned this one as a subagent, for server-side spawns
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+    /// Session-level network access list, before merging with agent and harness
+    #[serde(default)]
+    pub network_access: Option<NetworkAccessList>,
+    /// Maximum number of LLM iterations per turn
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
+}
+
+/// Request to update a session. Only provided fields are updated.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct UpdateSessionRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Replaces the session's full tag list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_identity_id: Option<String>,
+}
+
+impl UpdateSessionRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    pub fn agent_identity_id(mut self, agent_identity_id: impl Into<String>) -> Self {
+        self.agent_identity_id = Some(agent_identity_id.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -503,7 +721,7 @@ pub enum SessionStatus {
 }
 
 /// Token usage statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct TokenUsage {
     #[serde(default)]
@@ -514,6 +732,29 @@ pub struct TokenUsage {
     pub cache_read_tokens: u64,
 }
 
+impl std::ops::Add for TokenUsage {
+    type Output = TokenUsage;
+
+    fn add(mut self, rhs: TokenUsage) -> TokenUsage {
+        self += rhs;
+        self
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, rhs: TokenUsage) {
+        self.input_tokens += rhs.input_tokens;
+        self.output_tokens += rhs.output_tokens;
+        self.cache_read_tokens += rhs.cache_read_tokens;
+    }
+}
+
+impl std::iter::Sum for TokenUsage {
+    fn sum<I: Iterator<Item = TokenUsage>>(iter: I) -> TokenUsage {
+        iter.fold(TokenUsage::default(), |total, usage| total + usage)
+    }
+}
+
 /// Aggregate usage statistics for an agent or harness.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -531,6 +772,15 @@ pub struct ResourceStats {
     pub total_output_tokens: u64,
     pub total_cache_read_tokens: u64,
     pub total_cache_creation_tokens: u64,
+    /// Provider-reported actual cost in USD
+    #[serde(default)]
+    pub total_actual_cost_usd: f64,
+    /// Price-table estimated cost in USD
+    #[serde(default)]
+    pub total_estimated_cost_usd: f64,
+    /// Best-effort cost in USD: actual where reported, estimated otherwise
+    #[serde(default)]
+    pub total_cost_usd: f64,
     #[serde(default)]
     pub first_session_at: Option<String>,
     #[serde(default)]
@@ -698,6 +948,9 @@ pub struct CreateSessionRequest {
     pub locale: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model_id: Option<String>,
+    /// Session-level system prompt, prepended to the agent's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -706,6 +959,11 @@ pub struct CreateSessionRequest {
     pub tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub initial_files: Vec<InitialFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_access: Option<NetworkAccessList>,
+    /// Maximum number of LLM iterations per turn, overriding the agent's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<u32>,
 }
 
 impl Default for CreateSessionRequest {
@@ -724,10 +982,13 @@ impl CreateSessionRequest {
             title: None,
             locale: None,
             model_id: None,
+            system_prompt: None,
             tags: vec![],
             capabilities: vec![],
             tools: vec![],
             initial_files: vec![],
+            network_access: None,
+            max_iterations: None,
         }
     }
 
@@ -769,6 +1030,27 @@ impl CreateSessionRequest {
         self
     }
 
+    /// Set the session-level system prompt
+    pub fn system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Run the session with a one-off agent instead of a stored one.
+    ///
+    /// Sets the session's system prompt, capabilities and model from
+    /// `agent` and clears `agent_id`, so nothing has to be created or
+    /// cleaned up on the agents API.
+    pub fn inline_agent(mut self, agent: InlineAgent) -> Self {
+        self.agent_id = None;
+        self.system_prompt = Some(agent.system_prompt);
+        self.capabilities = agent.capabilities;
+        if agent.model_id.is_some() {
+            self.model_id = agent.model_id;
+        }
+        self
+    }
+
     /// Set the tags
     pub fn tags(mut self, tags: Vec<String>) -> Self {
         self.tags = tags;
@@ -792,6 +1074,52 @@ impl CreateSessionRequest {
         self.initial_files = initial_files;
         self
     }
+
+    /// Restrict the hosts the session can reach
+    pub fn network_access(mut self, network_access: NetworkAccessList) -> Self {
+        self.network_access = Some(network_access);
+        self
+    }
+
+    /// Bound the LLM iterations (model calls) of each turn
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+}
+
+/// Agent definition used for a single session, see
+/// [`CreateSessionRequest::inline_agent`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct InlineAgent {
+    pub system_prompt: String,
+    pub capabilities: Vec<AgentCapabilityConfig>,
+    /// Model to use; the harness default when unset
+    pub model_id: Option<String>,
+}
+
+impl InlineAgent {
+    /// Create an inline agent with a system prompt
+    pub fn new(system_prompt: impl Into<String>) -> Self {
+        Self {
+            system_prompt: system_prompt.into(),
+            capabilities: vec![],
+            model_id: None,
+        }
+    }
+
+    /// Set the capabilities
+    pub fn capabilities(mut self, capabilities: Vec<AgentCapabilityConfig>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Set the model ID
+    pub fn model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = Some(model_id.into());
+        self
+    }
 }
 
 /// External actor identity for messages from external channels (Slack, Discord, etc.)
@@ -874,9 +1202,13 @@ pub enum ContentPart {
     Image {
         url: Option<String>,
         base64: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        media_type: Option<String>,
     },
     ImageFile {
         image_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
     },
     ToolCall {
         id: String,
@@ -896,6 +1228,33 @@ impl ContentPart {
         Self::Text { text: text.into() }
     }
 
+    /// Create an image content part from a URL
+    pub fn image_url(url: impl Into<String>) -> Self {
+        Self::Image {
+            url: Some(url.into()),
+            base64: None,
+            media_type: None,
+        }
+    }
+
+    /// Create an inline image content part from raw bytes
+    pub fn image_bytes(bytes: &[u8], media_type: impl Into<String>) -> Self {
+        use base64::Engine;
+        Self::Image {
+            url: None,
+            base64: Some(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            media_type: Some(media_type.into()),
+        }
+    }
+
+    /// Create a content part referencing an image uploaded via the images API
+    pub fn image_file(image_id: impl Into<String>) -> Self {
+        Self::ImageFile {
+            image_id: image_id.into(),
+            filename: None,
+        }
+    }
+
     /// Create a tool result content part with a successful result
     pub fn tool_result(tool_call_id: impl Into<String>, result: serde_json::Value) -> Self {
         Self::ToolResult {
@@ -1013,8 +1372,45 @@ impl CreateMessageRequest {
         self.external_actor = Some(actor);
         self
     }
+
+    /// Append a content part to the message
+    pub fn content_part(mut self, part: ContentPart) -> Self {
+        self.message.content.push(part);
+        self
+    }
+
+    /// Check controls and inline image sizes before sending.
+    ///
+    /// Inline base64 images larger than [`MAX_INLINE_IMAGE_BYTES`] fail with
+    /// [`Error::PayloadTooLarge`](crate::Error::PayloadTooLarge); upload them
+    /// through the images API and reference them with
+    /// [`ContentPart::image_file`] instead.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if let Some(controls) = &self.controls {
+            controls.validate()?;
+        }
+        for part in &self.message.content {
+            if let ContentPart::Image {
+                base64: Some(data), ..
+            } = part
+            {
+                // Decoded size; base64 encodes 3 bytes in 4 characters.
+                let size = data.trim_end_matches('=').len() * 3 / 4;
+                if size > MAX_INLINE_IMAGE_BYTES {
+                    return Err(crate::error::Error::PayloadTooLarge {
+                        size,
+                        limit: MAX_INLINE_IMAGE_BYTES,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
+/// Largest decoded size accepted for a base64 image embedded in a message
+pub const MAX_INLINE_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
 /// Input for creating a message
 #[derive(Debug, Clone, Serialize)]
 #[non_exhaustive]
@@ -1041,6 +1437,105 @@ impl MessageInput {
     pub fn tool_results(results: Vec<ContentPart>) -> Self {
         Self::new(MessageRole::ToolResult, results)
     }
+
+    /// Start building a user message from mixed content parts
+    pub fn builder() -> MessageInputBuilder {
+        MessageInputBuilder::default()
+    }
+}
+
+/// Builder for user messages with mixed text and image content
+///
+/// ```rust,no_run
+/// # fn main() -> Result<(), everruns_sdk::Error> {
+/// use everruns_sdk::MessageInput;
+///
+/// let input = MessageInput::builder()
+///     .text("look at this")
+///     .image_path("a.png")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageInputBuilder {
+    parts: Vec<PendingPart>,
+}
+
+#[derive(Debug, Clone)]
+enum PendingPart {
+    Ready(ContentPart),
+    ImagePath(std::path::PathBuf),
+}
+
+impl MessageInputBuilder {
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.part(ContentPart::text(text))
+    }
+
+    pub fn image_url(self, url: impl Into<String>) -> Self {
+        self.part(ContentPart::image_url(url))
+    }
+
+    pub fn image_bytes(self, bytes: &[u8], media_type: impl Into<String>) -> Self {
+        self.part(ContentPart::image_bytes(bytes, media_type))
+    }
+
+    /// Reference an image uploaded via the images API
+    pub fn image_file(self, image_id: impl Into<String>) -> Self {
+        self.part(ContentPart::image_file(image_id))
+    }
+
+    /// Inline an image read from disk when [`build`](Self::build) is called
+    pub fn image_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.parts.push(PendingPart::ImagePath(path.into()));
+        self
+    }
+
+    pub fn part(mut self, part: ContentPart) -> Self {
+        self.parts.push(PendingPart::Ready(part));
+        self
+    }
+
+    /// Read any image paths and assemble the message
+    pub fn build(self) -> crate::error::Result<MessageInput> {
+        let content = self
+            .parts
+            .into_iter()
+            .map(|part| match part {
+                PendingPart::Ready(part) => Ok(part),
+                PendingPart::ImagePath(path) => {
+                    let media_type = image_content_type(&path)?;
+                    let bytes = std::fs::read(&path).map_err(|err| {
+                        crate::error::Error::Validation(format!(
+                            "failed to read {}: {err}",
+                            path.display()
+                        ))
+                    })?;
+                    Ok(ContentPart::image_bytes(&bytes, media_type))
+                }
+            })
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        Ok(MessageInput::new(MessageRole::User, content))
+    }
+}
+
+/// Media type for an image file, inferred from its extension
+pub(crate) fn image_content_type(path: &std::path::Path) -> crate::error::Result<&'static str> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => Ok("image/png"),
+        Some("jpg" | "jpeg") => Ok("image/jpeg"),
+        Some("gif") => Ok("image/gif"),
+        Some("webp") => Ok("image/webp"),
+        _ => Err(crate::error::Error::Validation(format!(
+            "unsupported image type: {}",
+            path.display()
+        ))),
+    }
 }
 
 /// Controls for message generation
@@ -1088,17 +1583,55 @@ impl Controls {
         self.temperature = Some(temperature);
         self
     }
-}
 
-/// Paginated list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[non_exhaustive]
-pub struct ListResponse<T> {
-    pub data: Vec<T>,
-    #[serde(default)]
-    pub total: u64,
-    #[serde(default)]
-    pub offset: u64,
+    /// Preset for varied, exploratory output (temperature 1.0)
+    pub fn creative() -> Self {
+        Self::new().temperature(1.0)
+    }
+
+    /// Preset for repeatable output (temperature 0.0)
+    pub fn deterministic() -> Self {
+        Self::new().temperature(0.0)
+    }
+
+    /// Layer `overrides` on top of these controls; fields set in `overrides` win.
+    ///
+    /// Use this to combine session-wide defaults with per-message settings.
+    pub fn merge(self, overrides: &Controls) -> Self {
+        Self {
+            model_id: overrides.model_id.clone().or(self.model_id),
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+            temperature: overrides.temperature.or(self.temperature),
+        }
+    }
+
+    /// Check that values are within the ranges the API accepts.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if let Some(temperature) = self.temperature
+            && !(0.0..=2.0).contains(&temperature)
+        {
+            return Err(crate::error::Error::Validation(format!(
+                "temperature must be between 0 and 2, got {temperature}"
+            )));
+        }
+        if self.max_tokens == Some(0) {
+            return Err(crate::error::Error::Validation(
+                "max_tokens must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Paginated list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ListResponse<T> {
+    pub data: Vec<T>,
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub offset: u64,
     #[serde(default)]
     pub limit: u64,
 }
@@ -1115,6 +1648,9 @@ pub struct Event {
     pub data: serde_json::Value,
     #[serde(default)]
     pub context: EventContext,
+    /// Sequence number within the session (for ordering)
+    #[serde(default)]
+    pub sequence: Option<i32>,
 }
 
 impl Event {
@@ -1124,6 +1660,17 @@ impl Event {
     pub fn tool_calls(&self) -> Vec<ToolCallInfo<'_>> {
         extract_tool_calls(&self.data)
     }
+
+    /// The event's type as an [`EventKind`](crate::events::EventKind), for
+    /// matching that the compiler checks for exhaustiveness.
+    pub fn kind(&self) -> crate::events::EventKind<'_> {
+        crate::events::EventKind::parse(&self.event_type)
+    }
+
+    /// Deserialize the event's `data` payload into a typed struct.
+    pub fn data_as<T: serde::de::DeserializeOwned>(&self) -> crate::error::Result<T> {
+        Ok(T::deserialize(&self.data)?)
+    }
 }
 
 /// Extract tool call info from `tool.call_requested` or `output.message.completed` event data.
@@ -1171,6 +1718,153 @@ pub struct EventContext {
     pub turn_id: Option<String>,
     #[serde(default)]
     pub input_message_id: Option<String>,
+    /// Atom execution identifier
+    #[serde(default)]
+    pub exec_id: Option<String>,
+    /// OTel-style trace ID; for agent turns this is typically the turn ID
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    #[serde(default)]
+    pub span_id: Option<String>,
+    #[serde(default)]
+    pub parent_span_id: Option<String>,
+    /// Context keys not modeled above, kept so newer server fields survive
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+// --- Reasoning Event Models ---
+
+/// Data for a `reason.thinking.delta` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ThinkingDelta {
+    /// Turn this delta belongs to
+    pub turn_id: String,
+    /// New thinking text since the previous delta
+    pub delta: String,
+    /// Thinking text accumulated so far in the turn
+    pub accumulated: String,
+}
+
+// --- Tool Event Models ---
+
+/// Tool call issued by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// Data for a `tool.started` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ToolStartedData {
+    pub tool_call: ToolCall,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub narration: Option<String>,
+    /// Stable fingerprint of tool name and normalized arguments
+    #[serde(default)]
+    pub tool_call_fingerprint: Option<String>,
+}
+
+/// Data for a `tool.progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ToolProgressData {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    /// Interim status message (e.g. "Connecting to browser…")
+    pub message: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// Data for a `tool.output.delta` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ToolOutputDeltaData {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub delta: String,
+    /// Output stream identifier (e.g. `stdout`, `stderr`)
+    pub stream: String,
+}
+
+/// Data for a `tool.completed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ToolCompletedData {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub success: bool,
+    /// `success`, `error`, `timeout` or `cancelled`
+    pub status: String,
+    #[serde(default)]
+    pub result: Option<Vec<ContentPart>>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub narration: Option<String>,
+    #[serde(default)]
+    pub capability_id: Option<String>,
+    #[serde(default)]
+    pub capability_name: Option<String>,
+    #[serde(default)]
+    pub tool_call_fingerprint: Option<String>,
+    #[serde(default)]
+    pub tool_result_fingerprint: Option<String>,
+}
+
+// --- Context Compaction Event Models ---
+
+/// Reason why context compaction was triggered
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionReason {
+    ProactiveBudget,
+    RequestTooLarge,
+    Manual,
+}
+
+/// Data for a `context.compacting` event (compaction starting)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ContextCompactingData {
+    pub reason: CompactionReason,
+    /// Strategy requested (may differ from `strategy_used` once completed)
+    pub strategy: String,
+    pub messages_before: u64,
+}
+
+/// A single step in a compaction cascade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CompactionStepData {
+    pub strategy: String,
+    pub messages_after: u64,
+    pub duration_ms: u64,
+}
+
+/// Data for a `context.compacted` event (compaction completed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ContextCompactedData {
+    /// Combined strategy description (e.g. `observation_masking+native`)
+    pub strategy_used: String,
+    pub messages_before: u64,
+    pub messages_after: u64,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub steps: Vec<CompactionStepData>,
 }
 
 // --- Workspace Models ---
@@ -1781,6 +2475,30 @@ pub struct GuardrailExample {
     pub config: serde_json::Value,
 }
 
+impl GuardrailExample {
+    /// Capability config that adopts this preset on an agent or session
+    pub fn capability_config(&self) -> AgentCapabilityConfig {
+        AgentCapabilityConfig::guardrails(self.config.clone())
+    }
+}
+
+/// Data for an `output.message.replaced` event, emitted when a guardrail
+/// suppresses model output.
+///
+/// Clients should discard text accumulated for `turn_id` and show
+/// `replacement` instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct OutputMessageReplacedData {
+    pub turn_id: String,
+    /// Capability that contributed the guardrail
+    pub guardrail_capability_id: String,
+    pub guardrail_id: String,
+    /// Stable machine-readable reason (e.g. `system_prompt_leak`)
+    pub reason_code: String,
+    pub replacement: String,
+}
+
 // --- Budget Models ---
 
 /// Budget status
@@ -2014,13 +2732,13 @@ pub struct Connection {
 /// Request to set a connection API key
 #[derive(Debug, Clone, Serialize)]
 pub struct SetConnectionRequest {
-    pub api_key: String,
+    pub api_key: Sensitive<String>,
 }
 
 impl SetConnectionRequest {
     pub fn new(api_key: impl Into<String>) -> Self {
         Self {
-            api_key: api_key.into(),
+            api_key: Sensitive::new(api_key.into()),
         }
     }
 }
@@ -2030,19 +2748,653 @@ impl SetConnectionRequest {
 /// Request to batch-set session secrets
 #[derive(Debug, Clone, Serialize)]
 pub struct SetSecretsRequest {
-    pub secrets: std::collections::HashMap<String, String>,
+    /// Secret values by name; names stay visible in `Debug` output
+    pub secrets: std::collections::HashMap<String, Sensitive<String>>,
 }
 
 impl SetSecretsRequest {
     pub fn new(secrets: std::collections::HashMap<String, String>) -> Self {
-        Self { secrets }
+        Self {
+            secrets: secrets
+                .into_iter()
+                .map(|(name, value)| (name, Sensitive::new(value)))
+                .collect(),
+        }
+    }
+}
+
+/// Session secret metadata; values are write-only and never returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SecretInfo {
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Entry of a session's key-value storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct KeyValueInfo {
+    pub key: String,
+    pub value: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// --- User Models ---
+
+/// Member of the current organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct User {
+    pub id: String,
+    pub email: String,
+    pub name: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub auth_provider: Option<String>,
+}
+
+// --- Knowledge Base Models ---
+
+/// Knowledge base resource.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct KnowledgeBase {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Embedding model for hybrid retrieval (`None` = keyword search only)
+    #[serde(default)]
+    pub embedding_model_id: Option<String>,
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+}
+
+/// Request to create a knowledge base.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct CreateKnowledgeBaseRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_model_id: Option<String>,
+}
+
+impl CreateKnowledgeBaseRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            embedding_model_id: None,
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn embedding_model_id(mut self, embedding_model_id: impl Into<String>) -> Self {
+        self.embedding_model_id = Some(embedding_model_id.into());
+        self
+    }
+}
+
+/// Request to update a knowledge base.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct UpdateKnowledgeBaseRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_model_id: Option<String>,
+}
+
+impl UpdateKnowledgeBaseRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn embedding_model_id(mut self, embedding_model_id: impl Into<String>) -> Self {
+        self.embedding_model_id = Some(embedding_model_id.into());
+        self
+    }
+}
+
+/// Entry inside a knowledge base.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct KnowledgeEntry {
+    pub id: String,
+    pub kb_id: String,
+    pub title: String,
+    pub body: String,
+    /// One of `note`, `table`, `business`, `query`, `runbook`
+    pub kind: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request to create a knowledge base entry.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct CreateKnowledgeEntryRequest {
+    pub title: String,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl CreateKnowledgeEntryRequest {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            kind: None,
+            tags: None,
+        }
+    }
+
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+}
+
+/// Request to update a knowledge base entry.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct UpdateKnowledgeEntryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl UpdateKnowledgeEntryRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+}
+
+// --- Image Models ---
+
+/// Image metadata (without binary data)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ImageInfo {
+    pub id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    pub created_at: String,
+}
+
+/// Response from uploading an image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ImageUploadResponse {
+    pub id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+// --- Model Catalog Models ---
+
+/// Identifier for an LLM model, as accepted by `default_model_id` and
+/// [`Controls::model_id`].
+///
+/// The constants are aliases; resolve them to a concrete model with
+/// [`ModelsClient::resolve`](crate::client::ModelsClient::resolve) so agent
+/// definitions keep working when dated model versions are rotated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModelId(String);
+
+impl ModelId {
+    /// Newest Claude Sonnet model
+    pub const CLAUDE_SONNET_LATEST: &'static str = "claude-sonnet-latest";
+    /// Newest Claude Opus model
+    pub const CLAUDE_OPUS_LATEST: &'static str = "claude-opus-latest";
+    /// Newest Claude Haiku model
+    pub const CLAUDE_HAIKU_LATEST: &'static str = "claude-haiku-latest";
+
+    /// Create a model ID from a string
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// The ID as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Model family prefix if this is a `<family>-latest` alias
+    pub fn alias_family(&self) -> Option<&str> {
+        self.0.strip_suffix("-latest")
+    }
+}
+
+impl std::fmt::Display for ModelId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for ModelId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<String> for ModelId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ModelId> for String {
+    fn from(id: ModelId) -> Self {
+        id.0
+    }
+}
+
+impl AsRef<str> for ModelId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// LLM model with provider info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ModelWithProvider {
+    pub id: String,
+    pub provider_id: String,
+    /// Provider-side model identifier as sent on the wire (e.g. `gpt-4o`)
+    pub model_id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    pub enabled: bool,
+    /// How the model was added (`manual`, `discovered`, `predefined`)
+    pub source: String,
+    #[serde(default)]
+    pub provider_name: String,
+    #[serde(default)]
+    pub provider_type: String,
+    /// Provider is active and configured
+    #[serde(default)]
+    pub healthy: bool,
+    /// Capabilities and limits from the model catalog, when known
+    #[serde(default)]
+    pub profile: Option<ModelProfile>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Catalog profile of a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ModelProfile {
+    pub name: String,
+    /// Model family (e.g. `gpt-4o`, `claude-3-5-sonnet`)
+    pub family: String,
+    #[serde(default)]
+    pub limits: Option<ModelLimits>,
+    #[serde(default)]
+    pub reasoning: bool,
+    #[serde(default)]
+    pub tool_call: bool,
+}
+
+/// Token limits of a model
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ModelLimits {
+    /// Context window size in tokens
+    pub context: u32,
+    /// Maximum output tokens
+    pub output: u32,
+    /// Maximum input tokens, when not `context - output`
+    #[serde(default)]
+    pub input: Option<u32>,
+}
+
+impl ModelLimits {
+    /// Tokens available for the prompt, leaving room for a full response
+    pub fn max_input(&self) -> u32 {
+        self.input
+            .unwrap_or(self.context.saturating_sub(self.output))
+    }
+}
+
+// --- Voice Models ---
+
+/// Realtime-session options shared by the voice requests that open a connection.
+///
+/// Omitted fields fall back to the agent's or provider's default.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct VoiceSessionOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+}
+
+impl VoiceSessionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn reasoning_effort(mut self, reasoning_effort: impl Into<String>) -> Self {
+        self.reasoning_effort = Some(reasoning_effort.into());
+        self
+    }
+
+    pub fn voice(mut self, voice: impl Into<String>) -> Self {
+        self.voice = Some(voice.into());
+        self
+    }
+}
+
+/// Request to start a WebRTC voice call from an SDP offer
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct VoiceCallRequest {
+    pub sdp: String,
+    #[serde(flatten)]
+    pub options: VoiceSessionOptions,
+}
+
+impl VoiceCallRequest {
+    pub fn new(sdp: impl Into<String>) -> Self {
+        Self {
+            sdp: sdp.into(),
+            options: VoiceSessionOptions::default(),
+        }
+    }
+
+    pub fn options(mut self, options: VoiceSessionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// Request to attach an external provider call to a session
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct VoiceAttachRequest {
+    pub provider_call_id: String,
+    #[serde(flatten)]
+    pub options: VoiceSessionOptions,
+}
+
+impl VoiceAttachRequest {
+    pub fn new(provider_call_id: impl Into<String>) -> Self {
+        Self {
+            provider_call_id: provider_call_id.into(),
+            options: VoiceSessionOptions::default(),
+        }
+    }
+
+    pub fn options(mut self, options: VoiceSessionOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// Voice call connection details, including the SDP answer for the WebRTC handshake
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceCall {
+    pub voice_connection_id: String,
+    pub provider: String,
+    pub model: String,
+    pub voice: String,
+    pub reasoning_effort: String,
+    pub expires_at: String,
+    pub answer_sdp: String,
+    #[serde(default)]
+    pub provider_call_id: Option<String>,
+}
+
+/// Session plus the voice call opened on it in the same round trip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceSession {
+    pub session: Session,
+    pub voice: VoiceCall,
+}
+
+/// Ephemeral credential for connecting directly to the realtime provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceClientSecret {
+    pub voice_connection_id: String,
+    pub provider: String,
+    pub model: String,
+    pub voice: String,
+    pub reasoning_effort: String,
+    /// Must be used to connect before this time
+    pub expires_at: String,
+    /// Provider-specific credential payload
+    pub client_secret: Sensitive<serde_json::Value>,
+}
+
+/// Voice connection attached to an external provider call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceAttachment {
+    pub voice_connection_id: String,
+    pub provider_call_id: String,
+    pub provider: String,
+    pub model: String,
+    pub voice: String,
+    pub reasoning_effort: String,
+    pub expires_at: String,
+}
+
+/// Request to end a voice connection
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct VoiceEndRequest {
+    /// Free-text reason recorded with the `voice.session.ended` event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl VoiceEndRequest {
+    pub fn new() -> Self {
+        Self::default()
     }
+
+    pub fn reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// Result of ending a voice connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceEndResponse {
+    pub voice_connection_id: String,
+    pub status: String,
+}
+
+/// Data for a `voice.session.started` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceSessionStartedData {
+    pub voice_connection_id: String,
+    pub model: String,
+    pub voice: String,
+    pub reasoning_effort: String,
+    /// One of `webrtc`, `sip`, `websocket`
+    pub transport: String,
+}
+
+/// Data for a `voice.session.ended` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceSessionEndedData {
+    pub voice_connection_id: String,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Data for a `voice.session.failed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceSessionFailedData {
+    pub voice_connection_id: String,
+    pub error: String,
+}
+
+/// Data for voice transcript delta and completed events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VoiceTranscriptData {
+    pub voice_connection_id: String,
+    /// Newly transcribed text; empty for events that only mark completion
+    #[serde(default)]
+    pub delta: String,
+    /// Transcript accumulated for this item so far
+    pub accumulated: String,
+    /// `user_partial`, `user_final`, `assistant_partial` or `assistant_final`
+    #[serde(default)]
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub item_id: Option<String>,
+    #[serde(default)]
+    pub response_id: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn controls_merge_prefers_overrides() {
+        let defaults = Controls::deterministic()
+            .model_id("model-a")
+            .max_tokens(512);
+        let merged = defaults.merge(&Controls::new().temperature(0.7));
+        assert_eq!(merged.model_id.as_deref(), Some("model-a"));
+        assert_eq!(merged.max_tokens, Some(512));
+        assert_eq!(merged.temperature, Some(0.7));
+    }
+
+    #[test]
+    fn create_message_request_validate_rejects_oversized_inline_image() {
+        let image = |len: usize| ContentPart::Image {
+            url: None,
+            base64: Some("A".repeat(len)),
+            media_type: None,
+        };
+        let small = CreateMessageRequest::user_text("look").content_part(image(1024));
+        assert!(small.validate().is_ok());
+
+        let large = CreateMessageRequest::user_text("look")
+            .content_part(image(MAX_INLINE_IMAGE_BYTES / 3 * 4 + 8));
+        assert!(matches!(
+            large.validate(),
+            Err(crate::error::Error::PayloadTooLarge { limit, .. }) if limit == MAX_INLINE_IMAGE_BYTES
+        ));
+    }
+
+    #[test]
+    fn controls_validate_rejects_out_of_range_values() {
+        assert!(Controls::creative().validate().is_ok());
+        assert!(Controls::deterministic().max_tokens(1).validate().is_ok());
+        assert!(Controls::new().temperature(2.5).validate().is_err());
+        assert!(Controls::new().temperature(-0.1).validate().is_err());
+        assert!(Controls::new().temperature(f32::NAN).validate().is_err());
+        assert!(Controls::new().max_tokens(0).validate().is_err());
+    }
+
     #[test]
     fn list_response_deserializes_without_pagination_fields() {
         let json = r#"{"data": [1, 2, 3]}"#;
@@ -0,0 +1,395 @@
+//! Conversion to and from OpenAI chat-completion messages.
+//!
+//! [`to_chat_messages`] turns session history into the `messages` array of
+//! an OpenAI-style chat completion request, for replaying a conversation
+//! against another provider or feeding existing evaluation tooling.
+//! [`from_chat_messages`] goes the other way, for datasets and transcripts
+//! recorded in that format:
+//!
+//! ```no_run
+//! use everruns_sdk::openai::{ChatMessage, to_chat_messages};
+//!
+//! # async fn example(client: everruns_sdk::Everruns) -> Result<(), everruns_sdk::Error> {
+//! let history = client.messages().list("sess_123").await?.data;
+//! let chat: Vec<ChatMessage> = to_chat_messages(&history);
+//! println!("{}", serde_json::to_string_pretty(&chat)?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The formats do not map one to one. Images uploaded through the images
+//! API have no URL to export and are dropped, as is agent thinking. Tool
+//! errors export as the tool message's content, and system messages import
+//! as a system prompt rather than as messages.
+
+use crate::error::{Error, Result};
+use crate::models::{ContentPart, Message, MessageInput, MessageRole};
+use serde::{Deserialize, Serialize};
+
+/// Author of a [`ChatMessage`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    System,
+    Developer,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// One entry of a chat completion `messages` array
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    /// Text, or text and image parts; `null` for tool-call-only assistant
+    /// messages
+    #[serde(default)]
+    pub content: Option<ChatContent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ChatToolCall>,
+    /// Tool call answered by a `tool` message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: Some(ChatContent::Text(content.into())),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
+    /// Text of the message; text parts are concatenated
+    pub fn text(&self) -> String {
+        match &self.content {
+            None => String::new(),
+            Some(ChatContent::Text(text)) => text.clone(),
+            Some(ChatContent::Parts(parts)) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ChatContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Message content: a string, or an array of parts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    Text(String),
+    Parts(Vec<ChatContentPart>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ChatImageUrl },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ChatImageUrl {
+    /// `https:` or `data:` URL
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Function call requested by an assistant message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ChatToolCall {
+    pub id: String,
+    /// Always `function`
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: ChatFunctionCall,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ChatFunctionCall {
+    pub name: String,
+    /// Arguments as a JSON-encoded string
+    pub arguments: String,
+}
+
+/// Chat messages converted back into session message inputs
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ChatHistory {
+    /// `system` and `developer` messages, joined by blank lines
+    pub system_prompt: Option<String>,
+    pub messages: Vec<MessageInput>,
+}
+
+/// Convert session messages to chat completion messages.
+///
+/// Each tool result becomes its own `tool` message, as the chat format
+/// requires.
+pub fn to_chat_messages(messages: &[Message]) -> Vec<ChatMessage> {
+    let mut chat = Vec::new();
+    for message in messages {
+        match message.role {
+            MessageRole::ToolResult => {
+                for part in &message.content {
+                    if let ContentPart::ToolResult {
+                        tool_call_id,
+                        result,
+                        error,
+                    } = part
+                    {
+                        let content = match (error, result) {
+                            (Some(error), _) => error.clone(),
+                            (None, Some(serde_json::Value::String(text))) => text.clone(),
+                            (None, Some(value)) => value.to_string(),
+                            (None, None) => String::new(),
+                        };
+                        let mut tool = ChatMessage::new(ChatRole::Tool, content);
+                        tool.tool_call_id = Some(tool_call_id.clone());
+                        chat.push(tool);
+                    }
+                }
+            }
+            MessageRole::User | MessageRole::Agent => {
+                let role = match message.role {
+                    MessageRole::User => ChatRole::User,
+                    _ => ChatRole::Assistant,
+                };
+                chat.push(export_message(role, &message.content));
+            }
+        }
+    }
+    chat
+}
+
+fn export_message(role: ChatRole, content: &[ContentPart]) -> ChatMessage {
+    let mut parts = Vec::new();
+    let mut tool_calls = Vec::new();
+    for part in content {
+        match part {
+            ContentPart::Text { text } => parts.push(ChatContentPart::Text { text: text.clone() }),
+            ContentPart::Image {
+                url,
+                base64,
+                media_type,
+            } => {
+                let url = match (url, base64) {
+                    (Some(url), _) => url.clone(),
+                    (None, Some(data)) => {
+                        let media_type = media_type.as_deref().unwrap_or("image/png");
+                        format!("data:{media_type};base64,{data}")
+                    }
+                    (None, None) => continue,
+                };
+                parts.push(ChatContentPart::ImageUrl {
+                    image_url: ChatImageUrl { url, detail: None },
+                });
+            }
+            ContentPart::ToolCall {
+                id,
+                name,
+                arguments,
+            } => tool_calls.push(ChatToolCall {
+                id: id.clone(),
+                kind: function_type(),
+                function: ChatFunctionCall {
+                    name: name.clone(),
+                    arguments: arguments.to_string(),
+                },
+            }),
+            ContentPart::ImageFile { .. } | ContentPart::ToolResult { .. } => {}
+        }
+    }
+    let content = match parts.as_slice() {
+        [] if !tool_calls.is_empty() => None,
+        [] => Some(ChatContent::Text(String::new())),
+        parts
+            if parts
+                .iter()
+                .all(|p| matches!(p, ChatContentPart::Text { .. })) =>
+        {
+            let text = parts
+                .iter()
+                .filter_map(|p| match p {
+                    ChatContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            Some(ChatContent::Text(text))
+        }
+        _ => Some(ChatContent::Parts(parts)),
+    };
+    ChatMessage {
+        role,
+        content,
+        tool_calls,
+        tool_call_id: None,
+    }
+}
+
+/// Convert chat completion messages to session message inputs.
+///
+/// Consecutive `tool` messages merge into one tool result message. Tool
+/// message content that parses as JSON is kept as JSON. `data:` image URLs
+/// become inline images.
+pub fn from_chat_messages(messages: &[ChatMessage]) -> Result<ChatHistory> {
+    let mut history = ChatHistory::default();
+    for message in messages {
+        match message.role {
+            ChatRole::System | ChatRole::Developer => {
+                let prompt = history.system_prompt.get_or_insert_default();
+                if !prompt.is_empty() {
+                    prompt.push_str("\n\n");
+                }
+                prompt.push_str(&message.text());
+            }
+            ChatRole::Tool => {
+                let tool_call_id = message.tool_call_id.clone().ok_or_else(|| {
+                    Error::Validation("tool message is missing tool_call_id".to_string())
+                })?;
+                let text = message.text();
+                let result = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+                let part = ContentPart::tool_result(tool_call_id, result);
+                match history.messages.last_mut() {
+                    Some(last) if matches!(last.role, MessageRole::ToolResult) => {
+                        last.content.push(part)
+                    }
+                    _ => history
+                        .messages
+                        .push(MessageInput::tool_results(vec![part])),
+                }
+            }
+            ChatRole::User | ChatRole::Assistant => {
+                let role = match message.role {
+                    ChatRole::User => MessageRole::User,
+                    _ => MessageRole::Agent,
+                };
+                history
+                    .messages
+                    .push(MessageInput::new(role, import_content(message)?));
+            }
+        }
+    }
+    Ok(history)
+}
+
+fn import_content(message: &ChatMessage) -> Result<Vec<ContentPart>> {
+    let mut content = Vec::new();
+    match &message.content {
+        None => {}
+        Some(ChatContent::Text(text)) => content.push(ContentPart::text(text.clone())),
+        Some(ChatContent::Parts(parts)) => {
+            for part in parts {
+                content.push(match part {
+                    ChatContentPart::Text { text } => ContentPart::text(text.clone()),
+                    ChatContentPart::ImageUrl { image_url } => import_image(&image_url.url),
+                });
+            }
+        }
+    }
+    for call in &message.tool_calls {
+        let arguments = serde_json::from_str(&call.function.arguments).map_err(|e| {
+            Error::Validation(format!(
+                "tool call '{}' has invalid JSON arguments: {e}",
+                call.id
+            ))
+        })?;
+        content.push(ContentPart::ToolCall {
+            id: call.id.clone(),
+            name: call.function.name.clone(),
+            arguments,
+        });
+    }
+    Ok(content)
+}
+
+fn import_image(url: &str) -> ContentPart {
+    if let Some(rest) = url.strip_prefix("data:")
+        && let Some((media_type, data)) = rest.split_once(";base64,")
+    {
+        return ContentPart::Image {
+            url: None,
+            base64: Some(data.to_string()),
+            media_type: Some(media_type.to_string()),
+        };
+    }
+    ContentPart::image_url(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_round_trips_through_chat_format() {
+        let messages: Vec<Message> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "msg_1", "session_id": "sess_1", "sequence": 1, "role": "user",
+                "content": [
+                    {"type": "text", "text": "What is in this chart?"},
+                    {"type": "image", "url": null, "base64": "iVBOR", "media_type": "image/png"}
+                ],
+                "created_at": "2024-01-01T00:00:00Z"
+            },
+            {
+                "id": "msg_2", "session_id": "sess_1", "sequence": 2, "role": "agent",
+                "content": [{"type": "tool_call", "id": "call_1", "name": "read_chart", "arguments": {"page": 1}}],
+                "created_at": "2024-01-01T00:00:00Z"
+            },
+            {
+                "id": "msg_3", "session_id": "sess_1", "sequence": 3, "role": "tool_result",
+                "content": [{"type": "tool_result", "tool_call_id": "call_1", "result": {"bars": 3}, "error": null}],
+                "created_at": "2024-01-01T00:00:00Z"
+            },
+            {
+                "id": "msg_4", "session_id": "sess_1", "sequence": 4, "role": "agent",
+                "content": [{"type": "text", "text": "Three bars."}],
+                "created_at": "2024-01-01T00:00:00Z"
+            }
+        ]))
+        .unwrap();
+
+        let chat = to_chat_messages(&messages);
+        assert_eq!(
+            serde_json::to_value(&chat).unwrap(),
+            serde_json::json!([
+                {"role": "user", "content": [
+                    {"type": "text", "text": "What is in this chart?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBOR"}}
+                ]},
+                {"role": "assistant", "content": null, "tool_calls": [
+                    {"id": "call_1", "type": "function",
+                     "function": {"name": "read_chart", "arguments": "{\"page\":1}"}}
+                ]},
+                {"role": "tool", "content": "{\"bars\":3}", "tool_call_id": "call_1"},
+                {"role": "assistant", "content": "Three bars."}
+            ])
+        );
+
+        let mut with_system = vec![ChatMessage::new(ChatRole::System, "Be brief.")];
+        with_system.extend(chat);
+        let history = from_chat_messages(&with_system).unwrap();
+        assert_eq!(history.system_prompt.as_deref(), Some("Be brief."));
+        let original: Vec<serde_json::Value> = messages
+            .iter()
+            .map(|m| serde_json::json!({"role": m.role, "content": m.content}))
+            .collect();
+        assert_eq!(
+            serde_json::to_value(&history.messages).unwrap(),
+            serde_json::json!(original)
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Export session events as OpenTelemetry traces.
+//!
+//! Each session becomes one trace: turns are root spans and tool calls are
+//! child spans of the turn that issued them, with token usage recorded as
+//! `gen_ai.usage.*` attributes. Traces are sent as OTLP/JSON over HTTP, which
+//! any OTLP collector (Jaeger, Tempo, the OpenTelemetry Collector) accepts on
+//! its `/v1/traces` endpoint.
+
+use crate::client::{Everruns, ListEventsOptions};
+use crate::error::{Error, Result};
+use crate::events::types;
+use crate::models::Event;
+use crate::pagination::PaginationOptions;
+use futures::TryStreamExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use url::Url;
+
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// Sends session traces to an OTLP/HTTP collector.
+#[derive(Debug, Clone)]
+pub struct OtlpExporter {
+    http: reqwest::Client,
+    traces_url: Url,
+    service_name: String,
+}
+
+impl OtlpExporter {
+    /// Create an exporter for a collector base URL such as `http://localhost:4318`.
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let base = Url::parse(endpoint)?;
+        Ok(Self {
+            http: reqwest::Client::new(),
+            traces_url: base.join("v1/traces")?,
+            service_name: "everruns".to_string(),
+        })
+    }
+
+    /// Set the `service.name` resource attribute (default `everruns`)
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = name.into();
+        self
+    }
+
+    /// Convert `events` to spans and send them to the collector.
+    pub async fn export(&self, events: &[Event]) -> Result<()> {
+        let body = session_trace(events, &self.service_name);
+        let resp = self
+            .http
+            .post(self.traces_url.clone())
+            .json(&body)
+            .send()
+            .await?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(Error::from_response(resp).await)
+        }
+    }
+
+    /// Fetch every event of a session and export it as one trace.
+    pub async fn export_session(&self, client: &Everruns, session_id: &str) -> Result<()> {
+        let events: Vec<Event> = client
+            .events()
+            .list_all(
+                session_id,
+                &ListEventsOptions::default(),
+                PaginationOptions::new().with_page_size(500),
+            )
+            .try_collect()
+            .await?;
+        self.export(&events).await
+    }
+}
+
+/// Build an OTLP/JSON `ExportTraceServiceRequest` from session events.
+///
+/// Events may be in any order. Spans left open (a turn still running, a tool
+/// that never completed) end at the last event's timestamp.
+pub fn session_trace(events: &[Event], service_name: &str) -> Value {
+    let mut events: Vec<&Event> = events.iter().collect();
+    events.sort_by_key(|event| (event.sequence, parse_rfc3339_nanos(&event.ts)));
+    let last_ts = events
+        .iter()
+        .filter_map(|event| parse_rfc3339_nanos(&event.ts))
+        .max()
+        .unwrap_or_default();
+
+    let mut spans: Vec<SpanBuilder> = Vec::new();
+    let mut turns: HashMap<String, usize> = HashMap::new();
+    let mut tools: HashMap<String, usize> = HashMap::new();
+    let mut trace_id = None;
+
+    for event in events {
+        let trace = *trace_id.get_or_insert_with(|| trace_id_for(&event.session_id));
+        let ts = parse_rfc3339_nanos(&event.ts).unwrap_or(last_ts);
+        let turn_id = event
+            .context
+            .turn_id
+            .clone()
+            .or_else(|| str_field(&event.data, "turn_id").map(str::to_string));
+        match event.event_type.as_str() {
+            types::TURN_STARTED => {
+                let Some(turn_id) = turn_id else { continue };
+                let mut span = SpanBuilder::new(trace, &turn_id, "turn", ts, None);
+                span.attr_str("everruns.session_id", &event.session_id);
+                span.attr_str("everruns.turn_id", &turn_id);
+                if let Some(input) = str_field(&event.data, "input_message_id") {
+                    span.attr_str("everruns.input_message_id", input);
+                }
+                turns.insert(turn_id, spans.len());
+                spans.push(span);
+            }
+            types::TURN_COMPLETED | types::TURN_FAILED | types::TURN_CANCELLED => {
+                let Some(&index) = turn_id.as_ref().and_then(|id| turns.get(id)) else {
+                    continue;
+                };
+                let span = &mut spans[index];
+                span.end = Some(ts);
+                if let Some(usage) = event.data.get("usage").filter(|u| !u.is_null()) {
+                    for (key, attr) in [
+                        ("input_tokens", "gen_ai.usage.input_tokens"),
+                        ("output_tokens", "gen_ai.usage.output_tokens"),
+                        ("cache_read_tokens", "gen_ai.usage.cache_read_tokens"),
+                    ] {
+                        if let Some(n) = usage.get(key).and_then(Value::as_i64) {
+                            span.attr_int(attr, n);
+                        }
+                    }
+                }
+                match event.event_type.as_str() {
+                    types::TURN_FAILED => span.status(
+                        STATUS_ERROR,
+                        str_field(&event.data, "error").unwrap_or("turn failed"),
+                    ),
+                    types::TURN_CANCELLED => span.status(STATUS_ERROR, "cancelled"),
+                    _ => span.status(STATUS_OK, ""),
+                }
+            }
+            types::TOOL_STARTED => {
+                let call = event.data.get("tool_call");
+                let (Some(call_id), Some(name)) = (
+                    call.and_then(|c| str_field(c, "id")),
+                    call.and_then(|c| str_field(c, "name")),
+                ) else {
+                    continue;
+                };
+                let parent = turn_id
+                    .as_ref()
+                    .and_then(|id| turns.get(id))
+                    .map(|&i| spans[i].span_id);
+                let mut span = SpanBuilder::new(trace, call_id, name, ts, parent);
+                span.attr_str("gen_ai.tool.name", name);
+                span.attr_str("gen_ai.tool.call.id", call_id);
+                tools.insert(call_id.to_string(), spans.len());
+                spans.push(span);
+            }
+            types::TOOL_COMPLETED => {
+                let Some(&index) =
+                    str_field(&event.data, "tool_call_id").and_then(|id| tools.get(id))
+                else {
+                    continue;
+                };
+                let span = &mut spans[index];
+                span.end = Some(ts);
+                if event.data.get("success").and_then(Value::as_bool) == Some(false) {
+                    let message = str_field(&event.data, "error")
+                        .or_else(|| str_field(&event.data, "status"))
+                        .unwrap_or("tool failed");
+                    span.status(STATUS_ERROR, message);
+                } else {
+                    span.status(STATUS_OK, "");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [string_attr("service.name", service_name)]
+            },
+            "scopeSpans": [{
+                "scope": {"name": "everruns-sdk", "version": env!("CARGO_PKG_VERSION")},
+                "spans": spans.into_iter().map(|span| span.finish(last_ts)).collect::<Vec<_>>()
+            }]
+        }]
+    })
+}
+
+struct SpanBuilder {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    name: String,
+    start: u64,
+    end: Option<u64>,
+    attributes: Vec<Value>,
+    status: Option<(u8, String)>,
+}
+
+impl SpanBuilder {
+    fn new(trace_id: u128, key: &str, name: &str, start: u64, parent: Option<u64>) -> Self {
+        Self {
+            trace_id,
+            span_id: fnv1a(key.as_bytes(), FNV_OFFSET),
+            parent_span_id: parent,
+            name: name.to_string(),
+            start,
+            end: None,
+            attributes: Vec::new(),
+            status: None,
+        }
+    }
+
+    fn attr_str(&mut self, key: &str, value: &str) {
+        self.attributes.push(string_attr(key, value));
+    }
+
+    fn attr_int(&mut self, key: &str, value: i64) {
+        // OTLP/JSON encodes 64-bit integers as strings.
+        self.attributes
+            .push(json!({"key": key, "value": {"intValue": value.to_string()}}));
+    }
+
+    fn status(&mut self, code: u8, message: &str) {
+        self.status = Some((code, message.to_string()));
+    }
+
+    fn finish(self, fallback_end: u64) -> Value {
+        let mut span = json!({
+            "traceId": format!("{:032x}", self.trace_id),
+            "spanId": format!("{:016x}", self.span_id),
+            "name": self.name,
+            "kind": SPAN_KIND_INTERNAL,
+            "startTimeUnixNano": self.start.to_string(),
+            "endTimeUnixNano": self.end.unwrap_or(fallback_end).max(self.start).to_string(),
+            "attributes": self.attributes,
+        });
+        if let Some(parent) = self.parent_span_id {
+            span["parentSpanId"] = json!(format!("{parent:016x}"));
+        }
+        if let Some((code, message)) = self.status {
+            span["status"] = json!({"code": code, "message": message});
+        }
+        span
+    }
+}
+
+fn string_attr(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Stable 64-bit FNV-1a hash, so re-exporting a session yields the same IDs.
+fn fnv1a(bytes: &[u8], seed: u64) -> u64 {
+    bytes.iter().fold(seed, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn trace_id_for(session_id: &str) -> u128 {
+    let high = fnv1a(session_id.as_bytes(), FNV_OFFSET);
+    let low = fnv1a(session_id.as_bytes(), !FNV_OFFSET);
+    (u128::from(high) << 64) | u128::from(low)
+}
+
+/// Parse an RFC 3339 timestamp into nanoseconds since the Unix epoch.
+fn parse_rfc3339_nanos(ts: &str) -> Option<u64> {
+    let bytes = ts.as_bytes();
+    let num = |range: std::ops::Range<usize>| -> Option<i64> { ts.get(range)?.parse().ok() };
+    if bytes.len() < 20 || bytes[4] != b'-' || bytes[10] != b'T' && bytes[10] != b' ' {
+        return None;
+    }
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+
+    let mut rest = &ts[19..];
+    let mut nanos = 0i64;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        let padded = format!("{:0<9}", &fraction[..digits.min(9)]);
+        nanos = padded.parse().ok()?;
+        rest = &fraction[digits..];
+    }
+    let offset_secs = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            sign * (rest.get(1..3)?.parse::<i64>().ok()? * 3600
+                + rest.get(4..6)?.parse::<i64>().ok()? * 60)
+        }
+        _ => return None,
+    };
+
+    // Days since the epoch for a proleptic Gregorian date (Howard Hinnant).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset_secs;
+    u64::try_from(secs * 1_000_000_000 + nanos).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rfc3339_timestamps() {
+        assert_eq!(parse_rfc3339_nanos("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_nanos("2024-01-15T10:30:00.250Z"),
+            Some(1_705_314_600_250_000_000)
+        );
+        assert_eq!(
+            parse_rfc3339_nanos("2024-01-15T12:30:00+02:00"),
+            Some(1_705_314_600_000_000_000)
+        );
+        assert_eq!(parse_rfc3339_nanos("not a timestamp"), None);
+    }
+}
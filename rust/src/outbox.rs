@@ -0,0 +1,172 @@
+//! Durable, disk-backed queue for outgoing messages.
+//!
+//! [`DurableSender`] writes each [`CreateMessageRequest`] to a spool directory
+//! before sending it, so messages composed while offline survive network
+//! blips and process restarts. Call [`DurableSender::flush`] when connectivity
+//! returns, or [`DurableSender::run`] to retry on an interval.
+//!
+//! Delivery is at-least-once: if the server accepts a message but the
+//! response is lost, the message is sent again on the next flush.
+
+use crate::client::Everruns;
+use crate::error::{Error, Result};
+use crate::models::{CreateMessageRequest, Message};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const PENDING_EXT: &str = "json";
+const FAILED_EXT: &str = "failed";
+
+/// Queued message as stored on disk
+#[derive(serde::Serialize, serde::Deserialize)]
+struct QueuedMessage {
+    session_id: String,
+    request: serde_json::Value,
+}
+
+/// Sends messages through an on-disk queue that is retried until delivered.
+pub struct DurableSender {
+    client: Everruns,
+    dir: PathBuf,
+    seq: AtomicU64,
+}
+
+impl DurableSender {
+    /// Create a sender that spools messages into `dir`, creating it if needed.
+    ///
+    /// Messages left in `dir` by a previous process are picked up by the
+    /// next [`flush`](Self::flush).
+    pub async fn new(client: Everruns, dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        tokio::fs::create_dir_all(&dir).await.map_err(io_error)?;
+        Ok(Self {
+            client,
+            dir,
+            seq: AtomicU64::new(0),
+        })
+    }
+
+    /// Directory holding queued messages
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Persist a message and try to deliver the queue.
+    ///
+    /// Returns `Ok` once the message is safely on disk, even if it could not
+    /// be sent yet; it stays queued for the next flush.
+    pub async fn send(&self, session_id: &str, req: CreateMessageRequest) -> Result<()> {
+        self.enqueue(session_id, req).await?;
+        match self.flush().await {
+            Ok(_) => Ok(()),
+            Err(err) if is_retryable(&err) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Persist a message without attempting delivery.
+    ///
+    /// The client's redactor runs here, so redacted content never reaches
+    /// the spool directory.
+    pub async fn enqueue(&self, session_id: &str, mut req: CreateMessageRequest) -> Result<()> {
+        req.validate()?;
+        self.client.redact(session_id, &mut req.message.content);
+        let queued = QueuedMessage {
+            session_id: session_id.to_string(),
+            request: serde_json::to_value(&req)?,
+        };
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        // Zero-padded so lexicographic order is submission order.
+        let name = format!("{nanos:024}-{seq:08}");
+        let tmp = self.dir.join(format!("{name}.tmp"));
+        tokio::fs::write(&tmp, serde_json::to_vec(&queued)?)
+            .await
+            .map_err(io_error)?;
+        tokio::fs::rename(&tmp, self.dir.join(format!("{name}.{PENDING_EXT}")))
+            .await
+            .map_err(io_error)
+    }
+
+    /// Number of messages waiting to be delivered
+    pub async fn pending(&self) -> Result<usize> {
+        Ok(self.queued_files(PENDING_EXT).await?.len())
+    }
+
+    /// Deliver queued messages in submission order.
+    ///
+    /// Stops at the first network error, 429 or 5xx response, leaving the rest
+    /// queued. Messages the server rejects outright are renamed to
+    /// `*.failed` so they do not block the queue. Returns the messages sent.
+    pub async fn flush(&self) -> Result<Vec<Message>> {
+        let mut sent = Vec::new();
+        for path in self.queued_files(PENDING_EXT).await? {
+            let bytes = tokio::fs::read(&path).await.map_err(io_error)?;
+            let queued: QueuedMessage = serde_json::from_slice(&bytes)?;
+            let result = self
+                .client
+                .post::<Message, _>(
+                    &format!("/sessions/{}/messages", queued.session_id),
+                    &queued.request,
+                )
+                .await;
+            match result {
+                Ok(message) => {
+                    tokio::fs::remove_file(&path).await.map_err(io_error)?;
+                    sent.push(message);
+                }
+                Err(err) if is_retryable(&err) => return Err(err),
+                Err(err) => {
+                    tracing::warn!(path = %path.display(), error = %err, "dropping undeliverable message");
+                    tokio::fs::rename(&path, path.with_extension(FAILED_EXT))
+                        .await
+                        .map_err(io_error)?;
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Flush the queue every `interval`, forever.
+    ///
+    /// Intended to be spawned as a background task; transient failures are
+    /// logged and retried on the next tick.
+    pub async fn run(&self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = self.flush().await {
+                tracing::debug!(error = %err, "message queue flush deferred");
+            }
+        }
+    }
+
+    async fn queued_files(&self, ext: &str) -> Result<Vec<PathBuf>> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await.map_err(io_error)?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == ext) {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+fn is_retryable(err: &Error) -> bool {
+    match err {
+        Error::Network(_) | Error::RateLimited { .. } => true,
+        Error::Api { status, .. } => *status >= 500,
+        _ => false,
+    }
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::Validation(format!("message queue I/O error: {err}"))
+}
@@ -0,0 +1,180 @@
+//! Auto-paginating list streams with page prefetching.
+//!
+//! Pages are fetched by a background task that runs ahead of the consumer
+//! (bounded by `lookahead`), so the next page is usually already in memory
+//! by the time the current one has been consumed.
+
+use crate::error::Result;
+use futures::stream::Stream;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Default number of items requested per page.
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+/// Default number of pages fetched ahead of the consumer.
+pub const DEFAULT_LOOKAHEAD: usize = 1;
+
+/// Boxed stream of items produced by an auto-paginating list call.
+pub type PageStream<T> = Pin<Box<dyn Stream<Item = Result<T>> + Send>>;
+
+/// Options for auto-paginating list streams
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PaginationOptions {
+    /// Number of items requested per page
+    pub page_size: u32,
+    /// Number of pages fetched ahead of the consumer (0 = fetch on demand)
+    pub lookahead: usize,
+}
+
+impl Default for PaginationOptions {
+    fn default() -> Self {
+        Self {
+            page_size: DEFAULT_PAGE_SIZE,
+            lookahead: DEFAULT_LOOKAHEAD,
+        }
+    }
+}
+
+impl PaginationOptions {
+    /// Create pagination options with defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of items requested per page
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Set the number of pages prefetched ahead of the consumer
+    pub fn with_lookahead(mut self, lookahead: usize) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+}
+
+/// A fetched page and the cursor for the page after it (`None` = last page).
+pub(crate) type Page<T, C> = (Vec<T>, Option<C>);
+
+/// Aborts the prefetch task when the consuming stream is dropped.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Turn a cursor-based page fetcher into a flat item stream.
+///
+/// With `lookahead > 0`, pages are fetched by a spawned task and buffered in
+/// a bounded channel of `lookahead` pages. The first error ends the stream.
+pub(crate) fn paginate<T, C, F, Fut>(first: C, lookahead: usize, mut fetch: F) -> PageStream<T>
+where
+    T: Send + 'static,
+    C: Send + 'static,
+    F: FnMut(C) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Page<T, C>>> + Send + 'static,
+{
+    if lookahead == 0 {
+        return Box::pin(async_stream::stream! {
+            let mut cursor = Some(first);
+            while let Some(current) = cursor.take() {
+                match fetch(current).await {
+                    Ok((items, next)) => {
+                        for item in items {
+                            yield Ok(item);
+                        }
+                        cursor = next;
+                    }
+                    Err(e) => yield Err(e),
+                }
+            }
+        });
+    }
+
+    Box::pin(async_stream::stream! {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Result<Vec<T>>>(lookahead);
+        let _prefetch = AbortOnDrop(crate::task::spawn_named("everruns.pagination.prefetch", async move {
+            let mut cursor = first;
+            loop {
+                match fetch(cursor).await {
+                    Ok((items, next)) => {
+                        if tx.send(Ok(items)).await.is_err() {
+                            return;
+                        }
+                        match next {
+                            Some(next) => cursor = next,
+                            None => return,
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                }
+            }
+        }));
+
+        while let Some(page) = rx.recv().await {
+            match page {
+                Ok(items) => {
+                    for item in items {
+                        yield Ok(item);
+                    }
+                }
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    async fn pages(cursor: u32) -> Result<Page<u32, u32>> {
+        let items = vec![cursor * 10, cursor * 10 + 1];
+        let next = (cursor < 2).then_some(cursor + 1);
+        Ok((items, next))
+    }
+
+    #[tokio::test]
+    async fn test_paginate_with_lookahead_preserves_order() {
+        let items: Vec<u32> = paginate(0, 2, pages)
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+        assert_eq!(items, vec![0, 1, 10, 11, 20, 21]);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_without_lookahead_preserves_order() {
+        let items: Vec<u32> = paginate(0, 0, pages)
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+        assert_eq!(items, vec![0, 1, 10, 11, 20, 21]);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stops_after_error() {
+        let stream = paginate(0u32, 1, |cursor| async move {
+            if cursor == 1 {
+                Err(crate::Error::Validation("boom".to_string()))
+            } else {
+                Ok((vec![cursor], Some(cursor + 1)))
+            }
+        });
+        let results: Vec<Result<u32>> = stream.collect().await;
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+}
@@ -0,0 +1,469 @@
+//! PII detection on incoming events.
+//!
+//! [`PiiFilter::apply`] wraps a session event stream and runs a
+//! [`PiiDetector`] over agent output before the application renders or
+//! stores it. Matches are masked as `[kind]` (e.g. `[email]`), or only
+//! reported with [`PiiFilter::flag_only`]:
+//!
+//! ```no_run
+//! use everruns_sdk::pii::{PiiFilter, RegexDetector};
+//! use futures::StreamExt;
+//!
+//! # async fn example(
+//! #     events: impl futures::Stream<Item = everruns_sdk::error::Result<everruns_sdk::Event>> + Send + 'static,
+//! # ) -> Result<(), everruns_sdk::Error> {
+//! let filter = PiiFilter::new(RegexDetector::builtin())
+//!     .on_detect(|finding| eprintln!("masked {} in {}", finding.kind, finding.event_id));
+//! let mut events = filter.apply(events);
+//! while let Some(event) = events.next().await {
+//!     println!("{:?}", event?.data);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Text deltas can split a match, so the filter holds back the last
+//! [`HOLDBACK`] bytes of streamed text until more arrives or the message
+//! completes; completed messages and guardrail replacements are checked in
+//! full. Rewritten deltas keep `delta` and `accumulated` consistent with
+//! each other, so consumers of either field see masked text.
+
+use crate::error::{Error, Result};
+use crate::events::types;
+use crate::models::Event;
+use futures::{Stream, StreamExt};
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Bytes of streamed text held back in case a match continues in the next
+/// delta
+pub const HOLDBACK: usize = 64;
+
+/// A piece of PII found in text
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PiiMatch {
+    /// Kind of PII, e.g. `email`; masked text reads `[kind]`
+    pub kind: String,
+    /// Byte range of the match
+    pub range: Range<usize>,
+}
+
+impl PiiMatch {
+    pub fn new(kind: impl Into<String>, range: Range<usize>) -> Self {
+        Self {
+            kind: kind.into(),
+            range,
+        }
+    }
+}
+
+/// Finds PII in text.
+///
+/// Matches must not overlap and must be sorted by start.
+pub trait PiiDetector: Send + Sync {
+    fn detect(&self, text: &str) -> Vec<PiiMatch>;
+}
+
+impl<F> PiiDetector for F
+where
+    F: Fn(&str) -> Vec<PiiMatch> + Send + Sync,
+{
+    fn detect(&self, text: &str) -> Vec<PiiMatch> {
+        self(text)
+    }
+}
+
+struct Rule {
+    kind: String,
+    regex: Regex,
+    validate: Option<fn(&str) -> bool>,
+}
+
+/// Rule-based detector matching one regex per kind of PII.
+///
+/// When matches of different rules overlap, the rule added first wins.
+#[derive(Default)]
+pub struct RegexDetector {
+    rules: Vec<Rule>,
+}
+
+impl RegexDetector {
+    /// Create a detector without rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Detector for email addresses, card numbers (Luhn-checked), US social
+    /// security numbers and phone numbers
+    pub fn builtin() -> Self {
+        let rule = |kind: &str, pattern: &str, validate: Option<fn(&str) -> bool>| Rule {
+            kind: kind.to_string(),
+            regex: Regex::new(pattern).expect("built-in PII pattern is valid"),
+            validate,
+        };
+        Self {
+            rules: vec![
+                rule(
+                    "email",
+                    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+                    None,
+                ),
+                rule("card_number", r"\b\d(?:[ -]?\d){12,18}\b", Some(luhn_valid)),
+                rule("ssn", r"\b\d{3}-\d{2}-\d{4}\b", None),
+                rule(
+                    "phone",
+                    r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]\d{4}\b",
+                    None,
+                ),
+            ],
+        }
+    }
+
+    /// Add a rule; invalid patterns are a validation error
+    pub fn rule(mut self, kind: impl Into<String>, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| Error::Validation(format!("invalid PII pattern: {e}")))?;
+        self.rules.push(Rule {
+            kind: kind.into(),
+            regex,
+            validate: None,
+        });
+        Ok(self)
+    }
+}
+
+impl PiiDetector for RegexDetector {
+    fn detect(&self, text: &str) -> Vec<PiiMatch> {
+        let mut found: Vec<(usize, PiiMatch)> = Vec::new();
+        for (priority, rule) in self.rules.iter().enumerate() {
+            for m in rule.regex.find_iter(text) {
+                if rule.validate.is_none_or(|validate| validate(m.as_str())) {
+                    found.push((priority, PiiMatch::new(&rule.kind, m.range())));
+                }
+            }
+        }
+        found.sort_by_key(|(priority, m)| (m.range.start, *priority));
+        let mut matches: Vec<PiiMatch> = Vec::new();
+        for (_, m) in found {
+            if matches
+                .last()
+                .is_none_or(|last| last.range.end <= m.range.start)
+            {
+                matches.push(m);
+            }
+        }
+        matches
+    }
+}
+
+fn luhn_valid(number: &str) -> bool {
+    let mut sum = 0;
+    let digits = number.chars().rev().filter_map(|c| c.to_digit(10));
+    for (i, digit) in digits.enumerate() {
+        sum += match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        };
+    }
+    sum % 10 == 0
+}
+
+/// PII found in an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PiiFinding {
+    pub kind: String,
+    pub event_id: String,
+    pub session_id: String,
+    pub turn_id: Option<String>,
+}
+
+type OnDetect = Arc<dyn Fn(&PiiFinding) + Send + Sync>;
+
+/// Applies a [`PiiDetector`] to agent output in an event stream.
+#[derive(Clone)]
+pub struct PiiFilter {
+    detector: Arc<dyn PiiDetector>,
+    mask: bool,
+    on_detect: Option<OnDetect>,
+}
+
+impl PiiFilter {
+    /// Mask what `detector` finds
+    pub fn new(detector: impl PiiDetector + 'static) -> Self {
+        Self {
+            detector: Arc::new(detector),
+            mask: true,
+            on_detect: None,
+        }
+    }
+
+    /// Report findings without changing the events
+    pub fn flag_only(mut self) -> Self {
+        self.mask = false;
+        self
+    }
+
+    /// Call `on_detect` for each finding. Findings never contain the PII.
+    pub fn on_detect(mut self, on_detect: impl Fn(&PiiFinding) + Send + Sync + 'static) -> Self {
+        self.on_detect = Some(Arc::new(on_detect));
+        self
+    }
+
+    /// Replace every match in `text` with `[kind]`
+    pub fn mask_text(&self, text: &str) -> String {
+        mask(text, 0..text.len(), &self.detector.detect(text))
+    }
+
+    /// Filter `output.message.*` events of a session event stream.
+    ///
+    /// Other events pass through unchanged.
+    pub fn apply<S>(self, events: S) -> Pin<Box<dyn Stream<Item = Result<Event>> + Send>>
+    where
+        S: Stream<Item = Result<Event>> + Send + 'static,
+    {
+        Box::pin(async_stream::try_stream! {
+            let mut pending: HashMap<String, Pending> = HashMap::new();
+            futures::pin_mut!(events);
+            while let Some(event) = events.next().await {
+                let mut event = match event {
+                    Err(Error::HeartbeatMissed { .. }) => continue,
+                    event => event?,
+                };
+                let turn_id = event.context.turn_id.clone().or_else(|| {
+                    event.data.get("turn_id").and_then(|v| v.as_str()).map(str::to_string)
+                });
+                let key = turn_id.clone().unwrap_or_default();
+                let found = match event.event_type.as_str() {
+                    types::OUTPUT_MESSAGE_STARTED => {
+                        pending.remove(&key);
+                        vec![]
+                    }
+                    types::OUTPUT_MESSAGE_DELTA => {
+                        self.filter_delta(pending.entry(key).or_default(), &mut event.data)
+                    }
+                    types::OUTPUT_MESSAGE_COMPLETED => {
+                        let scanned = pending.remove(&key).map_or(0, |p| p.emitted);
+                        self.filter_message(scanned, &mut event.data)
+                    }
+                    types::OUTPUT_MESSAGE_REPLACED => {
+                        pending.remove(&key);
+                        self.filter_field(&mut event.data, "replacement")
+                    }
+                    types::TURN_COMPLETED | types::TURN_FAILED | types::TURN_CANCELLED => {
+                        pending.remove(&key);
+                        vec![]
+                    }
+                    _ => vec![],
+                };
+                if let Some(on_detect) = &self.on_detect {
+                    for kind in found {
+                        on_detect(&PiiFinding {
+                            kind,
+                            event_id: event.id.clone(),
+                            session_id: event.session_id.clone(),
+                            turn_id: turn_id.clone(),
+                        });
+                    }
+                }
+                yield event;
+            }
+        })
+    }
+
+    /// Release the part of a message's streamed text that can no longer
+    /// change a match, masked. Returns the kinds found in it.
+    fn filter_delta(&self, pending: &mut Pending, data: &mut serde_json::Value) -> Vec<String> {
+        match data.get("accumulated").and_then(|v| v.as_str()) {
+            Some(accumulated) => pending.raw = accumulated.to_string(),
+            None => {
+                let delta = data.get("delta").and_then(|v| v.as_str()).unwrap_or("");
+                pending.raw.push_str(delta);
+            }
+        }
+        let raw = &pending.raw;
+        let matches = self.detector.detect(raw);
+        let mut cut = raw.len().saturating_sub(HOLDBACK);
+        while !raw.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        if let Some(m) = matches.iter().find(|m| m.range.contains(&cut)) {
+            cut = m.range.start;
+        }
+        let cut = cut.max(pending.emitted);
+        let released = pending.emitted..cut;
+        let found = kinds_within(&matches, &released);
+        if self.mask {
+            let chunk = mask(raw, released, &matches);
+            pending.masked.push_str(&chunk);
+            data["delta"] = chunk.into();
+            data["accumulated"] = pending.masked.clone().into();
+        }
+        pending.emitted = cut;
+        found
+    }
+
+    /// Check a completed message's text parts in full. Matches starting
+    /// before `scanned`, the bytes already checked as deltas, were reported
+    /// then.
+    fn filter_message(&self, scanned: usize, data: &mut serde_json::Value) -> Vec<String> {
+        let Some(parts) = data
+            .pointer_mut("/message/content")
+            .and_then(|v| v.as_array_mut())
+        else {
+            return vec![];
+        };
+        let mut found = Vec::new();
+        let mut offset = 0;
+        for part in parts {
+            let Some(text) = part.get("text").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let matches = self.detector.detect(text);
+            let unscanned = scanned.saturating_sub(offset).min(text.len())..text.len();
+            found.extend(kinds_within(&matches, &unscanned));
+            offset += text.len();
+            if self.mask {
+                part["text"] = mask(text, 0..text.len(), &matches).into();
+            }
+        }
+        found
+    }
+
+    fn filter_field(&self, data: &mut serde_json::Value, field: &str) -> Vec<String> {
+        let Some(text) = data.get(field).and_then(|v| v.as_str()) else {
+            return vec![];
+        };
+        let matches = self.detector.detect(text);
+        let found = kinds_within(&matches, &(0..text.len()));
+        if self.mask {
+            data[field] = mask(text, 0..text.len(), &matches).into();
+        }
+        found
+    }
+}
+
+impl std::fmt::Debug for PiiFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PiiFilter")
+            .field("mask", &self.mask)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Streamed text of one output message.
+#[derive(Default)]
+struct Pending {
+    /// Text received so far, unmasked
+    raw: String,
+    /// Bytes of `raw` already released downstream
+    emitted: usize,
+    /// Released text, masked
+    masked: String,
+}
+
+/// Kinds of the matches starting within `range`.
+fn kinds_within(matches: &[PiiMatch], range: &Range<usize>) -> Vec<String> {
+    matches
+        .iter()
+        .filter(|m| range.contains(&m.range.start))
+        .map(|m| m.kind.clone())
+        .collect()
+}
+
+/// `text[range]` with the matches starting inside it replaced by `[kind]`.
+fn mask(text: &str, range: Range<usize>, matches: &[PiiMatch]) -> String {
+    let mut out = String::new();
+    let mut pos = range.start;
+    for m in matches.iter().filter(|m| range.contains(&m.range.start)) {
+        out.push_str(&text[pos..m.range.start]);
+        out.push('[');
+        out.push_str(&m.kind);
+        out.push(']');
+        pos = m.range.end.min(range.end);
+    }
+    out.push_str(&text[pos..range.end]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_rules_find_common_pii() {
+        let filter = PiiFilter::new(RegexDetector::builtin());
+        assert_eq!(
+            filter.mask_text(
+                "Mail jo.doe@example.co.uk, card 4111 1111 1111 1111, \
+                 SSN 123-45-6789, call (555) 123-4567."
+            ),
+            "Mail [email], card [card_number], SSN [ssn], call [phone]."
+        );
+        // Fails the Luhn check
+        assert_eq!(
+            filter.mask_text("order 4111 1111 1111 1112"),
+            "order 4111 1111 1111 1112"
+        );
+    }
+
+    fn event(event_type: &str, data: serde_json::Value) -> Result<Event> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": "evt",
+            "type": event_type,
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "context": {"turn_id": "turn_1"},
+            "data": data
+        }))
+        .unwrap())
+    }
+
+    #[tokio::test]
+    async fn deltas_hold_back_text_until_a_split_match_completes() {
+        let padding = "x".repeat(HOLDBACK);
+        let deltas = ["Write to jo", "e@example.com", " today.", &padding];
+        let mut events: Vec<Result<Event>> = deltas
+            .iter()
+            .map(|delta| {
+                event(
+                    types::OUTPUT_MESSAGE_DELTA,
+                    serde_json::json!({"delta": delta}),
+                )
+            })
+            .collect();
+        events.push(event(
+            types::OUTPUT_MESSAGE_COMPLETED,
+            serde_json::json!({"message": {"content": [
+                {"type": "text", "text": format!("Write to joe@example.com today.{padding}")}
+            ]}}),
+        ));
+
+        let found = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = found.clone();
+        let filter = PiiFilter::new(RegexDetector::builtin())
+            .on_detect(move |finding| sink.lock().unwrap().push(finding.kind.clone()));
+        let out: Vec<Event> = filter
+            .apply(futures::stream::iter(events))
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+
+        let streamed: String = out[..4]
+            .iter()
+            .map(|event| event.data["delta"].as_str().unwrap())
+            .collect();
+        assert_eq!(streamed, "Write to [email] today.");
+        assert_eq!(out[3].data["accumulated"], streamed);
+        assert_eq!(
+            out[4].data["message"]["content"][0]["text"],
+            format!("Write to [email] today.{padding}")
+        );
+        assert_eq!(*found.lock().unwrap(), vec!["email"]);
+    }
+}
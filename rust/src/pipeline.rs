@@ -0,0 +1,228 @@
+//! Multi-agent pipelines.
+//!
+//! A [`Pipeline`] is a DAG of steps, each running one turn of an agent in a
+//! fresh session. A step's prompt is built from the outputs of the steps it
+//! depends on, so "agent A's answer feeds agent B" is expressed directly.
+//! Independent steps run concurrently; the first failure stops the pipeline
+//! and cancels turns still in flight.
+//!
+//! ```no_run
+//! use everruns_sdk::pipeline::Pipeline;
+//!
+//! # async fn example(client: everruns_sdk::Everruns) -> Result<(), everruns_sdk::Error> {
+//! let output = Pipeline::new()
+//!     .step("research", "agent_researcher", |_| "Find facts about otters".into())
+//!     .step_after("summary", "agent_writer", &["research"], |inputs| {
+//!         format!("Summarize:\n{}", inputs.text("research"))
+//!     })
+//!     .run(&client)
+//!     .await?;
+//! println!("{}", output.text("summary"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::Everruns;
+use crate::error::{Error, Result};
+use crate::models::{CreateMessageRequest, CreateSessionRequest};
+use crate::turn::{TurnOutcome, TurnStatus};
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use std::collections::{HashMap, HashSet};
+
+type PromptFn = Box<dyn Fn(&StepInputs<'_>) -> String + Send + Sync>;
+
+struct Step {
+    name: String,
+    agent_id: String,
+    depends_on: Vec<String>,
+    prompt: PromptFn,
+}
+
+/// Outputs of a step's dependencies, passed to its prompt builder.
+pub struct StepInputs<'a> {
+    outcomes: &'a HashMap<String, TurnOutcome>,
+}
+
+impl StepInputs<'_> {
+    /// Outcome of a completed step
+    pub fn outcome(&self, step: &str) -> Option<&TurnOutcome> {
+        self.outcomes.get(step)
+    }
+
+    /// Agent text produced by a step, or an empty string for unknown steps
+    pub fn text(&self, step: &str) -> String {
+        self.outcome(step)
+            .map(TurnOutcome::text)
+            .unwrap_or_default()
+    }
+
+    /// Parse a step's output text as JSON, for steps that answer in a schema.
+    pub fn output_as<T: serde::de::DeserializeOwned>(&self, step: &str) -> Result<T> {
+        Ok(serde_json::from_str(&self.text(step))?)
+    }
+}
+
+/// A DAG of agent turns.
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a step with no dependencies
+    pub fn step<F>(self, name: impl Into<String>, agent_id: impl Into<String>, prompt: F) -> Self
+    where
+        F: Fn(&StepInputs<'_>) -> String + Send + Sync + 'static,
+    {
+        self.step_after(name, agent_id, &[], prompt)
+    }
+
+    /// Add a step that runs once every step in `depends_on` has completed
+    pub fn step_after<F>(
+        mut self,
+        name: impl Into<String>,
+        agent_id: impl Into<String>,
+        depends_on: &[&str],
+        prompt: F,
+    ) -> Self
+    where
+        F: Fn(&StepInputs<'_>) -> String + Send + Sync + 'static,
+    {
+        self.steps.push(Step {
+            name: name.into(),
+            agent_id: agent_id.into(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            prompt: Box::new(prompt),
+        });
+        self
+    }
+
+    /// Run every step and return their outcomes.
+    ///
+    /// Fails with [`Error::Validation`] for duplicate names, unknown
+    /// dependencies or cycles, and with [`Error::StepFailed`] when a step's
+    /// request fails or its turn does not complete.
+    pub async fn run(&self, client: &Everruns) -> Result<PipelineOutput> {
+        self.validate()?;
+
+        let mut outcomes: HashMap<String, TurnOutcome> = HashMap::new();
+        let mut started: HashSet<&str> = HashSet::new();
+        let mut running = FuturesUnordered::new();
+        while outcomes.len() < self.steps.len() {
+            for step in &self.steps {
+                if started.contains(step.name.as_str())
+                    || !step.depends_on.iter().all(|d| outcomes.contains_key(d))
+                {
+                    continue;
+                }
+                started.insert(&step.name);
+                let prompt = (step.prompt)(&StepInputs {
+                    outcomes: &outcomes,
+                });
+                running.push(async move { (step, run_step(client, step, prompt).await) });
+            }
+
+            let Some((step, result)) = running.next().await else {
+                break;
+            };
+            let outcome = result.map_err(|e| Error::StepFailed {
+                step: step.name.clone(),
+                message: e.to_string(),
+            })?;
+            if outcome.status != TurnStatus::Completed {
+                return Err(Error::StepFailed {
+                    step: step.name.clone(),
+                    message: outcome
+                        .error
+                        .clone()
+                        .unwrap_or_else(|| format!("turn ended as {:?}", outcome.status)),
+                });
+            }
+            outcomes.insert(step.name.clone(), outcome);
+        }
+        Ok(PipelineOutput { outcomes })
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for step in &self.steps {
+            if !names.insert(step.name.as_str()) {
+                return Err(Error::Validation(format!(
+                    "duplicate pipeline step '{}'",
+                    step.name
+                )));
+            }
+        }
+        for step in &self.steps {
+            if let Some(missing) = step.depends_on.iter().find(|d| !names.contains(d.as_str())) {
+                return Err(Error::Validation(format!(
+                    "pipeline step '{}' depends on unknown step '{missing}'",
+                    step.name
+                )));
+            }
+        }
+        // Kahn's algorithm: every step must become ready eventually.
+        let mut done: HashSet<&str> = HashSet::new();
+        while done.len() < self.steps.len() {
+            let ready: Vec<&str> = self
+                .steps
+                .iter()
+                .filter(|s| !done.contains(s.name.as_str()))
+                .filter(|s| s.depends_on.iter().all(|d| done.contains(d.as_str())))
+                .map(|s| s.name.as_str())
+                .collect();
+            if ready.is_empty() {
+                return Err(Error::Validation(
+                    "pipeline steps form a dependency cycle".to_string(),
+                ));
+            }
+            done.extend(ready);
+        }
+        Ok(())
+    }
+}
+
+async fn run_step(client: &Everruns, step: &Step, prompt: String) -> Result<TurnOutcome> {
+    let session = client
+        .sessions()
+        .create_with_options(
+            CreateSessionRequest::new()
+                .agent_id(&step.agent_id)
+                .title(format!("pipeline: {}", step.name)),
+        )
+        .await?;
+    client
+        .messages()
+        .run(&session.id, CreateMessageRequest::user_text(prompt))
+        .await
+}
+
+/// Outcomes of a finished pipeline, by step name
+#[derive(Debug, Clone)]
+pub struct PipelineOutput {
+    outcomes: HashMap<String, TurnOutcome>,
+}
+
+impl PipelineOutput {
+    /// Outcome of a step
+    pub fn outcome(&self, step: &str) -> Option<&TurnOutcome> {
+        self.outcomes.get(step)
+    }
+
+    /// Agent text produced by a step
+    pub fn text(&self, step: &str) -> String {
+        self.outcome(step)
+            .map(TurnOutcome::text)
+            .unwrap_or_default()
+    }
+
+    /// All outcomes, keyed by step name
+    pub fn outcomes(&self) -> &HashMap<String, TurnOutcome> {
+        &self.outcomes
+    }
+}
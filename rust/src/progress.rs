@@ -0,0 +1,150 @@
+//! Progress reporting for long operations.
+//!
+//! Uploads, downloads, bulk deletes and event backfills accept a
+//! [`Progress`] implementation and call it as work completes, so CLIs and
+//! UIs can draw progress bars. Closures taking a [`ProgressUpdate`] implement
+//! the trait:
+//!
+//! ```no_run
+//! # async fn example(client: everruns_sdk::Everruns) -> Result<(), everruns_sdk::Error> {
+//! let bytes = std::fs::read("chart.png").unwrap();
+//! client
+//!     .images()
+//!     .upload_with_progress("chart.png", "image/png", bytes, None, |p: &everruns_sdk::progress::ProgressUpdate| {
+//!         eprint!("\r{}/{} bytes", p.done, p.total.unwrap_or(0));
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Callbacks may be invoked from several tasks at once and should return
+//! quickly.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Snapshot of an operation's progress
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ProgressUpdate {
+    /// Units completed so far: bytes for transfers, items otherwise
+    pub done: u64,
+    /// Total units, when known up front
+    pub total: Option<u64>,
+    /// Average transfer rate since the start, for byte transfers
+    pub bytes_per_sec: Option<f64>,
+}
+
+/// Receiver of progress updates.
+pub trait Progress: Send + Sync {
+    fn update(&self, update: &ProgressUpdate);
+}
+
+impl<F> Progress for F
+where
+    F: Fn(&ProgressUpdate) + Send + Sync,
+{
+    fn update(&self, update: &ProgressUpdate) {
+        self(update)
+    }
+}
+
+/// Cloneable, type-erased [`Progress`], for storing in options structs.
+#[derive(Clone)]
+pub struct SharedProgress(Arc<dyn Progress>);
+
+impl SharedProgress {
+    pub fn new(progress: impl Progress + 'static) -> Self {
+        Self(Arc::new(progress))
+    }
+}
+
+impl std::fmt::Debug for SharedProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedProgress")
+    }
+}
+
+impl Progress for SharedProgress {
+    fn update(&self, update: &ProgressUpdate) {
+        self.0.update(update)
+    }
+}
+
+/// Accumulates completed units and forwards updates to a [`Progress`].
+pub(crate) struct Tracker {
+    progress: SharedProgress,
+    total: Option<u64>,
+    bytes: bool,
+    started: Instant,
+    done: Mutex<u64>,
+}
+
+impl Tracker {
+    /// Track a byte transfer; updates carry a transfer rate.
+    pub(crate) fn bytes(progress: SharedProgress, total: Option<u64>) -> Self {
+        Self::new(progress, total, true)
+    }
+
+    /// Track a count of items.
+    pub(crate) fn items(progress: SharedProgress, total: Option<u64>) -> Self {
+        Self::new(progress, total, false)
+    }
+
+    fn new(progress: SharedProgress, total: Option<u64>, bytes: bool) -> Self {
+        Self {
+            progress,
+            total,
+            bytes,
+            started: Instant::now(),
+            done: Mutex::new(0),
+        }
+    }
+
+    /// Report the starting point, so a bar can appear before any work is done.
+    pub(crate) fn start(&self) {
+        self.advance(0);
+    }
+
+    pub(crate) fn advance(&self, n: u64) {
+        // Hold the lock while reporting so updates arrive in order.
+        let mut done = self.done.lock().unwrap();
+        *done += n;
+        let elapsed = self.started.elapsed().as_secs_f64();
+        self.progress.update(&ProgressUpdate {
+            done: *done,
+            total: self.total,
+            bytes_per_sec: (self.bytes && elapsed > 0.0).then(|| *done as f64 / elapsed),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_accumulates_and_reports_rates_for_bytes_only() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let progress = SharedProgress::new(move |p: &ProgressUpdate| {
+            sink.lock().unwrap().push(p.clone());
+        });
+
+        let items = Tracker::items(progress.clone(), Some(3));
+        items.start();
+        items.advance(1);
+        items.advance(2);
+        let bytes = Tracker::bytes(progress, None);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        bytes.advance(10);
+
+        let seen = seen.lock().unwrap();
+        let done: Vec<u64> = seen.iter().map(|p| p.done).collect();
+        assert_eq!(done, vec![0, 1, 3, 10]);
+        assert_eq!(seen[2].total, Some(3));
+        assert!(seen[2].bytes_per_sec.is_none());
+        assert!(seen[3].bytes_per_sec.unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,119 @@
+//! Redaction of outgoing message content.
+//!
+//! A [`Redactor`] installed with
+//! [`EverrunsBuilder::redactor`](crate::client::EverrunsBuilder::redactor)
+//! sees every content part the client is about to send — messages, tool
+//! results and messages queued by [`DurableSender`](crate::outbox::DurableSender)
+//! — and may rewrite it in place. Closures implement the trait:
+//!
+//! ```no_run
+//! use everruns_sdk::models::ContentPart;
+//! use everruns_sdk::redact::Redaction;
+//!
+//! # fn example() -> Result<(), everruns_sdk::Error> {
+//! let client = everruns_sdk::Everruns::builder()
+//!     .redactor(|part: &mut ContentPart| {
+//!         let ContentPart::Text { text } = part else {
+//!             return vec![];
+//!         };
+//!         let count = text.matches("hunter2").count();
+//!         *text = text.replace("hunter2", "[REDACTED]");
+//!         (count > 0).then(|| Redaction::new("password", count)).into_iter().collect()
+//!     })
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every [`Redaction`] reported is passed to [`Redactor::audit`] as a
+//! [`RedactionRecord`]. The default implementation logs it with `tracing`
+//! under the `everruns_sdk::redact` target; override it to keep an audit
+//! trail elsewhere. Records never contain the redacted content.
+
+use crate::models::ContentPart;
+use std::sync::Arc;
+
+/// What a redactor changed in one content part
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Redaction {
+    /// Name of the rule that matched, e.g. `card_number`
+    pub rule: String,
+    /// Number of matches replaced
+    pub count: usize,
+}
+
+impl Redaction {
+    pub fn new(rule: impl Into<String>, count: usize) -> Self {
+        Self {
+            rule: rule.into(),
+            count,
+        }
+    }
+}
+
+/// Audit record of a redaction applied to an outgoing request
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RedactionRecord {
+    pub session_id: String,
+    /// Index of the content part within the message or tool results
+    pub part_index: usize,
+    pub redaction: Redaction,
+}
+
+/// Hook that scrubs outgoing content parts.
+pub trait Redactor: Send + Sync {
+    /// Rewrite `part` in place and report what was redacted.
+    fn redact(&self, part: &mut ContentPart) -> Vec<Redaction>;
+
+    /// Record a redaction. Logs at info level by default.
+    fn audit(&self, record: &RedactionRecord) {
+        tracing::info!(
+            target: "everruns_sdk::redact",
+            session_id = %record.session_id,
+            part_index = record.part_index,
+            rule = %record.redaction.rule,
+            count = record.redaction.count,
+            "redacted outgoing content"
+        );
+    }
+}
+
+impl<F> Redactor for F
+where
+    F: Fn(&mut ContentPart) -> Vec<Redaction> + Send + Sync,
+{
+    fn redact(&self, part: &mut ContentPart) -> Vec<Redaction> {
+        self(part)
+    }
+}
+
+/// Cloneable, type-erased [`Redactor`] held by the client.
+#[derive(Clone)]
+pub(crate) struct SharedRedactor(Arc<dyn Redactor>);
+
+impl SharedRedactor {
+    pub(crate) fn new(redactor: impl Redactor + 'static) -> Self {
+        Self(Arc::new(redactor))
+    }
+
+    /// Redact `parts` and audit each redaction.
+    pub(crate) fn apply(&self, session_id: &str, parts: &mut [ContentPart]) {
+        for (part_index, part) in parts.iter_mut().enumerate() {
+            for redaction in self.0.redact(part) {
+                self.0.audit(&RedactionRecord {
+                    session_id: session_id.to_string(),
+                    part_index,
+                    redaction,
+                });
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SharedRedactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedRedactor")
+    }
+}
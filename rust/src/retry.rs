@@ -0,0 +1,224 @@
+//! Client-wide retry policy and budget.
+//!
+//! A [`RetryPolicy`] resends requests that failed transiently, such as a 429
+//! or a GET that hit a restarting server's 503, with exponential backoff or the wait
+//! the server asked for in `Retry-After`. A
+//! [`RetryBudget`] caps retries at a fraction of the requests a client made
+//! recently, so one failing endpoint cannot turn into a retry storm that
+//! burns the organization's rate limit. The budget is shared by every clone
+//! of a client and covers SDK-initiated retries such as SSE reconnects and
+//! the janitor's 429 retries.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Configuration for a client-wide retry budget.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RetryBudget {
+    /// Retries allowed as a fraction of requests in the window
+    pub ratio: f64,
+    /// Retries always allowed per window, so low-traffic clients can retry
+    pub min_retries: u32,
+    /// Length of the accounting window
+    pub window: Duration,
+}
+
+impl Default for RetryBudget {
+    /// 20% of requests, at least 10 retries per 10 second window.
+    fn default() -> Self {
+        Self {
+            ratio: 0.2,
+            min_retries: 10,
+            window: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryBudget {
+    /// Create a budget with the default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ratio(mut self, ratio: f64) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    pub fn min_retries(mut self, min_retries: u32) -> Self {
+        self.min_retries = min_retries;
+        self
+    }
+
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+/// Which failed requests to resend, and how long to wait between attempts.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Wait before the first retry; doubled for each one after it
+    pub initial_backoff: Duration,
+    /// Upper bound on the wait between attempts. A response whose
+    /// `Retry-After` asks for longer is returned instead of retried, so the
+    /// caller sees the requested wait in [`Error::RateLimited`](crate::Error::RateLimited).
+    pub max_backoff: Duration,
+    /// Response statuses that are retried. 5xx statuses are retried only for
+    /// idempotent methods such as GET, PUT and DELETE: the server may have
+    /// acted on a POST or PATCH before failing, and resending it could, for
+    /// example, post a message twice. Connection failures, where the request
+    /// never reached the server, are retried for every method.
+    pub statuses: Vec<u16>,
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_retries` times, waiting 250ms, 500ms, 1s and so on,
+    /// capped at 8 seconds, on 429, and on 502, 503 and 504 for idempotent
+    /// requests.
+    pub fn exponential(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+            statuses: vec![429, 502, 503, 504],
+        }
+    }
+
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    pub fn statuses(mut self, statuses: impl Into<Vec<u16>>) -> Self {
+        self.statuses = statuses.into();
+        self
+    }
+
+    /// Wait before retry number `attempt` (zero-based).
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+/// Wait requested by a `Retry-After` header, in seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(
+        at.duration_since(std::time::SystemTime::now())
+            .unwrap_or_default(),
+    )
+}
+
+struct Counters {
+    started: Instant,
+    requests: u64,
+    retries: u64,
+}
+
+/// Request and retry counts for the current window.
+pub(crate) struct BudgetTracker {
+    budget: RetryBudget,
+    counters: Mutex<Counters>,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(budget: RetryBudget) -> Self {
+        Self {
+            budget,
+            counters: Mutex::new(Counters {
+                started: Instant::now(),
+                requests: 0,
+                retries: 0,
+            }),
+        }
+    }
+
+    pub(crate) fn record_request(&self) {
+        self.current().requests += 1;
+    }
+
+    /// Spend one retry if the budget allows it.
+    pub(crate) fn try_retry(&self) -> bool {
+        let mut counters = self.current();
+        let allowed = (counters.requests as f64 * self.budget.ratio) as u64
+            + u64::from(self.budget.min_retries);
+        if counters.retries >= allowed {
+            return false;
+        }
+        counters.retries += 1;
+        true
+    }
+
+    fn current(&self) -> std::sync::MutexGuard<'_, Counters> {
+        let mut counters = self.counters.lock().unwrap();
+        if counters.started.elapsed() >= self.budget.window {
+            *counters = Counters {
+                started: Instant::now(),
+                requests: 0,
+                retries: 0,
+            };
+        }
+        counters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy::exponential(5).max_backoff(Duration::from_secs(1));
+        assert_eq!(policy.backoff(0), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(40), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn retry_after_reads_seconds_and_dates() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        let past = httpdate::fmt_http_date(std::time::UNIX_EPOCH);
+        headers.insert(reqwest::header::RETRY_AFTER, past.parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(reqwest::header::RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn retries_are_capped_by_ratio_and_minimum() {
+        let tracker = BudgetTracker::new(RetryBudget::new().ratio(0.5).min_retries(1));
+        for _ in 0..4 {
+            tracker.record_request();
+        }
+        // 4 requests * 0.5 + 1 = 3 retries
+        assert!(tracker.try_retry());
+        assert!(tracker.try_retry());
+        assert!(tracker.try_retry());
+        assert!(!tracker.try_retry());
+    }
+}
@@ -0,0 +1,267 @@
+//! Structured JSONL logging of session activity.
+//!
+//! [`SessionLogger`] consumes session events and appends one normalized JSON
+//! record per line for turns, messages and tool calls, which is easier to load
+//! into analytics tools than raw events. Delta and infrastructure events are
+//! skipped.
+
+use crate::error::{Error, Result};
+use crate::events::types;
+use crate::models::{ContentPart, Event, Message, MessageRole, TokenUsage};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+const REDACTED: &str = "[redacted]";
+
+/// Category of a [`LogRecord`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    Turn,
+    Message,
+    Tool,
+}
+
+/// One normalized line of a session log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct LogRecord {
+    pub ts: String,
+    pub session_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub kind: RecordKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// Lifecycle state, e.g. `started`, `completed`, `failed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Message text, or the error for failed turns and tools
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+impl LogRecord {
+    /// Normalize an event, or `None` for events that are not logged.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        let data = &event.data;
+        let str_field = |key: &str| data.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let mut record = Self {
+            ts: event.ts.clone(),
+            session_id: event.session_id.clone(),
+            event_id: event.id.clone(),
+            event_type: event.event_type.clone(),
+            kind: RecordKind::Turn,
+            turn_id: event
+                .context
+                .turn_id
+                .clone()
+                .or_else(|| str_field("turn_id")),
+            status: None,
+            message_id: None,
+            role: None,
+            text: None,
+            tool_name: None,
+            tool_call_id: None,
+            duration_ms: data.get("duration_ms").and_then(|v| v.as_u64()),
+            usage: data
+                .get("usage")
+                .filter(|usage| !usage.is_null())
+                .and_then(|usage| TokenUsage::deserialize(usage).ok()),
+        };
+        match event.event_type.as_str() {
+            types::TURN_STARTED
+            | types::TURN_COMPLETED
+            | types::TURN_FAILED
+            | types::TURN_CANCELLED => {
+                record.status = event.event_type.strip_prefix("turn.").map(str::to_string);
+                record.text = str_field("error");
+            }
+            types::INPUT_MESSAGE | types::OUTPUT_MESSAGE_COMPLETED => {
+                let message = Message::deserialize(data.get("message")?).ok()?;
+                record.kind = RecordKind::Message;
+                record.message_id = Some(message.id);
+                record.role = Some(
+                    match message.role {
+                        MessageRole::User => "user",
+                        MessageRole::Agent => "agent",
+                        MessageRole::ToolResult => "tool_result",
+                    }
+                    .to_string(),
+                );
+                record.text = Some(
+                    message
+                        .content
+                        .iter()
+                        .filter_map(|part| match part {
+                            ContentPart::Text { text } => Some(text.as_str()),
+                            _ => None,
+                        })
+                        .collect(),
+                );
+            }
+            types::TOOL_STARTED => {
+                let call = data.get("tool_call")?;
+                record.kind = RecordKind::Tool;
+                record.status = Some("started".to_string());
+                record.tool_call_id = call.get("id").and_then(|v| v.as_str()).map(str::to_string);
+                record.tool_name = call
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
+            types::TOOL_COMPLETED => {
+                record.kind = RecordKind::Tool;
+                record.status = str_field("status");
+                record.tool_call_id = str_field("tool_call_id");
+                record.tool_name = str_field("tool_name");
+                record.text = str_field("error");
+            }
+            _ => return None,
+        }
+        Some(record)
+    }
+}
+
+/// Appends [`LogRecord`]s to a JSONL file, with optional size-based rotation.
+pub struct SessionLogger {
+    path: PathBuf,
+    file: Option<tokio::fs::File>,
+    written: u64,
+    max_bytes: Option<u64>,
+    keep: usize,
+    redact_text: bool,
+}
+
+impl SessionLogger {
+    /// Log to `path`, appending if it already exists
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: None,
+            written: 0,
+            max_bytes: None,
+            keep: 0,
+            redact_text: false,
+        }
+    }
+
+    /// Rotate once the file would exceed `max_bytes`, keeping `keep` old files
+    /// as `<path>.1` (newest) through `<path>.<keep>`.
+    pub fn rotate_at(mut self, max_bytes: u64, keep: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self.keep = keep;
+        self
+    }
+
+    /// Replace message text and error strings with `[redacted]`
+    pub fn redact_text(mut self, redact: bool) -> Self {
+        self.redact_text = redact;
+        self
+    }
+
+    /// Path of the active log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write a record for `event` if it is one the logger tracks.
+    pub async fn log(&mut self, event: &Event) -> Result<()> {
+        let Some(mut record) = LogRecord::from_event(event) else {
+            return Ok(());
+        };
+        if self.redact_text && record.text.is_some() {
+            record.text = Some(REDACTED.to_string());
+        }
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+
+        if self.file.is_none() {
+            self.file = Some(self.open().await?);
+        }
+        if let Some(max_bytes) = self.max_bytes
+            && self.written > 0
+            && self.written + line.len() as u64 > max_bytes
+        {
+            self.rotate().await?;
+            self.file = Some(self.open().await?);
+        }
+        let file = self.file.as_mut().expect("log file opened above");
+        file.write_all(&line).await.map_err(io_error)?;
+        file.flush().await.map_err(io_error)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Log every event from `stream` until it ends or yields an error.
+    /// [`Error::HeartbeatMissed`] notices are skipped.
+    pub async fn run<S>(mut self, stream: S) -> Result<()>
+    where
+        S: Stream<Item = Result<Event>>,
+    {
+        futures::pin_mut!(stream);
+        while let Some(event) = stream.next().await {
+            match event {
+                Err(Error::HeartbeatMissed { .. }) => continue,
+                event => self.log(&event?).await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn open(&mut self) -> Result<tokio::fs::File> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(io_error)?;
+        self.written = file.metadata().await.map_err(io_error)?.len();
+        Ok(file)
+    }
+
+    async fn rotate(&mut self) -> Result<()> {
+        self.file = None;
+        if self.keep == 0 {
+            tokio::fs::remove_file(&self.path).await.map_err(io_error)?;
+        } else {
+            for n in (1..self.keep).rev() {
+                let from = self.rotated_path(n);
+                if tokio::fs::try_exists(&from).await.unwrap_or(false) {
+                    tokio::fs::rename(&from, self.rotated_path(n + 1))
+                        .await
+                        .map_err(io_error)?;
+                }
+            }
+            tokio::fs::rename(&self.path, self.rotated_path(1))
+                .await
+                .map_err(io_error)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    }
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::Validation(format!("session log I/O error: {err}"))
+}
@@ -0,0 +1,80 @@
+//! Shutdown state shared by a client and its clones.
+
+use crate::error::{Error, Result};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Tracks in-flight requests and whether the client has begun shutting down.
+#[derive(Default)]
+pub(crate) struct Lifecycle {
+    closing: AtomicBool,
+    in_flight: AtomicUsize,
+    /// Woken when shutdown begins and when the last in-flight request ends.
+    changed: Notify,
+}
+
+impl Lifecycle {
+    /// Register a request, or fail if the client is shutting down.
+    pub(crate) fn begin(self: &Arc<Self>) -> Result<InFlight> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let guard = InFlight(self.clone());
+        if self.is_closing() {
+            return Err(Error::ShuttingDown);
+        }
+        Ok(guard)
+    }
+
+    pub(crate) fn is_closing(&self) -> bool {
+        self.closing.load(Ordering::Acquire)
+    }
+
+    /// Resolves once shutdown has begun.
+    #[cfg(feature = "sse")]
+    pub(crate) fn closed(
+        self: &Arc<Self>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> {
+        let this = self.clone();
+        Box::pin(async move {
+            loop {
+                let notified = this.changed.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if this.is_closing() {
+                    return;
+                }
+                notified.await;
+            }
+        })
+    }
+
+    /// Stop accepting requests and wait up to `timeout` for in-flight ones.
+    pub(crate) async fn shutdown(&self, timeout: Duration) -> bool {
+        self.closing.store(true, Ordering::Release);
+        self.changed.notify_waiters();
+        let drained = async {
+            loop {
+                let notified = self.changed.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.in_flight.load(Ordering::Acquire) == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, drained).await.is_ok()
+    }
+}
+
+/// Guard held for the duration of one request.
+pub(crate) struct InFlight(Arc<Lifecycle>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.changed.notify_waiters();
+        }
+    }
+}
@@ -6,12 +6,16 @@
 //! - Graceful handling of `disconnecting` events
 //! - Exponential backoff for unexpected disconnections
 //! - Resume from last event ID via `since_id`
+//! - Optional heartbeat liveness checks
 
 use crate::client::Everruns;
 use crate::error::{Error, Result};
-use crate::models::Event;
+use crate::events::types;
+use crate::models::{Event, ThinkingDelta};
+use crate::progress::{Progress, SharedProgress};
 use futures::stream::Stream;
 use serde::Deserialize;
+use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -33,6 +37,19 @@ pub const READ_TIMEOUT_SECS: u64 = 45;
 /// responses, so EventStream races this timer against inner.poll_next().
 /// 45s = 1.5× the server's 30s heartbeat interval.
 pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 45;
+/// Type of the synthetic event yielded when
+/// [`StreamOptions::reconnect_notices`] is on and the stream recovers from an
+/// unexpected disconnect. Its `data` is `{"attempts": n}`.
+pub const RECONNECTED_EVENT: &str = "stream.reconnected";
+/// Type of the synthetic event yielded when [`StreamOptions::detect_gaps`]
+/// is on and event sequence numbers skip ahead. Its `data` parses as
+/// [`GapDetected`].
+pub const GAP_DETECTED_EVENT: &str = "stream.gap_detected";
+/// A connection is considered dead once nothing has arrived for this multiple
+/// of [`StreamOptions::heartbeat_interval`].
+const HEARTBEAT_GRACE: f64 = 1.5;
+/// Number of recent event IDs remembered for [`StreamOptions::dedup`].
+const DEDUP_WINDOW: usize = 1024;
 
 /// Options for SSE streaming
 #[derive(Debug, Clone)]
@@ -50,6 +67,35 @@ pub struct StreamOptions {
     /// When no events are yielded within this duration, the stream reconnects.
     /// Default: 45s (1.5× the server's 30s heartbeat interval).
     pub idle_timeout: Duration,
+    /// Drop events whose ID was already yielded by this stream.
+    pub dedup: bool,
+    /// Yield a [`RECONNECTED_EVENT`] after recovering from an unexpected
+    /// disconnect, so a UI can clear a "reconnecting" indicator. Planned
+    /// connection cycling does not produce one.
+    pub reconnect_notices: bool,
+    /// Only yield events belonging to this turn (by `context.turn_id`).
+    /// Filtered client-side; the stream still advances past other events.
+    pub turn_id: Option<String>,
+    /// Yield a [`GAP_DETECTED_EVENT`] before an event whose `sequence` skips
+    /// ahead of the previous one. Ignored when `types` or `exclude` are set,
+    /// since server-side filtering leaves expected holes in the sequence.
+    pub detect_gaps: bool,
+    /// Fetch events missing from a gap via the list endpoint and yield them
+    /// in order. A notice is still yielded if the gap cannot be filled.
+    /// Implies `detect_gaps`.
+    pub backfill_gaps: bool,
+    /// Expected interval between server heartbeats. When set, a connection
+    /// that receives no bytes at all (heartbeats included) for 1.5× this
+    /// interval is dropped: the stream yields [`Error::HeartbeatMissed`] and
+    /// then keeps going on a new connection, so treat that error as a notice
+    /// rather than fatal. The SDK's own consumers, such as
+    /// [`TurnHandle`](crate::turn::TurnHandle), skip it.
+    /// Unlike `idle_timeout`, quiet sessions with live heartbeats never trip it.
+    pub heartbeat_interval: Option<Duration>,
+    /// Receives the count of historical events replayed by
+    /// [`EventsClient::stream_from_start`](crate::client::EventsClient::stream_from_start)
+    /// before it switches to live streaming.
+    pub backfill_progress: Option<SharedProgress>,
 }
 
 impl Default for StreamOptions {
@@ -60,6 +106,13 @@ impl Default for StreamOptions {
             since_id: None,
             max_retries: None,
             idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
+            dedup: false,
+            reconnect_notices: false,
+            turn_id: None,
+            detect_gaps: false,
+            backfill_gaps: false,
+            heartbeat_interval: None,
+            backfill_progress: None,
         }
     }
 }
@@ -74,13 +127,65 @@ impl StreamOptions {
     pub fn exclude_deltas() -> Self {
         Self {
             exclude: vec![
-                "output.message.delta".to_string(),
-                "reason.thinking.delta".to_string(),
+                types::OUTPUT_MESSAGE_DELTA.to_string(),
+                types::REASON_THINKING_DELTA.to_string(),
             ],
             ..Self::default()
         }
     }
 
+    /// Recommended options for chat frontends.
+    ///
+    /// Keeps text and thinking deltas for live rendering, drops duplicate
+    /// events and emits reconnect notices. Heartbeats and the `connected` /
+    /// `disconnecting` lifecycle events are always consumed by the stream
+    /// itself and never reach the caller.
+    pub fn for_ui() -> Self {
+        Self {
+            dedup: true,
+            reconnect_notices: true,
+            ..Self::default()
+        }
+    }
+
+    /// Options that yield only the events of one turn.
+    ///
+    /// Useful when several turns run in a shared session and a consumer only
+    /// cares about the one it started.
+    pub fn for_turn(turn_id: impl Into<String>) -> Self {
+        Self::default().with_turn_id(turn_id)
+    }
+
+    /// Only yield events belonging to `turn_id`
+    pub fn with_turn_id(mut self, turn_id: impl Into<String>) -> Self {
+        self.turn_id = Some(turn_id.into());
+        self
+    }
+
+    /// Yield a [`GAP_DETECTED_EVENT`] when event sequence numbers skip ahead
+    pub fn with_gap_detection(mut self, detect: bool) -> Self {
+        self.detect_gaps = detect;
+        self
+    }
+
+    /// Fill sequence gaps from the list endpoint
+    pub fn with_gap_backfill(mut self, backfill: bool) -> Self {
+        self.backfill_gaps = backfill;
+        self
+    }
+
+    /// Declare the server's heartbeat interval (30s for Everruns)
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = Some(interval);
+        self
+    }
+
+    /// Report replayed events while backfilling from the start
+    pub fn with_backfill_progress(mut self, progress: impl Progress + 'static) -> Self {
+        self.backfill_progress = Some(SharedProgress::new(progress));
+        self
+    }
+
     /// Set the positive type filter
     pub fn with_types(mut self, types: Vec<String>) -> Self {
         self.types = types;
@@ -105,6 +210,18 @@ impl StreamOptions {
         self
     }
 
+    /// Drop events already yielded by this stream
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Yield a [`RECONNECTED_EVENT`] after recovering from a disconnect
+    pub fn with_reconnect_notices(mut self, notices: bool) -> Self {
+        self.reconnect_notices = notices;
+        self
+    }
+
     /// Set idle timeout for detecting half-open connections.
     ///
     /// When no events are yielded within this duration, the stream assumes
@@ -127,6 +244,43 @@ pub struct DisconnectingData {
     pub retry_ms: u64,
 }
 
+/// Data of a [`GAP_DETECTED_EVENT`]: sequence numbers `expected..got` were
+/// not delivered.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, Deserialize)]
+pub struct GapDetected {
+    pub expected: i32,
+    pub got: i32,
+}
+
+/// Time the current connection last received any bytes
+struct Activity(std::sync::Mutex<tokio::time::Instant>);
+
+impl Activity {
+    fn touch(&self) {
+        *self.0.lock().unwrap() = tokio::time::Instant::now();
+    }
+
+    fn silent_for(&self) -> Duration {
+        self.0.lock().unwrap().elapsed()
+    }
+}
+
+/// Fetch of the events missing from a gap
+type BackfillFuture = Pin<Box<dyn Future<Output = (GapDetected, Result<Vec<Event>>)> + Send>>;
+
+/// Position in a session's event stream, for resuming after a restart.
+///
+/// Obtained from [`EventStream::cursor`] and passed to
+/// [`EventsClient::resume`](crate::client::EventsClient::resume). Serializes
+/// to JSON so it can be persisted alongside the consumer's own state.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, Deserialize)]
+pub struct StreamCursor {
+    pub session_id: String,
+    /// Last event delivered, or `None` to start from the beginning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event_id: Option<String>,
+}
+
 /// A stream of SSE events from a session with automatic reconnection.
 ///
 /// This stream handles:
@@ -182,6 +336,26 @@ pub struct EventStream {
     idle_deadline: Option<Pin<Box<Sleep>>>,
     /// Duration before idle_deadline fires
     idle_timeout: Duration,
+    /// Resolves when the client shuts down, ending the stream.
+    shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    /// Recently yielded event IDs, when `options.dedup` is set
+    seen: VecDeque<String>,
+    /// Span entered while polling, so logs and reconnect delays are
+    /// attributed to this session
+    span: tracing::Span,
+    /// Byte-level liveness of the current connection
+    activity: Arc<Activity>,
+    /// Next time to check `activity` against the heartbeat interval
+    heartbeat_deadline: Option<Pin<Box<Sleep>>>,
+    /// Highest event sequence seen, for gap detection
+    last_sequence: Option<i32>,
+    /// Events waiting to be yielded after a gap notice or backfill
+    pending: VecDeque<Event>,
+    /// In-flight fetch of events missing from a gap
+    backfill: Option<BackfillFuture>,
+    /// Reconnection attempts so far while recovering from an unexpected
+    /// disconnect
+    recovering: Option<u32>,
 }
 
 impl EventStream {
@@ -190,12 +364,18 @@ impl EventStream {
         // reused across reconnections for connection pool / TCP reuse.
         // read_timeout is kept as a secondary safety net, but the primary
         // stall detection is the poll-level idle_deadline (see poll_next).
-        let sse_http_client = reqwest::Client::builder()
-            .read_timeout(Duration::from_secs(READ_TIMEOUT_SECS))
+        let mut sse_http_client =
+            reqwest::Client::builder().read_timeout(Duration::from_secs(READ_TIMEOUT_SECS));
+        if let Some(timeout) = client.connect_timeout() {
+            sse_http_client = sse_http_client.connect_timeout(timeout);
+        }
+        let sse_http_client = sse_http_client
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
 
         let idle_timeout = options.idle_timeout;
+        let shutdown = Some(client.lifecycle().closed());
+        let span = tracing::debug_span!("everruns.sse", session_id = %session_id);
 
         Self {
             client,
@@ -213,6 +393,15 @@ impl EventStream {
             sse_http_client,
             idle_deadline: None,
             idle_timeout,
+            shutdown,
+            seen: VecDeque::new(),
+            recovering: None,
+            span,
+            activity: Arc::new(Activity(std::sync::Mutex::new(tokio::time::Instant::now()))),
+            heartbeat_deadline: None,
+            last_sequence: None,
+            pending: VecDeque::new(),
+            backfill: None,
         }
     }
 
@@ -221,12 +410,148 @@ impl EventStream {
         self.last_event_id.as_deref()
     }
 
+    /// Resume point after the last event this stream yielded.
+    ///
+    /// Before any event arrives this is the position the stream started from.
+    pub fn cursor(&self) -> StreamCursor {
+        StreamCursor {
+            session_id: self.session_id.clone(),
+            last_event_id: self
+                .last_event_id
+                .clone()
+                .or_else(|| self.options.since_id.clone()),
+        }
+    }
+
+    /// Record `id` as yielded; returns `false` if dedup saw it already.
+    fn remember(&mut self, id: &str) -> bool {
+        if !self.options.dedup {
+            return true;
+        }
+        if self.seen.iter().any(|seen| seen == id) {
+            return false;
+        }
+        if self.seen.len() == DEDUP_WINDOW {
+            self.seen.pop_front();
+        }
+        self.seen.push_back(id.to_string());
+        true
+    }
+
+    /// Whether `event` passes the `turn_id` filter
+    fn in_turn(&self, event: &Event) -> bool {
+        let Some(turn_id) = &self.options.turn_id else {
+            return true;
+        };
+        let event_turn = event
+            .context
+            .turn_id
+            .as_deref()
+            .or_else(|| event.data.get("turn_id").and_then(|v| v.as_str()));
+        event_turn == Some(turn_id.as_str())
+    }
+
+    /// Apply dedup and the turn filter, advancing `last_event_id`.
+    fn deliver(&mut self, event: Event) -> Option<Event> {
+        if !self.remember(&event.id) {
+            return None;
+        }
+        self.last_event_id = Some(event.id.clone());
+        self.in_turn(&event).then_some(event)
+    }
+
+    /// Track `event`'s sequence and report a gap before it, if any.
+    fn check_gap(&mut self, event: &Event) -> Option<GapDetected> {
+        let options = &self.options;
+        if !(options.detect_gaps || options.backfill_gaps)
+            || !options.types.is_empty()
+            || !options.exclude.is_empty()
+        {
+            return None;
+        }
+        let got = event.sequence?;
+        let previous = self
+            .last_sequence
+            .replace(got.max(self.last_sequence.unwrap_or(got)));
+        match previous {
+            Some(last) if got > last + 1 => Some(GapDetected {
+                expected: last + 1,
+                got,
+            }),
+            _ => None,
+        }
+    }
+
+    fn start_backfill(&mut self, gap: GapDetected) {
+        let client = self.client.clone();
+        let session_id = self.session_id.clone();
+        self.backfill = Some(Box::pin(async move {
+            let options = crate::client::ListEventsOptions {
+                after_sequence: Some(gap.expected - 1),
+                before_sequence: Some(gap.got),
+                ..Default::default()
+            };
+            let result = client
+                .events()
+                .list_with_options(&session_id, &options)
+                .await
+                .map(|page| page.data);
+            (gap, result)
+        }));
+    }
+
+    /// Queue backfilled events ahead of the held one; returns the gap that
+    /// remains unfilled, if any.
+    fn finish_backfill(
+        &mut self,
+        gap: GapDetected,
+        result: Result<Vec<Event>>,
+    ) -> Option<GapDetected> {
+        let mut events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!("SSE gap backfill failed: {}", e);
+                return Some(gap);
+            }
+        };
+        events.retain(|e| {
+            e.sequence
+                .is_some_and(|seq| seq >= gap.expected && seq < gap.got)
+        });
+        events.sort_by_key(|e| e.sequence);
+        let complete = events.len() == (gap.got - gap.expected) as usize;
+        for event in events.into_iter().rev() {
+            self.pending.push_front(event);
+        }
+        (!complete).then_some(gap)
+    }
+
+    fn notice_event(&self, event_type: &str, data: serde_json::Value) -> Event {
+        Event {
+            id: String::new(),
+            event_type: event_type.to_string(),
+            ts: format_rfc3339(std::time::SystemTime::now()),
+            session_id: self.session_id.clone(),
+            data,
+            context: Default::default(),
+            sequence: None,
+        }
+    }
+
+    fn gap_event(&self, gap: &GapDetected) -> Event {
+        self.notice_event(
+            GAP_DETECTED_EVENT,
+            serde_json::to_value(gap).unwrap_or_default(),
+        )
+    }
+
     /// Stop the stream and prevent further reconnection attempts
     pub fn stop(&mut self) {
         self.should_reconnect = false;
         self.inner = None;
         self.delay_future = None;
         self.idle_deadline = None;
+        self.heartbeat_deadline = None;
     }
 
     /// Get the current retry count
@@ -234,6 +559,23 @@ impl EventStream {
         self.retry_count
     }
 
+    /// Adapt the stream to yield only typed `reason.thinking.delta` payloads.
+    ///
+    /// Other events are skipped; stream errors are passed through.
+    pub fn thinking_deltas(self) -> impl Stream<Item = Result<ThinkingDelta>> + Send {
+        use futures::StreamExt;
+
+        self.filter_map(|item| async move {
+            match item {
+                Ok(event) if event.event_type == types::REASON_THINKING_DELTA => {
+                    Some(event.data_as())
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+
     fn connect(&mut self) -> Pin<Box<dyn Stream<Item = Result<Event>> + Send>> {
         let client = self.client.clone();
         let session_id = self.session_id.clone();
@@ -245,31 +587,45 @@ impl EventStream {
         let exclude: Vec<String> = self.options.exclude.clone();
         let connected_signal = self.connected_signal.clone();
         let http_client = self.sse_http_client.clone();
+        let activity = self.activity.clone();
 
         Box::pin(async_stream::try_stream! {
-            use reqwest_eventsource::{Event as SseEvent, RequestBuilderExt};
+            use eventsource_stream::Eventsource;
             use futures::StreamExt;
 
             let types_refs: Vec<&str> = types.iter().map(|s| s.as_str()).collect();
             let exclude_refs: Vec<&str> = exclude.iter().map(|s| s.as_str()).collect();
             let url = client.sse_url(&session_id, since_id.as_deref(), &types_refs, &exclude_refs);
+            // Counted for the retry budget but not held: an open stream must
+            // not keep shutdown waiting.
+            client.start_request()?;
 
             tracing::debug!("Connecting to SSE: {}", url);
 
-            let mut es = http_client
+            let request = http_client
                 .get(url.clone())
                 .headers(client.auth_headers())
                 .header("Accept", "text/event-stream")
-                .header("Cache-Control", "no-cache")
-                .eventsource()
+                .header("Cache-Control", "no-cache");
+            let resp = client
+                .execute(&http_client, request)
+                .await
                 .map_err(|e| Error::Sse(e.to_string()))?;
+            if !resp.status().is_success() {
+                Err(Error::Sse(format!("Invalid status code: {}", resp.status())))?;
+            }
+            tracing::debug!("SSE connection opened");
+
+            // Any bytes, including heartbeat comments the parser drops, prove
+            // the connection is alive.
+            let mut es = resp
+                .bytes_stream()
+                .inspect(move |_| activity.touch())
+                .eventsource();
 
             while let Some(event) = es.next().await {
                 match event {
-                    Ok(SseEvent::Open) => {
-                        tracing::debug!("SSE connection opened");
-                    }
-                    Ok(SseEvent::Message(msg)) => {
+                    Ok(msg) => {
                         // Handle special lifecycle events
                         if msg.event == "connected" {
                             tracing::debug!("SSE connected event received");
@@ -307,16 +663,13 @@ impl EventStream {
                             tracing::debug!("Skipping non-event message: {}", msg.event);
                         }
                     }
-                    Err(reqwest_eventsource::Error::StreamEnded) => {
-                        tracing::debug!("SSE stream ended");
-                        break;
-                    }
                     Err(e) => {
                         tracing::warn!("SSE error: {}", e);
                         Err(Error::Sse(e.to_string()))?;
                     }
                 }
             }
+            tracing::debug!("SSE stream ended");
         })
     }
 
@@ -352,7 +705,21 @@ impl EventStream {
         }
     }
 
+    fn heartbeat_limit(&self) -> Option<Duration> {
+        self.options
+            .heartbeat_interval
+            .map(|interval| interval.mul_f64(HEARTBEAT_GRACE))
+    }
+
     fn schedule_reconnect(&mut self, delay: Duration) {
+        let mut delay = delay;
+        if !self.graceful_disconnect {
+            self.recovering = Some(self.retry_count);
+            if !self.client.try_retry() {
+                tracing::debug!("retry budget spent, delaying SSE reconnect");
+                delay = delay.max(Duration::from_millis(MAX_RETRY_MS));
+            }
+        }
         self.delay_future = Some(Box::pin(sleep(delay)));
     }
 }
@@ -361,7 +728,37 @@ impl Stream for EventStream {
     type Item = Result<Event>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let span = self.span.clone();
+        let _entered = span.enter();
         loop {
+            // Client shutdown ends the stream; last_event_id is kept for resuming.
+            if let Some(ref mut shutdown) = self.shutdown
+                && shutdown.as_mut().poll(cx).is_ready()
+            {
+                self.shutdown = None;
+                self.stop();
+                return Poll::Ready(None);
+            }
+
+            // Finish an in-flight gap backfill before reading further.
+            if let Some(ref mut backfill) = self.backfill {
+                let Poll::Ready((gap, result)) = backfill.as_mut().poll(cx) else {
+                    return Poll::Pending;
+                };
+                self.backfill = None;
+                if let Some(gap) = self.finish_backfill(gap, result) {
+                    return Poll::Ready(Some(Ok(self.gap_event(&gap))));
+                }
+            }
+
+            // Yield events held back by a gap notice or backfill.
+            if let Some(event) = self.pending.pop_front() {
+                if let Some(event) = self.deliver(event) {
+                    return Poll::Ready(Some(Ok(event)));
+                }
+                continue;
+            }
+
             // Check if we're waiting for a delay before reconnecting
             if let Some(ref mut delay) = self.delay_future {
                 match Pin::new(delay).poll(cx) {
@@ -381,6 +778,12 @@ impl Stream for EventStream {
             // the connection is healthy, so reset backoff/retry state.
             if self.connected_signal.swap(false, Ordering::Acquire) {
                 self.reset_backoff();
+                if let Some(attempts) = self.recovering.take()
+                    && self.options.reconnect_notices
+                {
+                    let data = serde_json::json!({ "attempts": attempts });
+                    return Poll::Ready(Some(Ok(self.notice_event(RECONNECTED_EVENT, data))));
+                }
             }
 
             if self.inner.is_none() {
@@ -390,6 +793,9 @@ impl Stream for EventStream {
                 self.inner = Some(self.connect());
                 // Start idle timer when a new connection is established
                 self.idle_deadline = Some(Box::pin(sleep(self.idle_timeout)));
+                self.activity.touch();
+                self.heartbeat_deadline =
+                    self.heartbeat_limit().map(|limit| Box::pin(sleep(limit)));
             }
 
             // Check idle timeout — detects half-open TCP connections where
@@ -413,14 +819,59 @@ impl Stream for EventStream {
                 return Poll::Ready(None);
             }
 
+            // Check heartbeat liveness — any bytes on the wire count.
+            if let Some(limit) = self.heartbeat_limit()
+                && let Some(ref mut deadline) = self.heartbeat_deadline
+                && deadline.as_mut().poll(cx).is_ready()
+            {
+                let silent = self.activity.silent_for();
+                if silent < limit {
+                    self.heartbeat_deadline = Some(Box::pin(sleep(limit - silent)));
+                    continue;
+                }
+                tracing::warn!(
+                    silent_ms = silent.as_millis() as u64,
+                    "SSE heartbeat missed, reconnecting"
+                );
+                self.inner = None;
+                self.idle_deadline = None;
+                self.heartbeat_deadline = None;
+                if self.should_retry() {
+                    self.retry_count += 1;
+                    let delay = self.get_retry_delay();
+                    self.update_backoff();
+                    self.schedule_reconnect(delay);
+                } else {
+                    self.should_reconnect = false;
+                }
+                return Poll::Ready(Some(Err(Error::HeartbeatMissed {
+                    interval_ms: self
+                        .options
+                        .heartbeat_interval
+                        .unwrap_or_default()
+                        .as_millis() as u64,
+                    silent_ms: silent.as_millis() as u64,
+                })));
+            }
+
             let inner = self.inner.as_mut().unwrap();
             match Pin::new(inner).poll_next(cx) {
                 Poll::Ready(Some(Ok(event))) => {
                     // Successfully received an event - reset backoff and idle timer
                     self.reset_backoff();
-                    self.last_event_id = Some(event.id.clone());
                     self.idle_deadline = Some(Box::pin(sleep(self.idle_timeout)));
-                    return Poll::Ready(Some(Ok(event)));
+                    if let Some(gap) = self.check_gap(&event) {
+                        self.pending.push_back(event);
+                        if self.options.backfill_gaps {
+                            self.start_backfill(gap);
+                            continue;
+                        }
+                        return Poll::Ready(Some(Ok(self.gap_event(&gap))));
+                    }
+                    match self.deliver(event) {
+                        Some(event) => return Poll::Ready(Some(Ok(event))),
+                        None => continue,
+                    }
                 }
                 Poll::Ready(Some(Err(e))) => {
                     // Check if this is a graceful disconnect
@@ -483,12 +934,42 @@ impl Stream for EventStream {
 
                     return Poll::Ready(None);
                 }
+                // A `connected` event was consumed; handle it at the loop top.
+                Poll::Pending if self.connected_signal.load(Ordering::Acquire) => continue,
                 Poll::Pending => return Poll::Pending,
             }
         }
     }
 }
 
+/// Format a timestamp as RFC 3339 in UTC with millisecond precision.
+fn format_rfc3339(time: std::time::SystemTime) -> String {
+    let since_epoch = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = since_epoch.as_secs() as i64;
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Civil date from days since the epoch (Howard Hinnant).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -528,6 +1009,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_stream_options_for_ui() {
+        let opts = StreamOptions::for_ui();
+        assert!(opts.exclude.is_empty());
+        assert!(opts.dedup);
+        assert!(opts.reconnect_notices);
+    }
+
+    #[test]
+    fn test_format_rfc3339() {
+        let ts = std::time::UNIX_EPOCH + Duration::from_millis(1_705_314_600_250);
+        assert_eq!(format_rfc3339(ts), "2024-01-15T10:30:00.250Z");
+        assert_eq!(
+            format_rfc3339(std::time::UNIX_EPOCH),
+            "1970-01-01T00:00:00.000Z"
+        );
+    }
+
     #[test]
     fn test_disconnecting_data_parse() {
         let json = r#"{"reason":"connection_cycle","retry_ms":100}"#;
@@ -0,0 +1,281 @@
+//! Local SQLite mirror of sessions, messages and events.
+//!
+//! [`LocalStore`] keeps a copy of what the SDK fetches so desktop apps can
+//! show history offline and render a session before the network answers.
+//! [`LocalStore::sync_session`] fetches only what is new, using the last
+//! stored message sequence and event ID as cursors:
+//!
+//! ```no_run
+//! use everruns_sdk::store::LocalStore;
+//!
+//! # async fn example(client: everruns_sdk::Everruns) -> Result<(), everruns_sdk::Error> {
+//! let store = LocalStore::open("history.db")?;
+//! for message in store.messages("sess_123")? {
+//!     println!("{}: {:?}", message.sequence, message.content);
+//! }
+//! let synced = store.sync_session(&client, "sess_123").await?;
+//! println!("{} new messages", synced.messages);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Events received live can be mirrored as they arrive with
+//! [`LocalStore::record_event`]. Records are stored as JSON documents
+//! keyed by ID, so syncing the same data twice adds nothing. Database calls
+//! block the calling thread briefly on local disk I/O.
+
+use crate::client::{Everruns, ListEventsOptions};
+use crate::error::{Error, Result};
+use crate::models::{Event, Message, Session};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::sync::Mutex;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    json TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS messages (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    sequence INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS messages_by_session ON messages (session_id, sequence);
+CREATE TABLE IF NOT EXISTS events (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_by_session ON events (session_id);
+";
+
+/// What one [`LocalStore::sync_session`] call added
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SyncReport {
+    pub messages: usize,
+    pub events: usize,
+}
+
+/// SQLite-backed mirror of session history.
+pub struct LocalStore {
+    conn: Mutex<Connection>,
+}
+
+impl LocalStore {
+    /// Open or create a store at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path).map_err(db_error)?)
+    }
+
+    /// Create a store that lives only in memory
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Fetch a session and the messages and events added since the last
+    /// sync, and store them.
+    ///
+    /// Messages are fetched after the highest stored `sequence`, events
+    /// after the last stored event ID.
+    pub async fn sync_session(&self, client: &Everruns, session_id: &str) -> Result<SyncReport> {
+        let session = client.sessions().get(session_id).await?;
+        let messages = match self.last_message_sequence(session_id)? {
+            Some(after_sequence) => {
+                client
+                    .messages()
+                    .list_since(session_id, after_sequence)
+                    .await?
+            }
+            None => client.messages().list(session_id).await?.data,
+        };
+        let options = ListEventsOptions {
+            since_id: self.last_event_id(session_id)?,
+            ..Default::default()
+        };
+        let events = client
+            .events()
+            .list_with_options(session_id, &options)
+            .await?
+            .data;
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(db_error)?;
+        upsert_session(&tx, &session)?;
+        let mut report = SyncReport::default();
+        for message in &messages {
+            report.messages += insert_message(&tx, message)?;
+        }
+        for event in &events {
+            report.events += insert_event(&tx, event)?;
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(report)
+    }
+
+    /// Store an event received from a live stream. Returns whether it was new.
+    ///
+    /// `input.message` and `output.message.completed` events also store
+    /// their message.
+    pub fn record_event(&self, event: &Event) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        if let Some(message) = event.data.get("message")
+            && let Ok(message) = serde_json::from_value::<Message>(message.clone())
+        {
+            insert_message(&conn, &message)?;
+        }
+        Ok(insert_event(&conn, event)? > 0)
+    }
+
+    /// Store a session, replacing an older copy
+    pub fn record_session(&self, session: &Session) -> Result<()> {
+        upsert_session(&self.conn.lock().unwrap(), session)
+    }
+
+    /// A stored session
+    pub fn session(&self, session_id: &str) -> Result<Option<Session>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT json FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(db_error)?
+        .map(|json| parse(&json))
+        .transpose()
+    }
+
+    /// All stored sessions
+    pub fn sessions(&self) -> Result<Vec<Session>> {
+        self.query("SELECT json FROM sessions ORDER BY id", [])
+    }
+
+    /// Stored messages of a session, oldest first
+    pub fn messages(&self, session_id: &str) -> Result<Vec<Message>> {
+        self.query(
+            "SELECT json FROM messages WHERE session_id = ?1 ORDER BY sequence",
+            [session_id],
+        )
+    }
+
+    /// Stored events of a session, in the order they were received
+    pub fn events(&self, session_id: &str) -> Result<Vec<Event>> {
+        self.query(
+            "SELECT json FROM events WHERE session_id = ?1 ORDER BY rowid",
+            [session_id],
+        )
+    }
+
+    /// Highest stored message sequence of a session
+    pub fn last_message_sequence(&self, session_id: &str) -> Result<Option<u64>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT MAX(sequence) FROM messages WHERE session_id = ?1",
+            [session_id],
+            |row| row.get::<_, Option<i64>>(0),
+        )
+        .map(|sequence| sequence.map(|s| s as u64))
+        .map_err(db_error)
+    }
+
+    /// ID of the last stored event of a session
+    pub fn last_event_id(&self, session_id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id FROM events WHERE session_id = ?1 ORDER BY rowid DESC LIMIT 1",
+            [session_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(db_error)
+    }
+
+    /// Delete a session and its messages and events
+    pub fn remove_session(&self, session_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tables = [
+            ("sessions", "id"),
+            ("messages", "session_id"),
+            ("events", "session_id"),
+        ];
+        for (table, column) in tables {
+            conn.execute(
+                &format!("DELETE FROM {table} WHERE {column} = ?1"),
+                [session_id],
+            )
+            .map_err(db_error)?;
+        }
+        Ok(())
+    }
+
+    fn query<T: DeserializeOwned>(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<T>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(sql).map_err(db_error)?;
+        let rows = stmt
+            .query_map(params, |row| row.get::<_, String>(0))
+            .map_err(db_error)?;
+        rows.map(|json| parse(&json.map_err(db_error)?)).collect()
+    }
+}
+
+impl std::fmt::Debug for LocalStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalStore").finish_non_exhaustive()
+    }
+}
+
+fn upsert_session(conn: &Connection, session: &Session) -> Result<()> {
+    conn.execute(
+        "INSERT INTO sessions (id, json) VALUES (?1, ?2)
+         ON CONFLICT (id) DO UPDATE SET json = excluded.json",
+        params![session.id, serde_json::to_string(session)?],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Insert a message unless stored already; returns the rows added.
+fn insert_message(conn: &Connection, message: &Message) -> Result<usize> {
+    conn.execute(
+        "INSERT OR IGNORE INTO messages (id, session_id, sequence, json) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            message.id,
+            message.session_id,
+            message.sequence as i64,
+            serde_json::to_string(message)?
+        ],
+    )
+    .map_err(db_error)
+}
+
+/// Insert an event unless stored already; returns the rows added.
+fn insert_event(conn: &Connection, event: &Event) -> Result<usize> {
+    conn.execute(
+        "INSERT OR IGNORE INTO events (id, session_id, json) VALUES (?1, ?2, ?3)",
+        params![event.id, event.session_id, serde_json::to_string(event)?],
+    )
+    .map_err(db_error)
+}
+
+fn parse<T: DeserializeOwned>(json: &str) -> Result<T> {
+    Ok(serde_json::from_str(json)?)
+}
+
+fn db_error(err: rusqlite::Error) -> Error {
+    Error::Validation(format!("local store error: {err}"))
+}
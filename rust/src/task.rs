@@ -0,0 +1,46 @@
+//! Named background tasks.
+//!
+//! Task names show up in tokio-console when the `tokio-console` feature is
+//! enabled and the application is built with `RUSTFLAGS="--cfg tokio_unstable"`;
+//! otherwise these are plain spawns.
+
+use std::future::Future;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+/// Spawn `future` on the current runtime as a task called `name`.
+pub(crate) fn spawn_named<F>(name: &'static str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_named_on(name, future, &Handle::current())
+}
+
+/// Spawn `future` on `runtime` as a task called `name`.
+///
+/// The future runs inside a span of the same name, parented to the caller's
+/// current span.
+pub(crate) fn spawn_named_on<F>(
+    name: &'static str,
+    future: F,
+    runtime: &Handle,
+) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let future = future.instrument(tracing::debug_span!("task", task.name = name));
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    {
+        tokio::task::Builder::new()
+            .name(name)
+            .spawn_on(future, runtime)
+            .expect("spawning a task on a running runtime")
+    }
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        runtime.spawn(future)
+    }
+}
@@ -0,0 +1,255 @@
+//! Terminal output of tool calls.
+//!
+//! Shell-like tools stream their output as `tool.output.delta` events, split
+//! at arbitrary byte boundaries and tagged `stdout` or `stderr`. The adapters
+//! here turn a session event stream into something a TUI can consume
+//! directly: [`lines`] yields whole lines tagged with their output stream,
+//! and [`reader`] exposes the raw output as a [`tokio::io::AsyncRead`] for
+//! terminal emulators that interpret escape sequences themselves.
+
+use crate::error::{Error, Result};
+use crate::events::types;
+use crate::models::{Event, ToolCompletedData, ToolOutputDeltaData};
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// One line of tool output
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TerminalLine {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    /// Output stream identifier (e.g. `stdout`, `stderr`)
+    pub stream: String,
+    /// Line text without the trailing newline
+    pub text: String,
+}
+
+impl TerminalLine {
+    /// Whether the line was written to stderr, e.g. to render it in red
+    pub fn is_stderr(&self) -> bool {
+        self.stream == "stderr"
+    }
+}
+
+/// Partial lines, per tool call and output stream.
+#[derive(Default)]
+struct LineBuffer {
+    partial: HashMap<(String, String), (String, String)>,
+}
+
+impl LineBuffer {
+    /// Append a delta and return the lines it completes.
+    fn push(&mut self, delta: ToolOutputDeltaData) -> Vec<TerminalLine> {
+        let key = (delta.tool_call_id, delta.stream);
+        let (tool_name, buf) = self
+            .partial
+            .entry(key.clone())
+            .or_insert_with(|| (delta.tool_name, String::new()));
+        buf.push_str(&delta.delta);
+
+        let mut lines = Vec::new();
+        while let Some(end) = buf.find('\n') {
+            let text = buf[..end].trim_end_matches('\r').to_string();
+            buf.drain(..=end);
+            lines.push(TerminalLine {
+                tool_call_id: key.0.clone(),
+                tool_name: tool_name.clone(),
+                stream: key.1.clone(),
+                text,
+            });
+        }
+        lines
+    }
+
+    /// Flush unterminated lines of one tool call, or of all calls.
+    fn flush(&mut self, tool_call_id: Option<&str>) -> Vec<TerminalLine> {
+        let keys: Vec<(String, String)> = self
+            .partial
+            .keys()
+            .filter(|(id, _)| tool_call_id.is_none_or(|wanted| id == wanted))
+            .cloned()
+            .collect();
+        let mut lines = Vec::new();
+        for key in keys {
+            let Some((tool_name, text)) = self.partial.remove(&key) else {
+                continue;
+            };
+            if !text.is_empty() {
+                lines.push(TerminalLine {
+                    tool_call_id: key.0,
+                    tool_name,
+                    stream: key.1,
+                    text,
+                });
+            }
+        }
+        lines
+    }
+}
+
+/// Turn session events into lines of tool output.
+///
+/// Output is buffered per tool call and stream until a newline arrives; an
+/// unterminated last line is emitted when its tool completes or the event
+/// stream ends. Events other than tool output are skipped.
+pub fn lines<S>(events: S) -> Pin<Box<dyn Stream<Item = Result<TerminalLine>> + Send>>
+where
+    S: Stream<Item = Result<Event>> + Send + 'static,
+{
+    Box::pin(async_stream::try_stream! {
+        let mut buffer = LineBuffer::default();
+        futures::pin_mut!(events);
+        while let Some(event) = events.next().await {
+            let event = match event {
+                Err(Error::HeartbeatMissed { .. }) => continue,
+                event => event?,
+            };
+            let lines = match event.event_type.as_str() {
+                types::TOOL_OUTPUT_DELTA => buffer.push(event.data_as()?),
+                types::TOOL_COMPLETED => {
+                    let data: ToolCompletedData = event.data_as()?;
+                    buffer.flush(Some(&data.tool_call_id))
+                }
+                _ => continue,
+            };
+            for line in lines {
+                yield line;
+            }
+        }
+        for line in buffer.flush(None) {
+            yield line;
+        }
+    })
+}
+
+/// Expose the raw tool output in session events as an [`AsyncRead`].
+///
+/// stdout and stderr are interleaved in arrival order, bytes unchanged, so
+/// ANSI escape sequences reach the terminal emulator intact. Stream errors
+/// surface as [`std::io::Error`]s wrapping the SDK [`Error`].
+pub fn reader<S>(events: S) -> TerminalReader
+where
+    S: Stream<Item = Result<Event>> + Send + 'static,
+{
+    TerminalReader {
+        events: Box::pin(events),
+        chunk: Vec::new(),
+        pos: 0,
+    }
+}
+
+/// [`AsyncRead`] over tool output, created by [`reader`].
+pub struct TerminalReader {
+    events: Pin<Box<dyn Stream<Item = Result<Event>> + Send>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl AsyncRead for TerminalReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        while self.pos >= self.chunk.len() {
+            let event = match self.events.poll_next_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                // End of stream: a read of zero bytes signals EOF.
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Ready(Some(Err(Error::HeartbeatMissed { .. }))) => continue,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(io_error(e))),
+                Poll::Ready(Some(Ok(event))) => event,
+            };
+            if event.event_type != types::TOOL_OUTPUT_DELTA {
+                continue;
+            }
+            let data: ToolOutputDeltaData = event.data_as().map_err(io_error)?;
+            self.chunk = data.delta.into_bytes();
+            self.pos = 0;
+        }
+        let n = buf.remaining().min(self.chunk.len() - self.pos);
+        let start = self.pos;
+        buf.put_slice(&self.chunk[start..start + n]);
+        self.pos += n;
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn io_error(e: Error) -> std::io::Error {
+    std::io::Error::other(e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    fn event(event_type: &str, data: serde_json::Value) -> Result<Event> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": "evt",
+            "type": event_type,
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "data": data
+        }))
+        .unwrap())
+    }
+
+    fn delta(call: &str, stream: &str, text: &str) -> Result<Event> {
+        event(
+            types::TOOL_OUTPUT_DELTA,
+            serde_json::json!({
+                "tool_call_id": call, "tool_name": "bash", "delta": text, "stream": stream
+            }),
+        )
+    }
+
+    fn events() -> Vec<Result<Event>> {
+        vec![
+            delta("call_1", "stdout", "hel"),
+            delta("call_1", "stderr", "warn: x\n"),
+            event(types::TURN_STARTED, serde_json::json!({})),
+            delta("call_1", "stdout", "lo\r\nwor"),
+            event(
+                types::TOOL_COMPLETED,
+                serde_json::json!({
+                    "tool_call_id": "call_1", "tool_name": "bash",
+                    "success": true, "status": "success"
+                }),
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn lines_are_reassembled_per_stream_and_flushed_on_completion() {
+        let lines: Vec<(String, bool)> = lines(futures::stream::iter(events()))
+            .map(|line| {
+                let line = line.unwrap();
+                (line.text.clone(), line.is_stderr())
+            })
+            .collect()
+            .await;
+        assert_eq!(
+            lines,
+            vec![
+                ("warn: x".to_string(), true),
+                ("hello".to_string(), false),
+                ("wor".to_string(), false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reader_yields_raw_output_bytes() {
+        let mut out = String::new();
+        reader(futures::stream::iter(events()))
+            .read_to_string(&mut out)
+            .await
+            .unwrap();
+        assert_eq!(out, "helwarn: x\nlo\r\nwor");
+    }
+}
@@ -0,0 +1,112 @@
+//! Token counting for prompt budgets.
+//!
+//! The API has no tokenizer endpoint, so counts are computed locally. With
+//! the `tiktoken` feature, models that use an OpenAI tokenizer are counted
+//! exactly; everything else gets an estimate of one token per four
+//! characters, which is close for English prose and errs high for code.
+//!
+//! [`TokensClient::count`](crate::client::TokensClient::count) also looks
+//! up the model's limits, to check a prompt fits before sending it:
+//!
+//! ```no_run
+//! # async fn example(client: everruns_sdk::Everruns, prompt: &str) -> Result<(), everruns_sdk::Error> {
+//! let count = client.tokens().count("gpt-4o", prompt).await?;
+//! if !count.fits() {
+//!     println!("prompt is {} tokens over budget", count.tokens - count.max_input.unwrap());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+/// Token count of some content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenCount {
+    pub tokens: u64,
+    /// Whether the count comes from the model's tokenizer rather than an estimate
+    pub exact: bool,
+    /// The model's context window, when known
+    pub context_window: Option<u64>,
+    /// Tokens available for the prompt, when known
+    /// (see [`ModelLimits::max_input`](crate::models::ModelLimits::max_input))
+    pub max_input: Option<u64>,
+}
+
+impl TokenCount {
+    /// Count `text` for a provider-side model name (e.g. `gpt-4o`), without
+    /// model limits.
+    pub fn of(model: &str, text: &str) -> Self {
+        let (tokens, exact) = match tokenize(model, text) {
+            Some(tokens) => (tokens, true),
+            None => (estimate(text), false),
+        };
+        Self {
+            tokens,
+            exact,
+            context_window: None,
+            max_input: None,
+        }
+    }
+
+    /// Prompt tokens left under the model's input limit, when known
+    pub fn remaining(&self) -> Option<u64> {
+        self.max_input
+            .map(|max_input| max_input.saturating_sub(self.tokens))
+    }
+
+    /// Whether the content fits the model's input limit; true when the limit
+    /// is unknown
+    pub fn fits(&self) -> bool {
+        self.max_input
+            .is_none_or(|max_input| self.tokens <= max_input)
+    }
+}
+
+/// Rough token count: one token per four characters, rounded up.
+pub fn estimate(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+#[cfg(feature = "tiktoken")]
+fn tokenize(model: &str, text: &str) -> Option<u64> {
+    use tiktoken_rs::tokenizer::{Tokenizer, get_tokenizer};
+
+    let bpe = match get_tokenizer(model)? {
+        Tokenizer::O200kBase => tiktoken_rs::o200k_base_singleton(),
+        Tokenizer::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+        Tokenizer::P50kBase => tiktoken_rs::p50k_base_singleton(),
+        Tokenizer::P50kEdit => tiktoken_rs::p50k_edit_singleton(),
+        Tokenizer::R50kBase | Tokenizer::Gpt2 => tiktoken_rs::r50k_base_singleton(),
+    };
+    Some(bpe.encode_with_special_tokens(text).len() as u64)
+}
+
+#[cfg(not(feature = "tiktoken"))]
+fn tokenize(_model: &str, _text: &str) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_round_up_and_limits_are_checked() {
+        assert_eq!(estimate(""), 0);
+        assert_eq!(estimate("hello"), 2);
+
+        let mut count = TokenCount::of("claude-sonnet-4", "hello world!");
+        assert_eq!((count.tokens, count.exact), (3, false));
+        assert!(count.fits());
+        count.max_input = Some(2);
+        assert!(!count.fits());
+        assert_eq!(count.remaining(), Some(0));
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn openai_models_are_counted_exactly() {
+        let count = TokenCount::of("gpt-4o", "hello world");
+        assert_eq!((count.tokens, count.exact), (2, true));
+    }
+}
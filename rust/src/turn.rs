@@ -0,0 +1,548 @@
+//! Typed turn lifecycle tracking built on the session event stream.
+//!
+//! A [`TurnHandle`] follows the turn triggered by a single input message:
+//! it filters the session's SSE stream down to that turn, tracks its
+//! [`TurnStatus`], and accumulates output messages and token usage.
+
+use crate::client::Everruns;
+use crate::error::{Error, Result};
+use crate::events::types;
+use crate::models::{
+    ContentPart, CreateMessageRequest, CreateSessionRequest, Event, Message, MessageRole,
+    TokenUsage,
+};
+use crate::sse::EventStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// Lifecycle state of a turn
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnStatus {
+    /// Input message accepted, turn not started yet
+    Pending,
+    /// `turn.started` received
+    Running,
+    /// `turn.completed` received
+    Completed,
+    /// `turn.failed` received
+    Failed,
+    /// `turn.cancelled` received
+    Cancelled,
+}
+
+impl TurnStatus {
+    /// Whether the turn has reached a final state
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Why the model stopped generating
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum StopReason {
+    /// Model finished its answer
+    EndTurn,
+    /// Output was truncated by the token limit
+    MaxTokens,
+    /// Model stopped to call a tool
+    ToolUse,
+    /// Output was blocked by a content filter
+    ContentFilter,
+    /// Turn was cancelled
+    Cancelled,
+    /// Reason not known to this SDK version
+    Other(String),
+}
+
+impl StopReason {
+    /// Wire name of the reason
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::EndTurn => "end_turn",
+            Self::MaxTokens => "max_tokens",
+            Self::ToolUse => "tool_use",
+            Self::ContentFilter => "content_filter",
+            Self::Cancelled => "cancelled",
+            Self::Other(reason) => reason,
+        }
+    }
+}
+
+impl From<String> for StopReason {
+    /// Parse a wire name or a provider finish reason, as reported in
+    /// `llm.generation` metadata (`stop`, `length` and `tool_calls` from
+    /// OpenAI-style providers).
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "end_turn" | "stop" => Self::EndTurn,
+            "max_tokens" | "length" => Self::MaxTokens,
+            "tool_use" | "tool_calls" => Self::ToolUse,
+            "content_filter" => Self::ContentFilter,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Other(reason),
+        }
+    }
+}
+
+impl From<StopReason> for String {
+    fn from(reason: StopReason) -> Self {
+        match reason {
+            StopReason::Other(reason) => reason,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Final result of a turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TurnOutcome {
+    pub session_id: String,
+    pub input_message_id: String,
+    #[serde(default)]
+    pub turn_id: Option<String>,
+    /// Server trace ID of the turn, as in `context.trace_id` of its events
+    /// and webhook deliveries
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    pub status: TurnStatus,
+    /// Output messages emitted during the turn, in order
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Token usage for the turn: the total reported by `turn.completed` or
+    /// `turn.cancelled`, or the sum over output messages until then
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+    /// Error message from `turn.failed`
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Why the turn's last LLM generation stopped, from the `finish_reasons`
+    /// of its `llm.generation` event; `Cancelled` for cancelled turns
+    #[serde(default)]
+    pub stop_reason: Option<StopReason>,
+    /// LLM iterations the turn took, from `turn.completed`; compare with
+    /// the session's `max_iterations` to tell whether the turn was cut short
+    #[serde(default)]
+    pub iterations: Option<u32>,
+}
+
+impl TurnOutcome {
+    /// Text of the agent's output messages, concatenated in order
+    pub fn text(&self) -> String {
+        self.messages
+            .iter()
+            .filter(|message| matches!(message.role, MessageRole::Agent))
+            .flat_map(|message| &message.content)
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Fold a continuation turn into this outcome.
+    pub(crate) fn absorb(&mut self, next: TurnOutcome) {
+        self.turn_id = next.turn_id.or(self.turn_id.take());
+        self.trace_id = self.trace_id.take().or(next.trace_id);
+        self.status = next.status;
+        self.messages.extend(next.messages);
+        self.usage = match (self.usage.take(), next.usage) {
+            (Some(total), Some(more)) => Some(total + more),
+            (total, more) => total.or(more),
+        };
+        self.error = next.error;
+        self.stop_reason = next.stop_reason;
+        self.iterations = match (self.iterations, next.iterations) {
+            (Some(total), Some(more)) => Some(total + more),
+            (total, more) => total.or(more),
+        };
+    }
+}
+
+/// Caller-supplied future that cancels a run when it completes
+pub(crate) type CancelSignal = futures::future::Shared<futures::future::BoxFuture<'static, ()>>;
+
+/// Options for [`MessagesClient::run_with_options`](crate::client::MessagesClient::run_with_options)
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct RunOptions {
+    /// Follow-up turns allowed when output stops at the token limit
+    pub max_continuations: u32,
+    /// User message sent to ask the agent to continue
+    pub continue_prompt: String,
+    /// Set with [`cancel_on`](Self::cancel_on)
+    pub(crate) cancel: Option<CancelSignal>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            max_continuations: 0,
+            continue_prompt: "Continue exactly where you left off.".to_string(),
+            cancel: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for RunOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunOptions")
+            .field("max_continuations", &self.max_continuations)
+            .field("continue_prompt", &self.continue_prompt)
+            .field("cancel", &self.cancel.is_some())
+            .finish()
+    }
+}
+
+impl RunOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Continue up to `max_continuations` times while the turn stops with
+    /// [`StopReason::MaxTokens`] (a last `llm.generation` finishing with
+    /// `length` or `max_tokens`), merging the follow-up turns into one outcome.
+    pub fn auto_continue(mut self, max_continuations: u32) -> Self {
+        self.max_continuations = max_continuations;
+        self
+    }
+
+    pub fn continue_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.continue_prompt = prompt.into();
+        self
+    }
+
+    /// Cancel the turn server-side when `signal` completes, for example on
+    /// `tokio::signal::ctrl_c()`. The outcome is then returned as cancelled
+    /// and no further continuations are sent.
+    pub fn cancel_on(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        use futures::FutureExt;
+
+        self.cancel = Some(signal.boxed().shared());
+        self
+    }
+}
+
+/// Handle to a single in-flight turn.
+///
+/// Created by [`MessagesClient::send`](crate::client::MessagesClient::send).
+pub struct TurnHandle {
+    client: Everruns,
+    stream: EventStream,
+    outcome: TurnOutcome,
+}
+
+impl TurnHandle {
+    pub(crate) fn new(
+        client: Everruns,
+        stream: EventStream,
+        session_id: String,
+        input_message_id: String,
+    ) -> Self {
+        Self {
+            client,
+            stream,
+            outcome: TurnOutcome {
+                session_id,
+                input_message_id,
+                turn_id: None,
+                trace_id: None,
+                status: TurnStatus::Pending,
+                messages: Vec::new(),
+                usage: None,
+                error: None,
+                stop_reason: None,
+                iterations: None,
+            },
+        }
+    }
+
+    /// Session the turn runs in
+    pub fn session_id(&self) -> &str {
+        &self.outcome.session_id
+    }
+
+    /// ID of the input message that triggered the turn
+    pub fn input_message_id(&self) -> &str {
+        &self.outcome.input_message_id
+    }
+
+    /// Turn ID, known once `turn.started` (or a turn-scoped event) arrives
+    pub fn turn_id(&self) -> Option<&str> {
+        self.outcome.turn_id.as_deref()
+    }
+
+    /// Current lifecycle state
+    pub fn status(&self) -> TurnStatus {
+        self.outcome.status
+    }
+
+    /// Output messages received so far
+    pub fn messages(&self) -> &[Message] {
+        &self.outcome.messages
+    }
+
+    /// Token usage accumulated so far
+    pub fn usage(&self) -> Option<&TokenUsage> {
+        self.outcome.usage.as_ref()
+    }
+
+    /// Request cancellation of the turn.
+    ///
+    /// The turn reaches [`TurnStatus::Cancelled`] once the server emits
+    /// `turn.cancelled`; keep polling or call [`await_completion`](Self::await_completion).
+    pub async fn cancel(&self) -> Result<()> {
+        self.client
+            .sessions()
+            .cancel(&self.outcome.session_id)
+            .await
+    }
+
+    /// Start a subagent: a child session of `agent_id` answering `prompt`.
+    ///
+    /// The child is tagged with this turn's session and, once known, its
+    /// turn ID, so supervisors can find their workers again with
+    /// [`SessionsClient::children`](crate::client::SessionsClient::children).
+    /// Returns the handle of the child's first turn.
+    pub async fn spawn_subagent(
+        &self,
+        agent_id: &str,
+        prompt: impl Into<String>,
+    ) -> Result<TurnHandle> {
+        let mut tags = vec![crate::client::parent_session_tag(&self.outcome.session_id)];
+        if let Some(turn_id) = &self.outcome.turn_id {
+            tags.push(crate::client::parent_turn_tag(turn_id));
+        }
+        let child = self
+            .client
+            .sessions()
+            .create_with_options(CreateSessionRequest::new().agent_id(agent_id).tags(tags))
+            .await?;
+        self.client
+            .messages()
+            .send(&child.id, CreateMessageRequest::user_text(prompt))
+            .await
+    }
+
+    /// Receive the next event belonging to this turn, updating turn state.
+    ///
+    /// Returns `None` once the turn is terminal or the stream ends.
+    pub async fn next_event(&mut self) -> Option<Result<Event>> {
+        if self.outcome.status.is_terminal() {
+            return None;
+        }
+        while let Some(item) = self.stream.next().await {
+            let event = match item {
+                Ok(event) => event,
+                // The stream reconnects by itself after a missed heartbeat.
+                Err(Error::HeartbeatMissed { .. }) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            if !self.belongs_to_turn(&event) {
+                continue;
+            }
+            self.apply(&event);
+            if self.outcome.status.is_terminal() {
+                self.stream.stop();
+            }
+            return Some(Ok(event));
+        }
+        None
+    }
+
+    /// Drive the turn to completion, cancelling it if `signal` resolves first.
+    ///
+    /// When `signal` fires, the turn is cancelled server-side, the event
+    /// stream is shut down, and the outcome is returned with
+    /// [`TurnStatus::Cancelled`]. If the returned future is dropped before
+    /// the turn finishes (e.g. the calling task is aborted), a cancel request
+    /// is still sent in the background so the turn stops consuming tokens.
+    #[tracing::instrument(
+        name = "everruns.turn",
+        skip_all,
+        fields(session_id = %self.outcome.session_id, message_id = %self.outcome.input_message_id)
+    )]
+    pub async fn await_completion_or_cancel<F>(mut self, signal: F) -> Result<TurnOutcome>
+    where
+        F: Future<Output = ()>,
+    {
+        let mut guard = CancelOnDrop {
+            client: self.client.clone(),
+            session_id: self.outcome.session_id.clone(),
+            armed: true,
+        };
+        tokio::pin!(signal);
+        loop {
+            tokio::select! {
+                event = self.next_event() => match event {
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        guard.armed = false;
+                        return Err(e);
+                    }
+                    None => break,
+                },
+                _ = &mut signal => {
+                    guard.armed = false;
+                    self.stream.stop();
+                    self.cancel().await?;
+                    self.outcome.status = TurnStatus::Cancelled;
+                    self.outcome.stop_reason = Some(StopReason::Cancelled);
+                    return Ok(self.outcome);
+                }
+            }
+        }
+        guard.armed = false;
+        if !self.outcome.status.is_terminal() {
+            return Err(Error::Sse(
+                "event stream ended before the turn finished".to_string(),
+            ));
+        }
+        Ok(self.outcome)
+    }
+
+    /// Drive the turn to completion and return its outcome.
+    #[tracing::instrument(
+        name = "everruns.turn",
+        skip_all,
+        fields(session_id = %self.outcome.session_id, message_id = %self.outcome.input_message_id)
+    )]
+    pub async fn await_completion(mut self) -> Result<TurnOutcome> {
+        while let Some(event) = self.next_event().await {
+            event?;
+        }
+        if !self.outcome.status.is_terminal() {
+            return Err(Error::Sse(
+                "event stream ended before the turn finished".to_string(),
+            ));
+        }
+        Ok(self.outcome)
+    }
+
+    /// Replace the running per-message sum with the turn's own usage total.
+    fn take_turn_usage(&mut self, event: &Event) {
+        if let Some(usage) = usage_from(event) {
+            self.outcome.usage = Some(usage);
+        }
+    }
+
+    /// Record the finish reason of an `llm.generation` event.
+    fn take_stop_reason(&mut self, event: &Event) {
+        if let Some(reason) = event
+            .data
+            .pointer("/metadata/finish_reasons")
+            .and_then(|v| v.as_array())
+            .and_then(|reasons| reasons.last())
+            .and_then(|v| v.as_str())
+        {
+            self.outcome.stop_reason = Some(StopReason::from(reason.to_string()));
+        }
+    }
+
+    fn belongs_to_turn(&self, event: &Event) -> bool {
+        if let (Some(ours), Some(theirs)) = (&self.outcome.turn_id, &event.context.turn_id) {
+            return ours == theirs;
+        }
+        let input_id = &self.outcome.input_message_id;
+        event.context.input_message_id.as_ref() == Some(input_id)
+            || event.data.get("input_message_id").and_then(|v| v.as_str())
+                == Some(input_id.as_str())
+    }
+
+    fn apply(&mut self, event: &Event) {
+        if self.outcome.turn_id.is_none() {
+            self.outcome.turn_id = event.context.turn_id.clone().or_else(|| {
+                event
+                    .data
+                    .get("turn_id")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            });
+        }
+        if self.outcome.trace_id.is_none() {
+            self.outcome.trace_id = event.context.trace_id.clone();
+        }
+        match event.event_type.as_str() {
+            types::TURN_STARTED => self.outcome.status = TurnStatus::Running,
+            types::TURN_COMPLETED => {
+                self.outcome.status = TurnStatus::Completed;
+                self.take_turn_usage(event);
+                self.outcome.iterations = event
+                    .data
+                    .get("iterations")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as u32);
+            }
+            types::TURN_CANCELLED => {
+                self.outcome.status = TurnStatus::Cancelled;
+                self.outcome.stop_reason = Some(StopReason::Cancelled);
+                self.take_turn_usage(event);
+            }
+            types::TURN_FAILED => {
+                self.outcome.status = TurnStatus::Failed;
+                self.outcome.error = event
+                    .data
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
+            types::OUTPUT_MESSAGE_COMPLETED => {
+                if self.outcome.status == TurnStatus::Pending {
+                    self.outcome.status = TurnStatus::Running;
+                }
+                if let Some(message) = event
+                    .data
+                    .get("message")
+                    .and_then(|m| serde_json::from_value::<Message>(m.clone()).ok())
+                {
+                    self.outcome.messages.push(message);
+                }
+                if let Some(usage) = usage_from(event) {
+                    *self.outcome.usage.get_or_insert_default() += usage;
+                }
+            }
+            types::LLM_GENERATION => self.take_stop_reason(event),
+            _ => {}
+        }
+    }
+}
+
+fn usage_from(event: &Event) -> Option<TokenUsage> {
+    event
+        .data
+        .get("usage")
+        .filter(|usage| !usage.is_null())
+        .and_then(|usage| TokenUsage::deserialize(usage).ok())
+}
+
+/// Sends a best-effort cancel for the session when dropped while armed.
+struct CancelOnDrop {
+    client: Everruns,
+    session_id: String,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let client = self.client.clone();
+        let session_id = std::mem::take(&mut self.session_id);
+        crate::task::spawn_named_on(
+            "everruns.turn.cancel",
+            async move {
+                if let Err(e) = client.sessions().cancel(&session_id).await {
+                    tracing::debug!("failed to cancel aborted turn: {}", e);
+                }
+            },
+            &runtime,
+        );
+    }
+}
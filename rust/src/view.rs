@@ -0,0 +1,454 @@
+//! Materialized session state for chat UIs.
+//!
+//! A [`SessionView`] folds a session's events into the list a chat pane
+//! renders: finished messages, the assistant message still streaming in,
+//! tool calls with their status, and turn errors. [`SessionView::apply`]
+//! returns index-based [`ViewChange`]s, which map directly onto list
+//! models in GUI frameworks:
+//!
+//! ```no_run
+//! use everruns_sdk::view::{SessionView, ViewChange};
+//! use futures::{Stream, StreamExt};
+//!
+//! # async fn example(
+//! #     mut events: impl Stream<Item = everruns_sdk::error::Result<everruns_sdk::Event>> + Unpin,
+//! # ) -> Result<(), everruns_sdk::Error> {
+//! let mut view = SessionView::new();
+//! while let Some(event) = events.next().await {
+//!     for change in view.apply(&event?) {
+//!         if let ViewChange::Inserted(index) | ViewChange::Updated(index) = change {
+//!             println!("{index}: {:?}", view.items()[index]);
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Feed it a live stream, or history followed by a live stream (see
+//! `EventsClient::stream_from_start`). Messages and tool calls are keyed by
+//! ID, so a replayed event updates its item instead of duplicating it.
+//!
+//! The `ratatui` and `egui` features add ready-made chat pane widgets in
+//! `view::ratatui` and `view::egui`.
+
+use crate::events::types;
+use crate::models::{Event, Message, ToolCompletedData, ToolProgressData, ToolStartedData};
+
+#[cfg(feature = "egui")]
+pub mod egui;
+#[cfg(feature = "ratatui")]
+pub mod ratatui;
+
+/// One entry in a [`SessionView`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ViewItem {
+    /// A finished user or agent message
+    Message(Message),
+    /// Agent text still streaming in for a turn
+    Streaming {
+        turn_id: String,
+        text: String,
+    },
+    Tool(ToolView),
+    /// A turn that failed
+    Error {
+        turn_id: Option<String>,
+        message: String,
+    },
+}
+
+/// A tool call and its progress
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ToolView {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    /// Human-readable tool name, when the server provides one
+    pub display_name: Option<String>,
+    pub status: ToolStatus,
+    /// Latest interim status message
+    pub progress: Option<String>,
+    /// Error message of a failed call
+    pub error: Option<String>,
+}
+
+/// Status of a tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ToolStatus {
+    Running,
+    Succeeded,
+    /// Completed with `error`, `timeout` or `cancelled`
+    Failed(String),
+}
+
+/// Change to the item list made by one event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewChange {
+    Inserted(usize),
+    Updated(usize),
+    Removed(usize),
+}
+
+/// Chat state materialized from session events
+#[derive(Debug, Clone, Default)]
+pub struct SessionView {
+    items: Vec<ViewItem>,
+    running: bool,
+}
+
+impl SessionView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Items in display order
+    pub fn items(&self) -> &[ViewItem] {
+        &self.items
+    }
+
+    /// Whether a turn is in progress
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Fold one event into the view and report what changed.
+    ///
+    /// Events that do not affect the view return no changes.
+    pub fn apply(&mut self, event: &Event) -> Vec<ViewChange> {
+        let turn_id = event.context.turn_id.clone().or_else(|| {
+            event
+                .data
+                .get("turn_id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        });
+        match event.event_type.as_str() {
+            types::TURN_STARTED => {
+                self.running = true;
+                vec![]
+            }
+            types::INPUT_MESSAGE => message_from(event)
+                .map(|message| self.upsert_message(message))
+                .unwrap_or_default(),
+            types::OUTPUT_MESSAGE_STARTED | types::OUTPUT_MESSAGE_DELTA => {
+                let Some(turn_id) = turn_id else {
+                    return vec![];
+                };
+                let text = event.data.get("accumulated").and_then(|v| v.as_str());
+                let delta = event.data.get("delta").and_then(|v| v.as_str());
+                match self.streaming_index(&turn_id) {
+                    Some(index) => {
+                        if let ViewItem::Streaming { text: current, .. } = &mut self.items[index] {
+                            match (text, delta) {
+                                (Some(text), _) => *current = text.to_string(),
+                                (None, Some(delta)) => current.push_str(delta),
+                                (None, None) => return vec![],
+                            }
+                        }
+                        vec![ViewChange::Updated(index)]
+                    }
+                    None => {
+                        let text = text.or(delta).unwrap_or_default().to_string();
+                        self.push(ViewItem::Streaming { turn_id, text })
+                    }
+                }
+            }
+            types::OUTPUT_MESSAGE_REPLACED => {
+                let (Some(turn_id), Some(replacement)) = (
+                    turn_id,
+                    event.data.get("replacement").and_then(|v| v.as_str()),
+                ) else {
+                    return vec![];
+                };
+                let Some(index) = self.streaming_index(&turn_id) else {
+                    return vec![];
+                };
+                self.items[index] = ViewItem::Streaming {
+                    turn_id,
+                    text: replacement.to_string(),
+                };
+                vec![ViewChange::Updated(index)]
+            }
+            types::OUTPUT_MESSAGE_COMPLETED => {
+                let Some(message) = message_from(event) else {
+                    return vec![];
+                };
+                match turn_id.and_then(|turn_id| self.streaming_index(&turn_id)) {
+                    Some(index) => {
+                        self.items[index] = ViewItem::Message(message);
+                        vec![ViewChange::Updated(index)]
+                    }
+                    None => self.upsert_message(message),
+                }
+            }
+            types::TOOL_STARTED => {
+                let Ok(data) = event.data_as::<ToolStartedData>() else {
+                    return vec![];
+                };
+                if self.tool_index(&data.tool_call.id).is_some() {
+                    return vec![];
+                }
+                self.push(ViewItem::Tool(ToolView {
+                    tool_call_id: data.tool_call.id,
+                    tool_name: data.tool_call.name,
+                    display_name: data.display_name,
+                    status: ToolStatus::Running,
+                    progress: None,
+                    error: None,
+                }))
+            }
+            types::TOOL_PROGRESS => {
+                let Ok(data) = event.data_as::<ToolProgressData>() else {
+                    return vec![];
+                };
+                self.update_tool(&data.tool_call_id, |tool| {
+                    tool.progress = Some(data.message);
+                })
+            }
+            types::TOOL_COMPLETED => {
+                let Ok(data) = event.data_as::<ToolCompletedData>() else {
+                    return vec![];
+                };
+                self.update_tool(&data.tool_call_id, |tool| {
+                    tool.status = if data.success {
+                        ToolStatus::Succeeded
+                    } else {
+                        ToolStatus::Failed(data.status)
+                    };
+                    tool.error = data.error;
+                })
+            }
+            types::TURN_COMPLETED | types::TURN_CANCELLED => {
+                self.running = false;
+                self.drop_streaming(turn_id.as_deref())
+            }
+            types::TURN_FAILED => {
+                self.running = false;
+                let mut changes = self.drop_streaming(turn_id.as_deref());
+                let message = event
+                    .data
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("turn failed")
+                    .to_string();
+                changes.extend(self.push(ViewItem::Error { turn_id, message }));
+                changes
+            }
+            _ => vec![],
+        }
+    }
+
+    fn push(&mut self, item: ViewItem) -> Vec<ViewChange> {
+        self.items.push(item);
+        vec![ViewChange::Inserted(self.items.len() - 1)]
+    }
+
+    fn upsert_message(&mut self, message: Message) -> Vec<ViewChange> {
+        let existing = self
+            .items
+            .iter()
+            .position(|item| matches!(item, ViewItem::Message(m) if m.id == message.id));
+        match existing {
+            Some(index) => {
+                self.items[index] = ViewItem::Message(message);
+                vec![ViewChange::Updated(index)]
+            }
+            None => self.push(ViewItem::Message(message)),
+        }
+    }
+
+    fn streaming_index(&self, turn_id: &str) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| matches!(item, ViewItem::Streaming { turn_id: t, .. } if t == turn_id))
+    }
+
+    fn tool_index(&self, tool_call_id: &str) -> Option<usize> {
+        self.items.iter().position(
+            |item| matches!(item, ViewItem::Tool(tool) if tool.tool_call_id == tool_call_id),
+        )
+    }
+
+    fn update_tool(
+        &mut self,
+        tool_call_id: &str,
+        update: impl FnOnce(&mut ToolView),
+    ) -> Vec<ViewChange> {
+        let Some(index) = self.tool_index(tool_call_id) else {
+            return vec![];
+        };
+        if let ViewItem::Tool(tool) = &mut self.items[index] {
+            update(tool);
+        }
+        vec![ViewChange::Updated(index)]
+    }
+
+    /// Remove leftover streaming text of an ended turn, or of all turns
+    /// when the turn is unknown.
+    fn drop_streaming(&mut self, turn_id: Option<&str>) -> Vec<ViewChange> {
+        let mut changes = Vec::new();
+        for index in (0..self.items.len()).rev() {
+            if let ViewItem::Streaming { turn_id: t, .. } = &self.items[index]
+                && turn_id.is_none_or(|wanted| wanted == t)
+            {
+                self.items.remove(index);
+                changes.push(ViewChange::Removed(index));
+            }
+        }
+        changes
+    }
+}
+
+/// Speaker label and text of a message, as chat panes render it.
+#[cfg(any(feature = "egui", feature = "ratatui"))]
+fn message_parts(message: &Message) -> (&'static str, String) {
+    use crate::models::{ContentPart, MessageRole};
+
+    let label = match message.role {
+        MessageRole::User => "You",
+        MessageRole::Agent => "Agent",
+        MessageRole::ToolResult => "Tool result",
+    };
+    let text = message
+        .content
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    (label, text)
+}
+
+/// Status line of a tool call, e.g. `bash: failed (timeout): took too long`.
+#[cfg(any(feature = "egui", feature = "ratatui"))]
+fn tool_summary(tool: &ToolView) -> String {
+    let name = tool.display_name.as_deref().unwrap_or(&tool.tool_name);
+    let mut summary = match &tool.status {
+        ToolStatus::Running => format!("{name}: running"),
+        ToolStatus::Succeeded => format!("{name}: done"),
+        ToolStatus::Failed(status) => format!("{name}: failed ({status})"),
+    };
+    let progress = tool
+        .progress
+        .as_ref()
+        .filter(|_| tool.status == ToolStatus::Running);
+    if let Some(detail) = tool.error.as_ref().or(progress) {
+        summary.push_str(": ");
+        summary.push_str(detail);
+    }
+    summary
+}
+
+fn message_from(event: &Event) -> Option<Message> {
+    event
+        .data
+        .get("message")
+        .and_then(|m| serde_json::from_value(m.clone()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, data: serde_json::Value) -> Event {
+        serde_json::from_value(serde_json::json!({
+            "id": "evt",
+            "type": event_type,
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "context": {"turn_id": "turn_1"},
+            "data": data
+        }))
+        .unwrap()
+    }
+
+    fn message(id: &str, role: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "session_id": "sess_1",
+            "sequence": 1,
+            "role": role,
+            "content": [{"type": "text", "text": text}],
+            "created_at": "2024-01-01T00:00:00Z"
+        })
+    }
+
+    #[test]
+    fn streaming_text_becomes_a_message_with_tools_alongside() {
+        let mut view = SessionView::new();
+        let input = event(
+            types::INPUT_MESSAGE,
+            serde_json::json!({"message": message("msg_1", "user", "hi")}),
+        );
+        assert_eq!(view.apply(&input), vec![ViewChange::Inserted(0)]);
+        assert_eq!(view.apply(&input), vec![ViewChange::Updated(0)]);
+
+        view.apply(&event(types::TURN_STARTED, serde_json::json!({})));
+        assert!(view.is_running());
+        let delta = |accumulated: &str| {
+            event(
+                types::OUTPUT_MESSAGE_DELTA,
+                serde_json::json!({"turn_id": "turn_1", "delta": "", "accumulated": accumulated}),
+            )
+        };
+        assert_eq!(view.apply(&delta("Hel")), vec![ViewChange::Inserted(1)]);
+        assert_eq!(view.apply(&delta("Hello")), vec![ViewChange::Updated(1)]);
+        assert!(matches!(&view.items()[1], ViewItem::Streaming { text, .. } if text == "Hello"));
+
+        view.apply(&event(
+            types::TOOL_STARTED,
+            serde_json::json!({"tool_call": {"id": "call_1", "name": "bash", "arguments": {}}}),
+        ));
+        assert_eq!(
+            view.apply(&event(
+                types::TOOL_COMPLETED,
+                serde_json::json!({
+                    "tool_call_id": "call_1", "tool_name": "bash",
+                    "success": false, "status": "timeout", "error": "took too long"
+                }),
+            )),
+            vec![ViewChange::Updated(2)]
+        );
+        assert!(matches!(
+            &view.items()[2],
+            ViewItem::Tool(tool) if tool.status == ToolStatus::Failed("timeout".into())
+        ));
+
+        assert_eq!(
+            view.apply(&event(
+                types::OUTPUT_MESSAGE_COMPLETED,
+                serde_json::json!({"message": message("msg_2", "agent", "Hello")}),
+            )),
+            vec![ViewChange::Updated(1)]
+        );
+        assert!(matches!(&view.items()[1], ViewItem::Message(m) if m.id == "msg_2"));
+        view.apply(&event(types::TURN_COMPLETED, serde_json::json!({})));
+        assert!(!view.is_running());
+        assert_eq!(view.items().len(), 3);
+    }
+
+    #[test]
+    fn failed_turns_drop_partial_text_and_show_the_error() {
+        let mut view = SessionView::new();
+        view.apply(&event(
+            types::OUTPUT_MESSAGE_DELTA,
+            serde_json::json!({"turn_id": "turn_1", "delta": "Par"}),
+        ));
+        let changes = view.apply(&event(
+            types::TURN_FAILED,
+            serde_json::json!({"turn_id": "turn_1", "error": "model unavailable"}),
+        ));
+        assert_eq!(
+            changes,
+            vec![ViewChange::Removed(0), ViewChange::Inserted(0)]
+        );
+        assert!(matches!(
+            &view.items()[0],
+            ViewItem::Error { message, .. } if message == "model unavailable"
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! [`egui`](::egui) chat pane for a [`SessionView`].
+//!
+//! [`ChatPane`] is a widget that lays out the view's items in a scroll area
+//! which sticks to the bottom while output streams in:
+//!
+//! ```no_run
+//! use everruns_sdk::view::SessionView;
+//! use everruns_sdk::view::egui::ChatPane;
+//!
+//! # fn update(ctx: &egui::Context, view: &SessionView) {
+//! egui::CentralPanel::default().show(ctx, |ui| {
+//!     ui.add(ChatPane::new(view));
+//! });
+//! # }
+//! ```
+//!
+//! Request a repaint after applying events from a background task, e.g.
+//! with [`egui::Context::request_repaint`](::egui::Context::request_repaint).
+
+use super::{SessionView, ToolStatus, ViewItem, message_parts, tool_summary};
+use crate::models::MessageRole;
+use ::egui::{Color32, Id, Response, RichText, ScrollArea, Ui, Widget};
+
+/// Chat transcript widget in a bottom-anchored scroll area
+#[derive(Debug, Clone)]
+pub struct ChatPane<'a> {
+    view: &'a SessionView,
+    id_salt: Option<Id>,
+}
+
+impl<'a> ChatPane<'a> {
+    pub fn new(view: &'a SessionView) -> Self {
+        Self {
+            view,
+            id_salt: None,
+        }
+    }
+
+    /// Distinguish the scroll state of several panes in one window
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+}
+
+impl Widget for ChatPane<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let mut scroll = ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink([false, false]);
+        if let Some(id_salt) = self.id_salt {
+            scroll = scroll.id_salt(id_salt);
+        }
+        ui.scope(|ui| {
+            scroll.show(ui, |ui| {
+                for item in self.view.items() {
+                    show_item(ui, item);
+                    ui.add_space(6.0);
+                }
+            });
+        })
+        .response
+    }
+}
+
+fn show_item(ui: &mut Ui, item: &ViewItem) {
+    let label = |ui: &mut Ui, text: &str, color: Color32| {
+        ui.label(RichText::new(text).strong().color(color));
+    };
+    match item {
+        ViewItem::Message(message) => {
+            let (name, text) = message_parts(message);
+            let color = match message.role {
+                MessageRole::User => Color32::LIGHT_BLUE,
+                _ => Color32::LIGHT_GREEN,
+            };
+            label(ui, name, color);
+            ui.label(text);
+        }
+        ViewItem::Streaming { text, .. } => {
+            label(ui, "Agent", Color32::LIGHT_GREEN);
+            ui.label(format!("{text}▌"));
+        }
+        ViewItem::Tool(tool) => {
+            ui.horizontal_wrapped(|ui| {
+                let color = match tool.status {
+                    ToolStatus::Running => {
+                        ui.spinner();
+                        ui.visuals().warn_fg_color
+                    }
+                    ToolStatus::Succeeded => ui.visuals().weak_text_color(),
+                    _ => ui.visuals().error_fg_color,
+                };
+                ui.colored_label(color, tool_summary(tool));
+            });
+        }
+        ViewItem::Error { message, .. } => {
+            let color = ui.visuals().error_fg_color;
+            ui.colored_label(color, format!("error: {message}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::types;
+    use crate::models::Event;
+
+    #[test]
+    fn pane_lays_out_every_item_kind() {
+        let mut view = SessionView::new();
+        for (event_type, data) in [
+            (
+                types::TOOL_STARTED,
+                serde_json::json!({"tool_call": {"id": "call_1", "name": "bash", "arguments": {}}}),
+            ),
+            (
+                types::OUTPUT_MESSAGE_DELTA,
+                serde_json::json!({"delta": "Hi"}),
+            ),
+            (types::TURN_FAILED, serde_json::json!({"error": "boom"})),
+        ] {
+            let event: Event = serde_json::from_value(serde_json::json!({
+                "id": "evt",
+                "type": event_type,
+                "ts": "2024-01-01T00:00:00Z",
+                "session_id": "sess_1",
+                "context": {"turn_id": "turn_1"},
+                "data": data
+            }))
+            .unwrap();
+            view.apply(&event);
+        }
+
+        let ctx = ::egui::Context::default();
+        let _ = ctx.run(Default::default(), |ctx| {
+            ::egui::CentralPanel::default().show(ctx, |ui| {
+                let response = ui.add(ChatPane::new(&view).id_salt("chat"));
+                assert!(response.rect.height() > 0.0);
+            });
+        });
+    }
+}
@@ -0,0 +1,171 @@
+//! [`ratatui`](::ratatui) chat pane for a [`SessionView`].
+//!
+//! [`ChatPane`] renders the view's items as a wrapped transcript that
+//! stays scrolled to the newest output, so a TUI only has to apply events
+//! and redraw:
+//!
+//! ```no_run
+//! use everruns_sdk::view::SessionView;
+//! use everruns_sdk::view::ratatui::ChatPane;
+//! use ratatui::widgets::Block;
+//!
+//! # fn draw(frame: &mut ratatui::Frame, view: &SessionView) {
+//! let pane = ChatPane::new(view).block(Block::bordered().title("Agent"));
+//! frame.render_widget(pane, frame.area());
+//! # }
+//! ```
+
+use super::{SessionView, ToolStatus, ViewItem, message_parts, tool_summary};
+use crate::models::MessageRole;
+use ::ratatui::buffer::Buffer;
+use ::ratatui::layout::Rect;
+use ::ratatui::style::{Color, Modifier, Style};
+use ::ratatui::text::Line;
+use ::ratatui::widgets::{Block, Paragraph, Widget, Wrap};
+
+/// Chat transcript widget, scrolled to the bottom
+#[derive(Debug, Clone)]
+pub struct ChatPane<'a> {
+    view: &'a SessionView,
+    block: Option<Block<'a>>,
+    scroll_back: u16,
+}
+
+impl<'a> ChatPane<'a> {
+    pub fn new(view: &'a SessionView) -> Self {
+        Self {
+            view,
+            block: None,
+            scroll_back: 0,
+        }
+    }
+
+    /// Surround the transcript with a block, e.g. a titled border
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Scroll up from the bottom by this many rows, e.g. on PageUp
+    pub fn scroll_back(mut self, rows: u16) -> Self {
+        self.scroll_back = rows;
+        self
+    }
+}
+
+impl Widget for ChatPane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut paragraph = Paragraph::new(lines(self.view)).wrap(Wrap { trim: false });
+        if let Some(block) = self.block {
+            paragraph = paragraph.block(block);
+        }
+        let overflow = paragraph
+            .line_count(area.width)
+            .saturating_sub(area.height.into());
+        let top = u16::try_from(overflow)
+            .unwrap_or(u16::MAX)
+            .saturating_sub(self.scroll_back);
+        paragraph.scroll((top, 0)).render(area, buf);
+    }
+}
+
+/// Styled transcript lines, for apps that lay out the view themselves.
+///
+/// Items are separated by blank lines. Streaming text ends in a cursor.
+pub fn lines(view: &SessionView) -> Vec<Line<'static>> {
+    let label = |text: &'static str, color: Color| {
+        Line::styled(text, Style::new().fg(color).add_modifier(Modifier::BOLD))
+    };
+    let mut lines = Vec::new();
+    for item in view.items() {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        match item {
+            ViewItem::Message(message) => {
+                let (name, text) = message_parts(message);
+                let color = match message.role {
+                    MessageRole::User => Color::Cyan,
+                    _ => Color::Green,
+                };
+                lines.push(label(name, color));
+                lines.extend(text.lines().map(|line| Line::raw(line.to_string())));
+            }
+            ViewItem::Streaming { text, .. } => {
+                lines.push(label("Agent", Color::Green));
+                lines.extend(format!("{text}▌").lines().map(|l| Line::raw(l.to_string())));
+            }
+            ViewItem::Tool(tool) => {
+                let color = match tool.status {
+                    ToolStatus::Running => Color::Yellow,
+                    ToolStatus::Succeeded => Color::DarkGray,
+                    _ => Color::Red,
+                };
+                lines.push(Line::styled(
+                    format!("⚙ {}", tool_summary(tool)),
+                    Style::new().fg(color),
+                ));
+            }
+            ViewItem::Error { message, .. } => {
+                lines.push(Line::styled(
+                    format!("error: {message}"),
+                    Style::new().fg(Color::Red),
+                ));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::types;
+    use crate::models::Event;
+
+    fn event(event_type: &str, data: serde_json::Value) -> Event {
+        serde_json::from_value(serde_json::json!({
+            "id": "evt",
+            "type": event_type,
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "context": {"turn_id": "turn_1"},
+            "data": data
+        }))
+        .unwrap()
+    }
+
+    fn rows(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pane_shows_the_newest_rows() {
+        let mut view = SessionView::new();
+        view.apply(&event(
+            types::TOOL_STARTED,
+            serde_json::json!({"tool_call": {"id": "call_1", "name": "bash", "arguments": {}}}),
+        ));
+        view.apply(&event(
+            types::OUTPUT_MESSAGE_DELTA,
+            serde_json::json!({"delta": "one\ntwo\nthree"}),
+        ));
+
+        let area = Rect::new(0, 0, 16, 3);
+        let mut buf = Buffer::empty(area);
+        ChatPane::new(&view).render(area, &mut buf);
+        assert_eq!(rows(&buf), vec!["one", "two", "three▌"]);
+
+        let mut buf = Buffer::empty(area);
+        ChatPane::new(&view).scroll_back(3).render(area, &mut buf);
+        assert_eq!(rows(&buf), vec!["⚙ bash: running", "", "Agent"]);
+    }
+}
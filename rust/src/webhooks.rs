@@ -0,0 +1,150 @@
+//! Receiving task webhooks.
+//!
+//! Task webhooks POST task terminal-state events to a URL registered for the
+//! organization. When the webhook has a signing secret, every delivery
+//! carries an `X-Everruns-Signature: sha256=<hex>` header holding the
+//! HMAC-SHA256 of the raw body. [`router`] builds an axum endpoint that
+//! checks the signature, deserializes the body and calls a handler:
+//!
+//! ```no_run
+//! # async fn example() -> std::io::Result<()> {
+//! let app = everruns_sdk::webhooks::router(
+//!     std::env::var("EVERRUNS_WEBHOOK_SECRET").unwrap(),
+//!     |event: serde_json::Value| async move {
+//!         println!("task event: {event}");
+//!     },
+//! );
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+//! axum::serve(listener, app).await
+//! # }
+//! ```
+//!
+//! The delivery payload is not part of the OpenAPI spec, so the handler
+//! chooses the type it deserializes into; `serde_json::Value` accepts any
+//! body.
+//!
+//! Requires the `webhooks` feature.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, SecretString};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Header carrying the delivery signature
+pub const SIGNATURE_HEADER: &str = "x-everruns-signature";
+
+/// Check a `sha256=<hex>` signature header against the raw request body.
+///
+/// The comparison is constant-time.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(digest) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex| hex::decode(hex).ok())
+    else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    mac.verify_slice(&digest).is_ok()
+}
+
+/// Build a router that accepts signed webhook deliveries on `POST /`.
+///
+/// Responds `401` when the signature is missing or wrong, `400` when the
+/// body does not deserialize into `T`, and `204` once `handler` returns.
+/// Nest it under any path with [`Router::nest`].
+pub fn router<T, H, Fut>(secret: impl Into<String>, handler: H) -> Router
+where
+    T: DeserializeOwned + Send + 'static,
+    H: Fn(T) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let secret = Arc::new(SecretString::from(secret.into()));
+    Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: Bytes| async move {
+            let signed = headers
+                .get(SIGNATURE_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|signature| {
+                    verify_signature(secret.expose_secret(), &body, signature)
+                });
+            if !signed {
+                return StatusCode::UNAUTHORIZED;
+            }
+            let Ok(event) = serde_json::from_slice::<T>(&body) else {
+                return StatusCode::BAD_REQUEST;
+            };
+            handler(event).await;
+            StatusCode::NO_CONTENT
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn sign(secret: &str, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn signatures_are_checked_against_the_body() {
+        let signature = sign("whsec", r#"{"id":"1"}"#);
+        assert!(verify_signature("whsec", br#"{"id":"1"}"#, &signature));
+        assert!(!verify_signature("whsec", br#"{"id":"2"}"#, &signature));
+        assert!(!verify_signature("other", br#"{"id":"1"}"#, &signature));
+        assert!(!verify_signature("whsec", br#"{"id":"1"}"#, "sha256=zz"));
+    }
+
+    #[tokio::test]
+    async fn router_rejects_unsigned_and_delivers_signed_events() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = router("whsec", move |event: serde_json::Value| {
+            let tx = tx.clone();
+            async move {
+                tx.send(event).unwrap();
+            }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let http = reqwest::Client::new();
+        let body = r#"{"status":"completed"}"#;
+        let unsigned = http.post(&url).body(body).send().await.unwrap();
+        assert_eq!(unsigned.status(), 401);
+        let garbled = http
+            .post(&url)
+            .header(SIGNATURE_HEADER, sign("whsec", "not json"))
+            .body("not json")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(garbled.status(), 400);
+
+        let signed = http
+            .post(&url)
+            .header(SIGNATURE_HEADER, sign("whsec", body))
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(signed.status(), 204);
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            serde_json::json!({"status": "completed"})
+        );
+    }
+}
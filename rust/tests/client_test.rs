@@ -1,15 +1,20 @@
 //! Integration tests for Everruns SDK
 
+use everruns_sdk::pagination::PaginationOptions;
+use everruns_sdk::progress::ProgressUpdate;
 use everruns_sdk::{
-    AgentVersionChangeKind, AnalyzeAgentRequest, ContentPart, CreateAgentRequest,
-    CreateAgentVersionRequest, CreateBudgetRequest, CreateMemoryRequest, CreateSessionRequest,
-    CreateWorkspaceRequest, Everruns, ForkAgentVersionRequest, GuardrailsDryRunRequest,
-    HealthCheckStatus, InitialFile, RollbackAgentVersionRequest, TopUpRequest, UpdateBudgetRequest,
+    AgentCapabilityConfig, AgentVersionChangeKind, AnalyzeAgentRequest, ContentPart,
+    CreateAgentRequest, CreateAgentVersionRequest, CreateBudgetRequest, CreateMemoryRequest,
+    CreateMessageRequest, CreateSessionRequest, CreateWorkspaceRequest, Everruns,
+    ForkAgentVersionRequest, GuardrailsDryRunRequest, HealthCheckStatus, InitialFile,
+    NetworkAccessList, RollbackAgentVersionRequest, TopUpRequest, UpdateAgentRequest,
+    UpdateBudgetRequest,
 };
+use futures::StreamExt;
 use std::sync::Mutex;
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{body_json, header, method, path, query_param},
+    matchers::{body_json, header, method, path, query_param, query_param_is_missing},
 };
 
 static ENV_LOCK: Mutex<()> = Mutex::new(());
@@ -74,6 +79,99 @@ fn test_client_org_id_rejects_invalid_header_value() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_client_builder_sends_default_headers_and_user_agent_suffix() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .timeout(std::time::Duration::from_secs(5))
+        .connect_timeout(std::time::Duration::from_secs(1))
+        .user_agent_suffix("my-app/1.2")
+        .default_header("X-Gateway-Tenant", "acme")
+        .build()
+        .expect("client creation should succeed");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(header("X-Gateway-Tenant", "acme"))
+        .and(header(
+            "User-Agent",
+            concat!(
+                "everruns-sdk-rust/",
+                env!("CARGO_PKG_VERSION"),
+                " my-app/1.2"
+            ),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.agents().list().await.expect("list should succeed");
+}
+
+#[tokio::test]
+async fn test_interceptors_change_requests_and_observe_responses() {
+    struct Gateway(std::sync::Arc<Mutex<Vec<(String, u16)>>>);
+
+    impl everruns_sdk::intercept::Interceptor for Gateway {
+        fn before(&self, request: &mut http::request::Parts) {
+            request
+                .headers
+                .insert("x-gateway-tenant", http::HeaderValue::from_static("acme"));
+        }
+
+        fn after(&self, request: &http::request::Parts, response: &http::response::Parts) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((request.uri.path().to_string(), response.status.as_u16()));
+        }
+    }
+
+    let server = MockServer::start().await;
+    let seen = std::sync::Arc::new(Mutex::new(Vec::new()));
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .interceptor(Gateway(seen.clone()))
+        .build()
+        .expect("client creation should succeed");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(header("x-gateway-tenant", "acme"))
+        .and(header("Authorization", "evr_test_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.agents().list().await.expect("list should succeed");
+    assert_eq!(*seen.lock().unwrap(), vec![("/v1/agents".to_string(), 200)]);
+}
+
+#[test]
+fn test_client_builder_rejects_invalid_default_header() {
+    let result = Everruns::builder()
+        .api_key("evr_test_key")
+        .default_header("bad header", "value")
+        .build();
+
+    assert!(matches!(result, Err(everruns_sdk::Error::Validation(_))));
+}
+
+#[test]
+fn test_client_api_key_rejects_invalid_header_value() {
+    let result = Everruns::new("evr_bad\nkey");
+    assert!(matches!(result, Err(everruns_sdk::Error::Auth(_))));
+
+    // Debug output must not slice inside a multi-byte character.
+    let key = everruns_sdk::ApiKey::new("aéééééé");
+    assert_eq!(format!("{key:?}"), "ApiKey(***)");
+}
+
 #[test]
 fn test_custom_base_url() {
     let result = Everruns::with_base_url("evr_test_key", "https://custom.example.com/api");
@@ -435,6 +533,186 @@ async fn test_create_session_with_locale() {
     assert_eq!(session.locale.as_deref(), Some("uk-UA"));
 }
 
+#[tokio::test]
+async fn test_create_session_with_network_access() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions"))
+        .and(body_json(serde_json::json!({
+            "network_access": {"allowed": ["*.github.com"], "blocked": ["169.254.169.254"]}
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "session_456",
+            "organization_id": "org_123",
+            "harness_id": "harness_123",
+            "network_access": {"allowed": ["*.github.com"], "blocked": ["169.254.169.254"]},
+            "status": "started",
+            "created_at": "2026-03-13T00:00:00Z",
+            "updated_at": "2026-03-13T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+
+    let policy = NetworkAccessList::new()
+        .allowed(vec!["*.github.com".to_string()])
+        .blocked(vec!["169.254.169.254".to_string()]);
+    let session = client
+        .sessions()
+        .create_with_options(CreateSessionRequest::new().network_access(policy.clone()))
+        .await
+        .expect("session creation should succeed");
+
+    assert_eq!(session.network_access, Some(policy));
+}
+
+#[tokio::test]
+async fn test_create_session_with_wait_retries_capacity_exceeded() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    // RFC 9457 problem details, as in the spec's ErrorResponse
+    let full = serde_json::json!({
+        "title": "Service Unavailable",
+        "status": 503,
+        "detail": "no session capacity",
+        "retry_after_seconds": 0
+    });
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions"))
+        .respond_with(ResponseTemplate::new(503).set_body_json(full.clone()))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "session_456",
+            "organization_id": "org_123",
+            "harness_id": "harness_123",
+            "status": "started",
+            "created_at": "2026-03-13T00:00:00Z",
+            "updated_at": "2026-03-13T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client.sessions().create().await.unwrap_err();
+    assert!(matches!(
+        err,
+        everruns_sdk::Error::CapacityExceeded {
+            retry_after: Some(wait),
+            ..
+        } if wait.is_zero()
+    ));
+    let session = client
+        .sessions()
+        .create_with_wait(
+            CreateSessionRequest::new(),
+            std::time::Duration::from_secs(5),
+        )
+        .await
+        .expect("session creation should succeed after waiting");
+    assert_eq!(session.id, "session_456");
+}
+
+#[tokio::test]
+async fn test_agent_create_and_confirm_waits_for_read_visibility() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let agent = serde_json::json!({
+        "id": "agent_123",
+        "name": "lagging-agent",
+        "system_prompt": "Be helpful.",
+        "status": "active",
+        "created_at": "2026-04-15T00:00:00Z",
+        "updated_at": "2026-04-15T00:00:00Z"
+    });
+    let not_found = serde_json::json!({"error": {"code": "not_found", "message": "no agent"}});
+
+    Mock::given(method("POST"))
+        .and(path("/v1/agents"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(agent.clone()))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents/agent_123"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(not_found))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents/agent_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(agent))
+        .mount(&server)
+        .await;
+
+    let err = client
+        .agents()
+        .create_and_confirm(
+            CreateAgentRequest::new("lagging-agent", "Be helpful."),
+            std::time::Duration::ZERO,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, everruns_sdk::Error::NotYetVisible { ref id, .. } if id == "agent_123"));
+
+    let agent = client
+        .agents()
+        .create_and_confirm(
+            CreateAgentRequest::new("lagging-agent", "Be helpful."),
+            std::time::Duration::from_secs(5),
+        )
+        .await
+        .expect("agent should become readable");
+    assert_eq!(agent.id, "agent_123");
+}
+
+#[tokio::test]
+async fn test_raw_client_calls_unwrapped_endpoints() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/experimental/things"))
+        .and(query_param("kind", "new"))
+        .and(header("Authorization", "evr_test_key"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": ["thing_1"]})),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/experimental/things"))
+        .and(body_json(serde_json::json!({"name": "thing"})))
+        .respond_with(ResponseTemplate::new(422).set_body_json(serde_json::json!({
+            "error": {"code": "invalid_thing", "message": "bad thing"}
+        })))
+        .mount(&server)
+        .await;
+
+    let listed: serde_json::Value = client
+        .raw()
+        .get("/v1/experimental/things", &[("kind", "new")])
+        .await
+        .expect("raw get should succeed");
+    assert_eq!(listed["data"][0], "thing_1");
+
+    let err = client
+        .raw()
+        .post_json::<serde_json::Value, _>(
+            "/v1/experimental/things",
+            &serde_json::json!({"name": "thing"}),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        everruns_sdk::Error::Api { status: 422, ref code, .. } if code == "invalid_thing"
+    ));
+}
+
 #[tokio::test]
 async fn test_import_agent_from_example() {
     let server = MockServer::start().await;
@@ -468,6 +746,167 @@ async fn test_import_agent_from_example() {
     assert_eq!(agent.name, "dad-jokes-agent");
 }
 
+#[tokio::test]
+async fn test_agent_get_or_create_reuses_exact_name_match() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let agent = |id: &str, name: &str| {
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "system_prompt": "Tell dad jokes.",
+            "status": "active",
+            "created_at": "2026-04-15T00:00:00Z",
+            "updated_at": "2026-04-15T00:00:00Z"
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(query_param("search", "dad-jokes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [agent("agent_2", "dad-jokes-v2")], "total": 1, "offset": 0, "limit": 20
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(query_param("search", "dad-jokes-v2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [agent("agent_2", "dad-jokes-v2")], "total": 1, "offset": 0, "limit": 20
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/agents"))
+        .and(body_json(serde_json::json!({
+            "name": "dad-jokes",
+            "system_prompt": "Tell dad jokes."
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(agent("agent_1", "dad-jokes")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let created = client
+        .agents()
+        .get_or_create("dad-jokes", |req| req.system_prompt("Tell dad jokes."))
+        .await
+        .expect("missing agent should be created");
+    assert_eq!(created.id, "agent_1");
+
+    let existing = client
+        .agents()
+        .get_or_create("dad-jokes-v2", |req| req.system_prompt("Changed."))
+        .await
+        .expect("existing agent should be returned");
+    assert_eq!(existing.id, "agent_2");
+    assert_eq!(existing.system_prompt, "Tell dad jokes.");
+}
+
+#[tokio::test]
+async fn test_agent_get_or_create_finds_match_on_later_search_page() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let agent = |id: String, name: String| {
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "system_prompt": "Tell dad jokes.",
+            "status": "active",
+            "created_at": "2026-04-15T00:00:00Z",
+            "updated_at": "2026-04-15T00:00:00Z"
+        })
+    };
+    let page_size = everruns_sdk::pagination::DEFAULT_PAGE_SIZE;
+
+    // A full first page of near misses, then the exact name on page two.
+    let near_misses: Vec<_> = (0..page_size)
+        .map(|i| agent(format!("agent_{i}"), format!("dad-jokes-{i}")))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(query_param("search", "dad-jokes"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": near_misses
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(query_param("search", "dad-jokes"))
+        .and(query_param("offset", page_size.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [agent("agent_exact".to_string(), "dad-jokes".to_string())]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/agents"))
+        .respond_with(ResponseTemplate::new(201))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let existing = client
+        .agents()
+        .get_or_create("dad-jokes", |req| req.system_prompt("Changed."))
+        .await
+        .expect("existing agent should be returned");
+    assert_eq!(existing.id, "agent_exact");
+}
+
+#[tokio::test]
+async fn test_agent_update_dry_run_diffs_without_patching() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let agent = serde_json::json!({
+        "id": "agent_123",
+        "name": "support",
+        "system_prompt": "Be kind.",
+        "capabilities": [{"ref": "current_time"}],
+        "status": "active",
+        "created_at": "2026-04-15T00:00:00Z",
+        "updated_at": "2026-04-15T00:00:00Z"
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents/agent_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(agent.clone()))
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/agents/agent_123"))
+        .and(body_json(serde_json::json!({
+            "capabilities": [{"ref": "current_time"}, {"ref": "web_fetch"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(agent))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = UpdateAgentRequest::new().capabilities(vec![
+        AgentCapabilityConfig::new("current_time"),
+        AgentCapabilityConfig::new("web_fetch"),
+    ]);
+    let diff = client
+        .agents()
+        .update_dry_run("agent_123", &req)
+        .await
+        .expect("dry run should succeed");
+    assert_eq!(diff.capabilities_added, vec!["web_fetch"]);
+    assert_eq!(diff.to_string(), "capabilities added: web_fetch\n");
+
+    client
+        .agents()
+        .update("agent_123", req)
+        .await
+        .expect("update should succeed");
+}
+
 #[tokio::test]
 async fn test_agent_stats() {
     let server = MockServer::start().await;
@@ -488,6 +927,9 @@ async fn test_agent_stats() {
             "total_output_tokens": 50,
             "total_cache_read_tokens": 25,
             "total_cache_creation_tokens": 10,
+            "total_actual_cost_usd": 0.5,
+            "total_estimated_cost_usd": 0.75,
+            "total_cost_usd": 0.6,
             "first_session_at": "2026-05-01T00:00:00Z",
             "last_session_at": "2026-05-02T00:00:00Z",
             "last_execution_at": "2026-05-02T01:00:00Z"
@@ -505,6 +947,7 @@ async fn test_agent_stats() {
     assert_eq!(stats.execution_count, 7);
     assert_eq!(stats.avg_session_duration_ms, Some(3086));
     assert_eq!(stats.total_input_tokens, 100);
+    assert_eq!(stats.total_cost_usd, 0.6);
 }
 
 #[tokio::test]
@@ -1000,26 +1443,328 @@ async fn test_events_list_with_upstream_filters() {
     assert_eq!(response.data[0].id, "event_001");
 }
 
+fn event_json(seq: i32) -> serde_json::Value {
+    serde_json::json!({
+        "id": format!("event_{seq:03}"),
+        "type": "turn.started",
+        "ts": "2026-06-01T00:00:00Z",
+        "session_id": "sess_123",
+        "sequence": seq,
+        "data": {}
+    })
+}
+
 #[tokio::test]
-async fn test_create_tool_results_uses_tool_results_endpoint() {
+async fn test_events_list_all_paginates_backward_with_prefetch() {
     let server = MockServer::start().await;
     let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
 
-    Mock::given(method("POST"))
-        .and(path("/v1/sessions/session_123/tool-results"))
-        .and(body_json(serde_json::json!({
-            "tool_results": [{
-                "tool_call_id": "call_123",
-                "result": {"weather": "sunny"}
-            }]
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/events"))
+        .and(query_param("limit", "2"))
+        .and(query_param_is_missing("before_sequence"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [event_json(4), event_json(5)]
         })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/events"))
+        .and(query_param("limit", "2"))
+        .and(query_param("before_sequence", "4"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "accepted": 1,
-            "status": "active"
+            "data": [event_json(2), event_json(3)]
         })))
+        .expect(1)
         .mount(&server)
         .await;
-
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/events"))
+        .and(query_param("limit", "2"))
+        .and(query_param("before_sequence", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [event_json(1)]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/events"))
+        .and(query_param("limit", "2"))
+        .and(query_param("before_sequence", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut stream = client.events().list_all(
+        "sess_123",
+        &everruns_sdk::client::ListEventsOptions::default(),
+        PaginationOptions::new().with_page_size(2).with_lookahead(1),
+    );
+
+    let first = stream.next().await.expect("first event").expect("ok");
+    assert_eq!(first.sequence, Some(5));
+    // The second page is fetched while the first is still being consumed.
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let requests = server.received_requests().await.expect("recording enabled");
+    assert!(requests.len() >= 2, "next page should be prefetched");
+
+    let mut sequences = vec![first.sequence.unwrap()];
+    while let Some(event) = stream.next().await {
+        sequences.push(event.expect("ok").sequence.unwrap());
+    }
+    assert_eq!(sequences, vec![5, 4, 3, 2, 1]);
+}
+
+#[tokio::test]
+async fn test_events_list_all_continues_past_short_turn_snapped_page() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    // The server snaps older pages to a turn boundary, so the middle page
+    // holds fewer events than requested without being the last.
+    let pages = [
+        (None, vec![4, 5, 6]),
+        (Some("4"), vec![3]),
+        (Some("3"), vec![1, 2]),
+        (Some("1"), vec![]),
+    ];
+    for (before_sequence, sequences) in pages {
+        let data: Vec<serde_json::Value> = sequences.into_iter().map(event_json).collect();
+        let mock = Mock::given(method("GET"))
+            .and(path("/v1/sessions/sess_123/events"))
+            .and(query_param("limit", "3"));
+        let mock = match before_sequence {
+            Some(before) => mock.and(query_param("before_sequence", before)),
+            None => mock.and(query_param_is_missing("before_sequence")),
+        };
+        mock.respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": data })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    }
+
+    let sequences: Vec<i32> = client
+        .events()
+        .list_all(
+            "sess_123",
+            &everruns_sdk::client::ListEventsOptions::default(),
+            PaginationOptions::new().with_page_size(3),
+        )
+        .map(|event| event.expect("ok").sequence.unwrap())
+        .collect()
+        .await;
+    assert_eq!(sequences, vec![6, 5, 4, 3, 2, 1]);
+}
+
+#[tokio::test]
+async fn test_events_list_stream_parses_items_incrementally() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    let events: Vec<serde_json::Value> = (1..=500).map(event_json).collect();
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/events"))
+        .and(query_param("types", "turn.started"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": events,
+            "total": 500,
+            "offset": 0,
+            "limit": 500
+        })))
+        .mount(&server)
+        .await;
+
+    let options = everruns_sdk::client::ListEventsOptions {
+        types: vec!["turn.started".to_string()],
+        ..Default::default()
+    };
+    let sequences: Vec<i32> = client
+        .events()
+        .list_stream("sess_123", &options)
+        .map(|event| event.expect("ok").sequence.unwrap())
+        .collect()
+        .await;
+    assert_eq!(sequences, (1..=500).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_events_list_stream_surfaces_api_error() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_missing/events"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": "not_found", "message": "Session not found"}
+        })))
+        .mount(&server)
+        .await;
+
+    let results: Vec<_> = client
+        .events()
+        .list_stream(
+            "sess_missing",
+            &everruns_sdk::client::ListEventsOptions::default(),
+        )
+        .collect()
+        .await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(
+        results[0],
+        Err(everruns_sdk::Error::Api { status: 404, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_forbidden_error_maps_403() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents/agent_1"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "error": {
+                "code": "forbidden",
+                "message": "Insufficient permissions"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client.agents().get("agent_1").await.unwrap_err();
+    match err {
+        everruns_sdk::Error::Forbidden { code, message } => {
+            assert_eq!(code, "forbidden");
+            assert_eq!(message, "Insufficient permissions");
+        }
+        other => panic!("expected Forbidden, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_errors_map_to_proxy_http_responses() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": "not_found", "message": "Agent not found"}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents/locked"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+            "error": {"code": "unauthorized", "message": "Invalid key"}
+        })))
+        .mount(&server)
+        .await;
+
+    let not_found = client.agents().get("missing").await.unwrap_err();
+    assert_eq!(not_found.http_status(), 404);
+    assert_eq!(
+        serde_json::to_value(not_found.to_api_error()).unwrap(),
+        serde_json::json!({
+            "error": {"code": "not_found", "message": "API error: not_found - Agent not found"}
+        })
+    );
+
+    // The backend's own key is at fault, not the caller's request.
+    let unauthorized = client.agents().get("locked").await.unwrap_err();
+    assert_eq!(unauthorized.http_status(), 502);
+
+    let invalid = everruns_sdk::Error::Validation("title is required".into());
+    assert_eq!(invalid.http_status(), 400);
+    assert_eq!(invalid.to_api_error().error.code, "validation_error");
+}
+
+fn message_json(session_id: &str, text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": format!("msg_{session_id}"),
+        "session_id": session_id,
+        "sequence": 1,
+        "role": "user",
+        "content": [{"type": "text", "text": text}],
+        "created_at": "2026-06-01T00:00:00Z"
+    })
+}
+
+#[tokio::test]
+async fn test_messages_send_many_returns_results_in_order() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    for i in 0..5 {
+        let session_id = format!("sess_{i}");
+        Mock::given(method("POST"))
+            .and(path(format!("/v1/sessions/{session_id}/messages")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(message_json(&session_id, "hi"))
+                    .set_delay(std::time::Duration::from_millis(50 * (5 - i))),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_missing/messages"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": "not_found", "message": "Session not found"}
+        })))
+        .mount(&server)
+        .await;
+
+    let mut requests: Vec<_> = (0..5)
+        .map(|i| (format!("sess_{i}"), CreateMessageRequest::user_text("hi")))
+        .collect();
+    requests.insert(
+        2,
+        (
+            "sess_missing".to_string(),
+            CreateMessageRequest::user_text("hi"),
+        ),
+    );
+
+    let results = client.messages().send_many(requests, 3).await;
+
+    assert_eq!(results.len(), 6);
+    assert!(results[2].is_err());
+    let ids: Vec<_> = results
+        .iter()
+        .filter_map(|r| r.as_ref().ok())
+        .map(|m| m.session_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["sess_0", "sess_1", "sess_2", "sess_3", "sess_4"]);
+}
+
+#[tokio::test]
+async fn test_create_tool_results_uses_tool_results_endpoint() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/session_123/tool-results"))
+        .and(body_json(serde_json::json!({
+            "tool_results": [{
+                "tool_call_id": "call_123",
+                "result": {"weather": "sunny"}
+            }]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accepted": 1,
+            "status": "active"
+        })))
+        .mount(&server)
+        .await;
+
     let response = client
         .messages()
         .create_tool_results(
@@ -1482,6 +2227,43 @@ async fn test_session_set_secrets() {
         .expect("set_secrets should succeed");
 }
 
+#[tokio::test]
+async fn test_session_list_secrets_and_storage() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/storage/secrets"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{
+                "name": "GITHUB_TOKEN",
+                "created_at": "2026-03-13T00:00:00Z",
+                "updated_at": "2026-03-13T00:00:00Z"
+            }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_123/storage/keys"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{
+                "key": "REGION",
+                "value": "eu-west-1",
+                "created_at": "2026-03-13T00:00:00Z",
+                "updated_at": "2026-03-13T00:00:00Z"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let sessions = client.sessions();
+    let secrets = sessions.list_secrets("sess_123").await.unwrap();
+    assert_eq!(secrets.data[0].name, "GITHUB_TOKEN");
+    let storage = sessions.list_storage("sess_123").await.unwrap();
+    assert_eq!(storage.data[0].key, "REGION");
+    assert_eq!(storage.data[0].value, "eu-west-1");
+}
+
 #[tokio::test]
 async fn test_session_set_secrets_empty() {
     let server = MockServer::start().await;
@@ -1864,3 +2646,1054 @@ async fn test_session_export() {
     assert!(result.contains("msg_001"));
     assert!(result.contains("msg_002"));
 }
+
+#[tokio::test]
+async fn test_messages_list_since_returns_only_newer_messages() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    let messages: Vec<_> = [3, 1, 4, 2]
+        .into_iter()
+        .map(|sequence| {
+            let mut message = message_json("sess_1", "hi");
+            message["id"] = serde_json::json!(format!("msg_{sequence}"));
+            message["sequence"] = serde_json::json!(sequence);
+            message
+        })
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": messages,
+            "total": 4,
+            "offset": 0,
+            "limit": 100
+        })))
+        .mount(&server)
+        .await;
+
+    let newer = client.messages().list_since("sess_1", 2).await.unwrap();
+    let ids: Vec<_> = newer.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["msg_3", "msg_4"]);
+}
+
+#[tokio::test]
+async fn test_images_attach_file_uploads_and_appends_image_part() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/images"))
+        .and(wiremock::matchers::header_exists("content-type"))
+        .and(wiremock::matchers::body_string_contains(
+            "filename=\"chart.png\"",
+        ))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "img_1",
+            "filename": "chart.png",
+            "content_type": "image/png",
+            "size_bytes": 4,
+            "created_at": "2026-06-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = std::env::temp_dir().join(format!("everruns-attach-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("chart.png");
+    std::fs::write(&file, b"PNG!").unwrap();
+
+    let req = client
+        .images()
+        .attach_file(CreateMessageRequest::user_text("What is this?"), &file)
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let body = serde_json::to_value(&req).unwrap();
+    assert_eq!(body["message"]["content"][0]["type"], "text");
+    assert_eq!(
+        body["message"]["content"][1],
+        serde_json::json!({"type": "image_file", "image_id": "img_1", "filename": "chart.png"})
+    );
+}
+
+#[tokio::test]
+async fn test_images_upload_and_download_report_progress() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let image = vec![7u8; 150 * 1024];
+
+    Mock::given(method("POST"))
+        .and(path("/v1/images"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "img_1",
+            "filename": "big.png",
+            "content_type": "image/png",
+            "size_bytes": image.len(),
+            "created_at": "2026-06-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/images/img_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(image.clone()))
+        .mount(&server)
+        .await;
+
+    let record = |updates: &std::sync::Arc<Mutex<Vec<ProgressUpdate>>>| {
+        let updates = updates.clone();
+        move |p: &ProgressUpdate| updates.lock().unwrap().push(p.clone())
+    };
+    let total = image.len() as u64;
+
+    let sent = std::sync::Arc::new(Mutex::new(Vec::new()));
+    client
+        .images()
+        .upload_with_progress("big.png", "image/png", image.clone(), None, record(&sent))
+        .await
+        .unwrap();
+    {
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.first().unwrap().done, 0);
+        assert!(sent.len() > 2, "expected one update per chunk");
+        assert_eq!(sent.last().unwrap().done, total);
+        assert_eq!(sent.last().unwrap().total, Some(total));
+    }
+
+    let received = std::sync::Arc::new(Mutex::new(Vec::new()));
+    let bytes = client
+        .images()
+        .get_with_progress("img_1", record(&received))
+        .await
+        .unwrap();
+    assert_eq!(bytes, image);
+    let received = received.lock().unwrap();
+    assert_eq!(received.last().unwrap().done, total);
+    assert_eq!(received.last().unwrap().total, Some(total));
+}
+
+#[tokio::test]
+async fn test_images_upload_file_rejects_unknown_extension() {
+    let client = Everruns::with_base_url("evr_test_key", "http://localhost:1").expect("client");
+    let err = client
+        .images()
+        .upload_file("notes.txt", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, everruns_sdk::Error::Validation(_)));
+}
+
+#[tokio::test]
+async fn test_models_resolve_latest_alias() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    let model = |id: &str, model_id: &str, enabled: bool| {
+        serde_json::json!({
+            "id": id,
+            "provider_id": "provider_1",
+            "model_id": model_id,
+            "display_name": model_id,
+            "capabilities": ["text"],
+            "is_favorite": false,
+            "enabled": enabled,
+            "source": "predefined",
+            "provider_name": "Anthropic",
+            "provider_type": "anthropic",
+            "healthy": true,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z"
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [
+                model("model_1", "claude-sonnet-4-20250514", true),
+                model("model_2", "claude-sonnet-4-5-20250929", true),
+                model("model_3", "claude-sonnet-9", false),
+                model("model_4", "gpt-4o", true)
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let models = client.models();
+    let latest = models
+        .resolve(everruns_sdk::ModelId::CLAUDE_SONNET_LATEST)
+        .await
+        .unwrap();
+    assert_eq!(latest.as_str(), "model_2");
+    assert_eq!(models.resolve("gpt-4o").await.unwrap().as_str(), "model_4");
+    assert!(models.resolve("claude-opus-latest").await.is_err());
+
+    let req = CreateAgentRequest::new("agent", "prompt").default_model_id(latest);
+    assert_eq!(req.default_model_id.as_deref(), Some("model_2"));
+}
+
+#[tokio::test]
+async fn test_models_resolve_latest_alias_by_release_date() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    let model = |id: &str, model_id: &str| {
+        serde_json::json!({
+            "id": id,
+            "provider_id": "provider_1",
+            "model_id": model_id,
+            "display_name": model_id,
+            "capabilities": ["text"],
+            "is_favorite": false,
+            "enabled": true,
+            "source": "predefined",
+            "provider_name": "Provider",
+            "provider_type": "openai",
+            "healthy": true,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z"
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [
+                // Sorts above 4-1 as a string, but was released earlier.
+                model("opus_4", "claude-opus-4-20250514"),
+                model("opus_4_1", "claude-opus-4-1-20250805"),
+                model("gpt_4o", "gpt-4o-2024-08-06"),
+                // A sibling family, newer than any gpt-4o release.
+                model("gpt_4o_mini", "gpt-4o-mini-2024-12-01")
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let models = client.models();
+    let opus = models
+        .resolve(everruns_sdk::ModelId::CLAUDE_OPUS_LATEST)
+        .await
+        .unwrap();
+    assert_eq!(opus.as_str(), "opus_4_1");
+    let gpt = models.resolve("gpt-4o-latest").await.unwrap();
+    assert_eq!(gpt.as_str(), "gpt_4o");
+}
+
+#[tokio::test]
+async fn test_tokens_count_checks_model_input_limit() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{
+                "id": "model_1",
+                "provider_id": "provider_1",
+                "model_id": "claude-sonnet-4-20250514",
+                "display_name": "Claude Sonnet 4",
+                "enabled": true,
+                "source": "predefined",
+                "profile": {
+                    "name": "Claude Sonnet 4",
+                    "family": "claude-sonnet",
+                    "attachment": true,
+                    "reasoning": true,
+                    "temperature": true,
+                    "tool_call": true,
+                    "structured_output": true,
+                    "open_weights": false,
+                    "limits": {"context": 12, "output": 8}
+                },
+                "created_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-01T00:00:00Z"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let count = client
+        .tokens()
+        .count("model_1", "twenty characters!!!")
+        .await
+        .unwrap();
+    assert_eq!(count.tokens, 5);
+    assert_eq!(count.context_window, Some(12));
+    assert_eq!(count.max_input, Some(4));
+    assert!(!count.fits());
+    assert!(client.tokens().count("gpt-4o", "hi").await.is_err());
+}
+
+fn session_json(id: &str, tags: &[&str]) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "organization_id": "org_123",
+        "harness_id": "harness_123",
+        "tags": tags,
+        "status": "idle",
+        "created_at": "2026-03-13T00:00:00Z",
+        "updated_at": "2026-03-13T00:00:00Z"
+    })
+}
+
+#[tokio::test]
+async fn test_sessions_add_and_remove_tags_patch_full_tag_list() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(session_json("sess_1", &["a", "b"])))
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/sessions/sess_1"))
+        .and(body_json(
+            serde_json::json!({"tags": ["a", "b", "eval-2025-06-01"]}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(session_json("sess_1", &["a", "b", "eval-2025-06-01"])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/sessions/sess_1"))
+        .and(body_json(serde_json::json!({"tags": ["b"]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(session_json("sess_1", &["b"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let sessions = client.sessions();
+    let tagged = sessions
+        .add_tags("sess_1", &["b", "eval-2025-06-01"])
+        .await
+        .unwrap();
+    assert_eq!(tagged.tags, vec!["a", "b", "eval-2025-06-01"]);
+    let untagged = sessions.remove_tags("sess_1", &["a"]).await.unwrap();
+    assert_eq!(untagged.tags, vec!["b"]);
+}
+
+#[tokio::test]
+async fn test_sessions_list_by_tag_pages_and_filters() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions"))
+        .and(query_param("offset", "0"))
+        .and(query_param("limit", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [session_json("sess_1", &["eval"]), session_json("sess_2", &[])]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions"))
+        .and(query_param("offset", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [session_json("sess_3", &["x", "eval"])]
+        })))
+        .mount(&server)
+        .await;
+
+    let ids: Vec<String> = client
+        .sessions()
+        .list_by_tag("eval", PaginationOptions::new().with_page_size(2))
+        .map(|session| session.unwrap().id)
+        .collect()
+        .await;
+    assert_eq!(ids, vec!["sess_1", "sess_3"]);
+}
+
+#[tokio::test]
+async fn test_sessions_children_match_parent_id_and_tag() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    let mut spawned = session_json("sess_2", &[]);
+    spawned["parent_session_id"] = serde_json::json!("sess_1");
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [
+                session_json("sess_1", &[]),
+                spawned,
+                session_json("sess_3", &["parent_session:sess_1", "parent_turn:turn_1"]),
+                session_json("sess_4", &["parent_session:sess_9"])
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let ids: Vec<String> = client
+        .sessions()
+        .children("sess_1", PaginationOptions::new())
+        .map(|session| session.unwrap().id)
+        .collect()
+        .await;
+    assert_eq!(ids, vec!["sess_2", "sess_3"]);
+}
+
+#[tokio::test]
+async fn test_knowledge_bases_create_and_entries() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/knowledge-bases"))
+        .and(body_json(serde_json::json!({
+            "name": "support-runbooks",
+            "description": "Runbooks for the support team"
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "kb_1",
+            "name": "support-runbooks",
+            "description": "Runbooks for the support team",
+            "status": "active",
+            "created_at": "2026-06-01T00:00:00Z",
+            "updated_at": "2026-06-01T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/knowledge-bases/kb_1/entries"))
+        .and(body_json(serde_json::json!({
+            "title": "Refunds",
+            "body": "Use the refund endpoint.",
+            "kind": "runbook",
+            "tags": ["billing"]
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "kbe_1",
+            "kb_id": "kb_1",
+            "title": "Refunds",
+            "body": "Use the refund endpoint.",
+            "kind": "runbook",
+            "tags": ["billing"],
+            "created_at": "2026-06-01T00:00:00Z",
+            "updated_at": "2026-06-01T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/knowledge-bases/kb_1/entries"))
+        .and(query_param("kind", "runbook"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": []
+        })))
+        .mount(&server)
+        .await;
+
+    let kbs = client.knowledge_bases();
+    let kb = kbs
+        .create(
+            everruns_sdk::CreateKnowledgeBaseRequest::new("support-runbooks")
+                .description("Runbooks for the support team"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(kb.id, "kb_1");
+    assert!(kb.embedding_model_id.is_none());
+
+    let entry = kbs
+        .create_entry(
+            &kb.id,
+            everruns_sdk::CreateKnowledgeEntryRequest::new("Refunds", "Use the refund endpoint.")
+                .kind("runbook")
+                .tags(vec!["billing".to_string()]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(entry.kb_id, "kb_1");
+
+    let entries = kbs
+        .list_entries(&kb.id, None, Some("runbook"))
+        .await
+        .unwrap();
+    assert!(entries.data.is_empty());
+}
+
+#[tokio::test]
+async fn test_users_search_lists_org_members() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/users"))
+        .and(query_param("search", "ada"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{
+                "id": "user_1",
+                "email": "ada@example.com",
+                "name": "Ada",
+                "roles": ["admin"],
+                "created_at": "2026-06-01T00:00:00Z"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let users = client.users().search("ada").await.unwrap();
+    assert_eq!(users.data.len(), 1);
+    assert_eq!(users.data[0].roles, vec!["admin"]);
+}
+
+#[tokio::test]
+async fn test_voice_call_flattens_session_options() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/voice/calls"))
+        .and(body_json(serde_json::json!({
+            "sdp": "v=0 offer",
+            "voice": "alloy",
+            "reasoning_effort": "low"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "voice_connection_id": "voice_1",
+            "provider": "openai",
+            "model": "gpt-realtime",
+            "voice": "alloy",
+            "reasoning_effort": "low",
+            "expires_at": "2026-06-01T00:10:00Z",
+            "answer_sdp": "v=0 answer"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/voice/voice_1/end"))
+        .and(body_json(serde_json::json!({"reason": "hung up"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "voice_connection_id": "voice_1",
+            "status": "ended"
+        })))
+        .mount(&server)
+        .await;
+
+    let voice = client.voice();
+    let call = voice
+        .call(
+            "sess_1",
+            everruns_sdk::VoiceCallRequest::new("v=0 offer").options(
+                everruns_sdk::VoiceSessionOptions::new()
+                    .voice("alloy")
+                    .reasoning_effort("low"),
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(call.answer_sdp, "v=0 answer");
+    assert!(call.provider_call_id.is_none());
+
+    let ended = voice
+        .end(
+            "sess_1",
+            &call.voice_connection_id,
+            everruns_sdk::VoiceEndRequest::new().reason("hung up"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ended.status, "ended");
+}
+
+#[tokio::test]
+async fn test_server_time_tracks_clock_skew_from_date_header() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    assert_eq!(client.clock_skew_millis(), 0);
+
+    let ahead = std::time::SystemTime::now() + std::time::Duration::from_secs(3600);
+    Mock::given(method("GET"))
+        .and(path("/v1/durable/health"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Date", httpdate::fmt_http_date(ahead).as_str())
+                .set_body_json(serde_json::json!({"status": "ok"})),
+        )
+        .mount(&server)
+        .await;
+
+    client.server_time().await.unwrap();
+    let skew = client.clock_skew_millis();
+    assert!((3_598_000..=3_601_000).contains(&skew), "skew {skew}");
+
+    let corrected = client
+        .now()
+        .duration_since(std::time::SystemTime::now())
+        .unwrap();
+    assert!(corrected > std::time::Duration::from_secs(3590));
+}
+
+#[tokio::test]
+async fn test_warm_up_ignores_health_and_optionally_checks_auth() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_bad_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/durable/health"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({})))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .and(query_param("limit", "1"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+            "error": "unauthorized",
+            "message": "invalid API key"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.warm_up().await.unwrap();
+    let err = client
+        .warm_up_with_options(&everruns_sdk::client::WarmUpOptions {
+            validate_auth: true,
+        })
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, everruns_sdk::Error::Api { status: 401, .. }),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_cache_serves_gets_within_ttl_and_revalidates_with_etag() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .cache_ttl(std::time::Duration::from_millis(100))
+        .build()
+        .expect("client");
+    let models = serde_json::json!({"data": []});
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(&models),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.models().list().await.unwrap();
+    client.models().list().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    let listed = client.models().list().await.unwrap();
+    assert!(listed.data.is_empty());
+}
+
+#[tokio::test]
+async fn test_cache_refetches_when_entry_evicted_before_not_modified() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .cache_ttl(std::time::Duration::from_millis(50))
+        .build()
+        .expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304).set_delay(std::time::Duration::from_millis(200)))
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(serde_json::json!({"data": []})),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    client.models().list().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let revalidating = {
+        let client = client.clone();
+        tokio::spawn(async move { client.models().list().await })
+    };
+    // Evict the entry while the conditional request is waiting on its 304.
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    client.clear_cache();
+
+    let listed = revalidating
+        .await
+        .unwrap()
+        .expect("should refetch the body");
+    assert!(listed.data.is_empty());
+}
+
+#[tokio::test]
+async fn test_janitor_deletes_sessions_with_retry() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_1"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+            "error": {"code": "rate_limited", "message": "slow down"}
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_1"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_2"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": "not_found", "message": "session not found"}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_3"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let ids: Vec<String> = ["sess_1", "sess_2", "sess_3"].map(String::from).to_vec();
+    let processed = std::sync::Arc::new(Mutex::new(Vec::new()));
+    let sink = processed.clone();
+    let options = everruns_sdk::client::CleanupOptions {
+        max_qps: Some(50.0),
+        progress: Some(everruns_sdk::progress::SharedProgress::new(
+            move |p: &ProgressUpdate| sink.lock().unwrap().push((p.done, p.total)),
+        )),
+        ..Default::default()
+    };
+    let report = client.janitor().delete_sessions(&ids, &options).await;
+
+    assert_eq!(
+        *processed.lock().unwrap(),
+        vec![(0, Some(3)), (1, Some(3)), (2, Some(3)), (3, Some(3))]
+    );
+    assert_eq!(report.deleted, vec!["sess_1"]);
+    assert_eq!(report.not_found, vec!["sess_2"]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, "sess_3");
+    assert!(!report.is_success());
+}
+
+#[tokio::test]
+async fn test_janitor_delete_sessions_where_snapshots_before_deleting() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let session = |id: &str, tags: &[&str]| {
+        serde_json::json!({
+            "id": id,
+            "organization_id": "org_123",
+            "harness_id": "harness_123",
+            "status": "idle",
+            "tags": tags,
+            "created_at": "2026-03-13T00:00:00Z",
+            "updated_at": "2026-03-13T00:00:00Z"
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [
+                session("sess_1", &["ci"]),
+                session("sess_2", &["prod"]),
+                session("sess_3", &["ci"])
+            ],
+            "total": 3,
+            "offset": 0,
+            "limit": 100
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_1"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_3"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut report = client
+        .janitor()
+        .delete_sessions_where(
+            |session| session.tags.iter().any(|tag| tag == "ci"),
+            &Default::default(),
+        )
+        .await
+        .expect("listing should succeed");
+
+    report.deleted.sort();
+    assert_eq!(report.deleted, vec!["sess_1", "sess_3"]);
+    assert!(report.is_success());
+}
+
+#[cfg(feature = "sse")]
+#[tokio::test]
+async fn test_shutdown_drains_in_flight_requests() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"data": []}))
+                .set_delay(std::time::Duration::from_millis(200)),
+        )
+        .mount(&server)
+        .await;
+
+    let in_flight = {
+        let client = client.clone();
+        tokio::spawn(async move { client.models().list().await })
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let mut stream = client.events().stream("sess_123");
+
+    assert!(client.shutdown(std::time::Duration::from_secs(5)).await);
+    assert!(in_flight.await.unwrap().is_ok());
+    assert!(matches!(
+        client.models().list().await,
+        Err(everruns_sdk::Error::ShuttingDown)
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_retry_policy_retries_transient_statuses() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .retry_policy(
+            everruns_sdk::retry::RetryPolicy::exponential(2)
+                .initial_backoff(std::time::Duration::from_millis(1)),
+        )
+        .build()
+        .expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "msg_1",
+            "session_id": "sess_1",
+            "sequence": 1,
+            "role": "user",
+            "content": [{"type": "text", "text": "hi"}],
+            "created_at": "2024-01-01T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .respond_with(ResponseTemplate::new(502))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let message = client
+        .messages()
+        .create("sess_1", "hi")
+        .await
+        .expect("send should succeed after retries");
+    assert_eq!(message.id, "msg_1");
+
+    let err = client.agents().list().await.unwrap_err();
+    assert!(matches!(err, everruns_sdk::Error::Api { status: 502, .. }));
+}
+
+#[tokio::test]
+async fn test_problem_details_error_carries_body_retry_hint() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+            "type": "https://docs.everruns.com/errors/rate_limited",
+            "title": "Too Many Requests",
+            "status": 429,
+            "code": "rate_limited",
+            "detail": "Organization request rate exceeded",
+            "retry_after_seconds": 30
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client.agents().list().await.unwrap_err();
+    match err {
+        everruns_sdk::Error::RateLimited {
+            code,
+            message,
+            retry_after,
+        } => {
+            assert_eq!(code, "rate_limited");
+            assert_eq!(message, "Organization request rate exceeded");
+            assert_eq!(retry_after, Some(std::time::Duration::from_secs(30)));
+        }
+        other => panic!("expected RateLimited, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limited_error_carries_retry_after() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "2")
+                .set_body_json(serde_json::json!({
+                    "error": {"code": "rate_limited", "message": "slow down"}
+                })),
+        )
+        .mount(&server)
+        .await;
+
+    let err = client.agents().list().await.unwrap_err();
+    assert!(matches!(
+        err,
+        everruns_sdk::Error::RateLimited {
+            retry_after: Some(wait),
+            ..
+        } if wait == std::time::Duration::from_secs(2)
+    ));
+    assert_eq!(err.http_status(), 429);
+}
+
+#[tokio::test]
+async fn test_retry_policy_does_not_resend_post_after_server_error() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .retry_policy(
+            everruns_sdk::retry::RetryPolicy::exponential(2)
+                .initial_backoff(std::time::Duration::from_millis(1)),
+        )
+        .build()
+        .expect("client");
+
+    // The message may have been stored before the 503; a resend could
+    // duplicate it.
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client.messages().create("sess_1", "hi").await.unwrap_err();
+    assert!(matches!(err, everruns_sdk::Error::Api { status: 503, .. }));
+}
+
+#[tokio::test]
+async fn test_retry_policy_returns_retry_after_beyond_max_backoff() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .retry_policy(
+            everruns_sdk::retry::RetryPolicy::exponential(3)
+                .max_backoff(std::time::Duration::from_secs(1)),
+        )
+        .build()
+        .expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = tokio::time::timeout(std::time::Duration::from_secs(5), client.agents().list())
+        .await
+        .expect("should not wait out Retry-After")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        everruns_sdk::Error::RateLimited {
+            retry_after: Some(wait),
+            ..
+        } if wait == std::time::Duration::from_secs(3600)
+    ));
+}
+
+#[tokio::test]
+async fn test_retry_budget_stops_janitor_retries() {
+    let server = MockServer::start().await;
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .retry_budget(
+            everruns_sdk::retry::RetryBudget::new()
+                .ratio(0.0)
+                .min_retries(0),
+        )
+        .build()
+        .expect("client");
+
+    Mock::given(method("DELETE"))
+        .and(path("/v1/sessions/sess_1"))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = client
+        .janitor()
+        .delete_sessions(&["sess_1".to_string()], &Default::default())
+        .await;
+    assert_eq!(report.failed.len(), 1);
+}
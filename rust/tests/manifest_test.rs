@@ -0,0 +1,118 @@
+//! Tests for declarative agent management against a mock API.
+
+use everruns_sdk::Everruns;
+use everruns_sdk::manifest::{AgentChange, Manifest};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn agent(id: &str, name: &str, system_prompt: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "name": name,
+        "system_prompt": system_prompt,
+        "status": "active",
+        "created_at": "2026-04-15T00:00:00Z",
+        "updated_at": "2026-04-15T00:00:00Z"
+    })
+}
+
+const MANIFEST: &str = r#"{
+    "prune": true,
+    "agents": [
+        {"name": "support", "system_prompt": "Help customers."},
+        {"name": "jokes", "system_prompt": "Tell better jokes.", "tags": ["fun"]},
+        {"name": "triage", "system_prompt": "Route tickets."}
+    ]
+}"#;
+
+#[tokio::test]
+async fn test_apply_reconciles_agents_with_manifest() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/agents"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [
+                agent("agent_1", "support", "Help customers."),
+                agent("agent_2", "jokes", "Tell jokes."),
+                agent("agent_3", "legacy", "Old."),
+            ],
+            "total": 3, "offset": 0, "limit": 100
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/agents"))
+        .and(body_partial_json(serde_json::json!({"name": "jokes"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(agent(
+            "agent_2",
+            "jokes",
+            "Tell better jokes.",
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/agents"))
+        .and(body_partial_json(serde_json::json!({"name": "triage"})))
+        .respond_with(ResponseTemplate::new(201).set_body_json(agent(
+            "agent_4",
+            "triage",
+            "Route tickets.",
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/agents/agent_3"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let manifest = Manifest::from_json(MANIFEST).expect("manifest should parse");
+    let plan = client.plan(&manifest).await.expect("plan should succeed");
+    let [update, create, archive] = plan.changes.as_slice() else {
+        panic!("expected three changes, got {:?}", plan.changes);
+    };
+    let AgentChange::Update { id, diff, .. } = update else {
+        panic!("expected an update, got {update:?}");
+    };
+    assert_eq!(id, "agent_2");
+    assert_eq!(diff.tags_added, vec!["fun"]);
+    assert_eq!(
+        diff.system_prompt.as_ref().unwrap().to_string(),
+        "- Tell jokes.\n+ Tell better jokes.\n"
+    );
+    assert_eq!(
+        create,
+        &AgentChange::Create {
+            name: "triage".to_string()
+        }
+    );
+    assert!(matches!(archive, AgentChange::Archive { id, .. } if id == "agent_3"));
+    assert_eq!(
+        plan.to_string(),
+        "~ jokes (system_prompt, tags)\n+ triage\n- legacy\n"
+    );
+
+    let applied = client.apply(&manifest).await.expect("apply should succeed");
+    assert_eq!(applied, plan);
+}
+
+#[test]
+fn test_manifest_rejects_duplicate_and_unknown_entries() {
+    assert!(Manifest::from_json(r#"{"agents": [], "agnets": []}"#).is_err());
+
+    let manifest = Manifest::from_json(
+        r#"{"agents": [
+            {"name": "support", "system_prompt": "a"},
+            {"name": "support", "system_prompt": "b"}
+        ]}"#,
+    )
+    .expect("manifest should parse");
+    let client = Everruns::with_base_url("evr_test_key", "http://127.0.0.1:1").expect("client");
+    let err = tokio_test::block_on(client.plan(&manifest)).unwrap_err();
+    assert!(matches!(err, everruns_sdk::Error::Validation(_)), "{err:?}");
+}
@@ -0,0 +1,96 @@
+//! Tests for exporting session events as OTLP traces.
+
+use everruns_sdk::Event;
+use everruns_sdk::otel::OtlpExporter;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn event(seq: i32, event_type: &str, ts: &str, data: serde_json::Value) -> Event {
+    serde_json::from_value(serde_json::json!({
+        "id": format!("evt_{seq}"),
+        "type": event_type,
+        "ts": ts,
+        "session_id": "sess_1",
+        "sequence": seq,
+        "data": data,
+        "context": {"turn_id": "turn_1"}
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_export_sends_turn_and_tool_spans() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/traces"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = vec![
+        event(
+            4,
+            "turn.completed",
+            "2024-01-01T00:00:05Z",
+            serde_json::json!({"turn_id": "turn_1", "usage": {"input_tokens": 12, "output_tokens": 7}}),
+        ),
+        event(
+            1,
+            "turn.started",
+            "2024-01-01T00:00:00Z",
+            serde_json::json!({"turn_id": "turn_1", "input_message_id": "msg_1"}),
+        ),
+        event(
+            2,
+            "tool.started",
+            "2024-01-01T00:00:01Z",
+            serde_json::json!({"tool_call": {"id": "call_1", "name": "read_file", "arguments": {}}}),
+        ),
+        event(
+            3,
+            "tool.completed",
+            "2024-01-01T00:00:02.5Z",
+            serde_json::json!({
+                "tool_call_id": "call_1",
+                "tool_name": "read_file",
+                "success": false,
+                "status": "error",
+                "error": "not found"
+            }),
+        ),
+    ];
+
+    OtlpExporter::new(&server.uri())
+        .unwrap()
+        .service_name("support-bot")
+        .export(&events)
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    let resource = &body["resourceSpans"][0];
+    assert_eq!(
+        resource["resource"]["attributes"][0]["value"]["stringValue"],
+        "support-bot"
+    );
+    let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+    assert_eq!(spans.len(), 2);
+
+    let (turn, tool) = (&spans[0], &spans[1]);
+    assert_eq!(turn["name"], "turn");
+    assert_eq!(turn["startTimeUnixNano"], "1704067200000000000");
+    assert_eq!(turn["endTimeUnixNano"], "1704067205000000000");
+    assert_eq!(turn["status"]["code"], 1);
+    assert!(turn["attributes"].as_array().unwrap().contains(
+        &serde_json::json!({"key": "gen_ai.usage.input_tokens", "value": {"intValue": "12"}})
+    ));
+
+    assert_eq!(tool["name"], "read_file");
+    assert_eq!(tool["traceId"], turn["traceId"]);
+    assert_eq!(tool["parentSpanId"], turn["spanId"]);
+    assert_eq!(tool["endTimeUnixNano"], "1704067202500000000");
+    assert_eq!(tool["status"]["code"], 2);
+    assert_eq!(tool["status"]["message"], "not found");
+}
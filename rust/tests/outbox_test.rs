@@ -0,0 +1,105 @@
+//! Tests for the disk-backed DurableSender message queue.
+
+use everruns_sdk::outbox::DurableSender;
+use everruns_sdk::{CreateMessageRequest, Everruns};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn spool_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("everruns-outbox-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn message_response(text: &str) -> ResponseTemplate {
+    ResponseTemplate::new(201).set_body_json(serde_json::json!({
+        "id": format!("msg_{text}"),
+        "session_id": "sess_1",
+        "sequence": 1,
+        "role": "user",
+        "content": [{"type": "text", "text": text}],
+        "created_at": "2024-01-01T00:00:00Z"
+    }))
+}
+
+#[tokio::test]
+async fn test_durable_sender_keeps_messages_until_server_recovers() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let dir = spool_dir("recover");
+    let sender = DurableSender::new(client.clone(), &dir).await.unwrap();
+
+    let outage = Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount_as_scoped(&server)
+        .await;
+    sender
+        .send("sess_1", CreateMessageRequest::user_text("first"))
+        .await
+        .unwrap();
+    sender
+        .send("sess_1", CreateMessageRequest::user_text("second"))
+        .await
+        .unwrap();
+    assert_eq!(sender.pending().await.unwrap(), 2);
+    drop(outage);
+
+    for text in ["first", "second"] {
+        Mock::given(method("POST"))
+            .and(path("/v1/sessions/sess_1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "message": {"content": [{"type": "text", "text": text}]}
+            })))
+            .respond_with(message_response(text))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    // A fresh sender over the same directory picks up the spooled messages.
+    let restarted = DurableSender::new(client, &dir).await.unwrap();
+    let sent = restarted.flush().await.unwrap();
+    let ids: Vec<_> = sent.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["msg_first", "msg_second"]);
+    assert_eq!(restarted.pending().await.unwrap(), 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_durable_sender_sets_aside_rejected_messages() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let dir = spool_dir("rejected");
+    let sender = DurableSender::new(client, &dir).await.unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_gone/messages"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": "not_found", "message": "Session not found"}
+        })))
+        .mount(&server)
+        .await;
+
+    sender
+        .enqueue("sess_gone", CreateMessageRequest::user_text("hello"))
+        .await
+        .unwrap();
+    assert!(sender.flush().await.unwrap().is_empty());
+    assert_eq!(sender.pending().await.unwrap(), 0);
+
+    let failed = std::fs::read_dir(&dir)
+        .unwrap()
+        .filter(|e| {
+            e.as_ref()
+                .unwrap()
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "failed")
+        })
+        .count();
+    assert_eq!(failed, 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
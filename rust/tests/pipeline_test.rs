@@ -0,0 +1,148 @@
+//! Tests for multi-agent pipelines against a mock API.
+
+#![cfg(feature = "sse")]
+
+use everruns_sdk::pipeline::Pipeline;
+use everruns_sdk::{Error, Everruns};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn sse_event(event_type: &str, data: serde_json::Value) -> String {
+    format!("event: {event_type}\ndata: {data}\n\n")
+}
+
+/// Mock one agent: session creation, message send and a completed turn
+/// whose output is `text`.
+async fn mock_agent(
+    server: &MockServer,
+    agent_id: &str,
+    session_id: &str,
+    prompt: &str,
+    text: &str,
+) {
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions"))
+        .and(body_partial_json(serde_json::json!({"agent_id": agent_id})))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": session_id,
+            "organization_id": "org_1",
+            "harness_id": "harness_1",
+            "agent_id": agent_id,
+            "status": "started",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/sessions/{session_id}/events")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("/v1/sessions/{session_id}/messages")))
+        .and(body_partial_json(serde_json::json!({
+            "message": {"content": [{"type": "text", "text": prompt}]}
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": format!("{session_id}_in"),
+            "session_id": session_id,
+            "sequence": 1,
+            "role": "user",
+            "content": [{"type": "text", "text": prompt}],
+            "created_at": "2024-01-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+
+    let event = |id: &str, event_type: &str, data: serde_json::Value| {
+        sse_event(
+            event_type,
+            serde_json::json!({
+                "id": format!("{session_id}_{id}"),
+                "type": event_type,
+                "ts": "2024-01-01T00:00:00Z",
+                "session_id": session_id,
+                "data": data,
+                "context": {"turn_id": "turn_1", "input_message_id": format!("{session_id}_in")}
+            }),
+        )
+    };
+    let body = [
+        event(
+            "start",
+            "turn.started",
+            serde_json::json!({"turn_id": "turn_1"}),
+        ),
+        event(
+            "msg",
+            "output.message.completed",
+            serde_json::json!({"message": {
+                "id": format!("{session_id}_out"),
+                "session_id": session_id,
+                "sequence": 2,
+                "role": "agent",
+                "content": [{"type": "text", "text": text}],
+                "created_at": "2024-01-01T00:00:00Z"
+            }}),
+        ),
+        event(
+            "end",
+            "turn.completed",
+            serde_json::json!({"turn_id": "turn_1"}),
+        ),
+    ]
+    .concat();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/sessions/{session_id}/sse")))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_pipeline_feeds_outputs_downstream() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).unwrap();
+    mock_agent(
+        &server,
+        "agent_research",
+        "sess_a",
+        "Find otter facts",
+        "Otters hold hands.",
+    )
+    .await;
+    mock_agent(
+        &server,
+        "agent_writer",
+        "sess_b",
+        "Summarize: Otters hold hands.",
+        "Otters are social.",
+    )
+    .await;
+
+    let output = Pipeline::new()
+        .step("research", "agent_research", |_| "Find otter facts".into())
+        .step_after("summary", "agent_writer", &["research"], |inputs| {
+            format!("Summarize: {}", inputs.text("research"))
+        })
+        .run(&client)
+        .await
+        .unwrap();
+
+    assert_eq!(output.text("research"), "Otters hold hands.");
+    assert_eq!(output.text("summary"), "Otters are social.");
+    assert_eq!(output.outcome("summary").unwrap().session_id, "sess_b");
+}
+
+#[tokio::test]
+async fn test_pipeline_rejects_cycles() {
+    let client = Everruns::with_base_url("evr_test_key", "http://127.0.0.1:9").unwrap();
+    let result = Pipeline::new()
+        .step_after("a", "agent_1", &["b"], |_| String::new())
+        .step_after("b", "agent_2", &["a"], |_| String::new())
+        .run(&client)
+        .await;
+    assert!(matches!(result, Err(Error::Validation(_))));
+}
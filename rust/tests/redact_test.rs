@@ -0,0 +1,88 @@
+//! Tests for redaction of outgoing content against a mock API.
+
+use everruns_sdk::models::ContentPart;
+use everruns_sdk::redact::{Redaction, RedactionRecord, Redactor};
+use everruns_sdk::{CreateMessageRequest, Everruns};
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Masks a fixed secret and keeps its audit records.
+#[derive(Default)]
+struct MaskSecret {
+    audit: Arc<Mutex<Vec<RedactionRecord>>>,
+}
+
+impl Redactor for MaskSecret {
+    fn redact(&self, part: &mut ContentPart) -> Vec<Redaction> {
+        let ContentPart::Text { text } = part else {
+            return vec![];
+        };
+        let count = text.matches("hunter2").count();
+        *text = text.replace("hunter2", "*******");
+        (count > 0)
+            .then(|| Redaction::new("password", count))
+            .into_iter()
+            .collect()
+    }
+
+    fn audit(&self, record: &RedactionRecord) {
+        self.audit.lock().unwrap().push(record.clone());
+    }
+}
+
+fn message_json(text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": "msg_1",
+        "session_id": "sess_1",
+        "sequence": 1,
+        "role": "user",
+        "content": [{"type": "text", "text": text}],
+        "created_at": "2026-04-15T00:00:00Z"
+    })
+}
+
+#[tokio::test]
+async fn test_redactor_scrubs_outgoing_messages_and_audits() {
+    let server = MockServer::start().await;
+    let redactor = MaskSecret::default();
+    let audit = redactor.audit.clone();
+    let client = Everruns::builder()
+        .api_key("evr_test_key")
+        .base_url(server.uri())
+        .redactor(redactor)
+        .build()
+        .expect("client");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .and(body_json(serde_json::json!({
+            "message": {
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "hello"},
+                    {"type": "text", "text": "my password is ******* (really, *******)"}
+                ]
+            }
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(message_json("redacted")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut req = CreateMessageRequest::user_text("hello");
+    req.message.content.push(ContentPart::text(
+        "my password is hunter2 (really, hunter2)",
+    ));
+    client
+        .messages()
+        .create_with_options("sess_1", req)
+        .await
+        .expect("message should be sent");
+
+    let audit = audit.lock().unwrap();
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].session_id, "sess_1");
+    assert_eq!(audit[0].part_index, 1);
+    assert_eq!(audit[0].redaction, Redaction::new("password", 2));
+}
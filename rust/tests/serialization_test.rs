@@ -3,10 +3,12 @@
 //! All output types must be serializable to support caching, logging, and persistence.
 
 use everruns_sdk::{
-    Agent, AgentCapabilityConfig, CapabilityInfo, CreateAgentRequest, CreateMessageRequest,
-    CreateSessionRequest, Event, ExternalActor, InitialFile, ListResponse, Message, Session,
-    ToolDefinition, extract_tool_calls, generate_agent_id, generate_harness_id,
-    validate_agent_name, validate_harness_name,
+    Agent, AgentCapabilityConfig, CapabilityInfo, CompactionReason, ContextCompactedData,
+    ContextCompactingData, CreateAgentRequest, CreateMessageRequest, CreateSessionRequest, Event,
+    ExternalActor, GuardrailExample, InitialFile, InlineAgent, ListResponse, Message, MessageInput,
+    OutputMessageReplacedData, Session, SetSecretsRequest, TokenUsage, ToolCompletedData,
+    ToolDefinition, ToolProgressData, ToolStartedData, extract_tool_calls, generate_agent_id,
+    generate_harness_id, validate_agent_name, validate_harness_name,
 };
 
 /// Test that ListResponse<Agent> can be serialized and deserialized (round-trip)
@@ -123,6 +125,31 @@ fn test_create_session_request_with_locale() {
     assert_eq!(value.get("locale"), Some(&serde_json::json!("uk-UA")));
 }
 
+#[test]
+fn test_create_session_request_with_max_iterations() {
+    let request = CreateSessionRequest::new().max_iterations(8);
+
+    let value = serde_json::to_value(&request).expect("request should serialize");
+    assert_eq!(value.get("max_iterations"), Some(&serde_json::json!(8)));
+}
+
+/// Test CreateSessionRequest with an inline agent definition
+#[test]
+fn test_create_session_request_with_inline_agent() {
+    let agent = InlineAgent::new("You tell dad jokes.")
+        .capabilities(vec![AgentCapabilityConfig::new("current_time")])
+        .model_id("model_123");
+    let request = CreateSessionRequest::new()
+        .agent_id("agent_123")
+        .inline_agent(agent);
+
+    let value = serde_json::to_value(&request).expect("request should serialize");
+    assert!(value.get("agent_id").is_none());
+    assert_eq!(value["system_prompt"], "You tell dad jokes.");
+    assert_eq!(value["model_id"], "model_123");
+    assert_eq!(value["capabilities"][0]["ref"], "current_time");
+}
+
 /// Test Session without agent_id (agent is optional)
 #[test]
 fn test_session_without_agent_id() {
@@ -648,6 +675,26 @@ fn test_create_agent_request_without_display_name() {
     assert!(raw.get("display_name").is_none());
 }
 
+#[test]
+fn test_event_kind_classifies_known_and_unknown_types() {
+    use everruns_sdk::events::EventKind;
+
+    let json = r#"{
+        "id": "evt_123",
+        "type": "turn.failed",
+        "ts": "2024-01-01T00:00:00Z",
+        "session_id": "session_456",
+        "data": {}
+    }"#;
+    let event: Event = serde_json::from_str(json).expect("Event should deserialize");
+    assert_eq!(event.kind(), EventKind::TurnFailed);
+    assert_eq!(event.kind().as_str(), "turn.failed");
+
+    let unknown = EventKind::parse("browser.screenshot");
+    assert_eq!(unknown, EventKind::Unknown("browser.screenshot"));
+    assert_eq!(unknown.as_str(), "browser.screenshot");
+}
+
 /// Test that Event serialization preserves the "type" field name (not "event_type")
 #[test]
 fn test_event_type_field_rename() {
@@ -841,3 +888,229 @@ fn test_create_message_request_without_external_actor() {
     let serialized = serde_json::to_string(&req).expect("should serialize");
     assert!(!serialized.contains("external_actor"));
 }
+
+/// Test typed access to context.compacted event data
+#[test]
+fn test_context_compacted_event_data() {
+    let json = r#"{
+        "id": "evt_1",
+        "type": "context.compacted",
+        "ts": "2024-01-15T10:30:00.000Z",
+        "session_id": "sess_1",
+        "data": {
+            "strategy_used": "observation_masking+native",
+            "messages_before": 120,
+            "messages_after": 18,
+            "duration_ms": 850,
+            "steps": [
+                {"strategy": "observation_masking", "messages_after": 60, "duration_ms": 12},
+                {"strategy": "native", "messages_after": 18, "duration_ms": 838}
+            ]
+        }
+    }"#;
+
+    let event: Event = serde_json::from_str(json).expect("should deserialize");
+    let data: ContextCompactedData = event.data_as().expect("should parse data");
+    assert_eq!(data.messages_before, 120);
+    assert_eq!(data.messages_after, 18);
+    assert_eq!(data.steps.len(), 2);
+    assert_eq!(data.steps[1].strategy, "native");
+
+    let compacting: ContextCompactingData = serde_json::from_value(serde_json::json!({
+        "reason": "request_too_large",
+        "strategy": "native",
+        "messages_before": 120
+    }))
+    .expect("should deserialize");
+    assert_eq!(compacting.reason, CompactionReason::RequestTooLarge);
+    assert!(event.data_as::<ContextCompactingData>().is_err());
+}
+
+/// Test that event context keeps tracing fields and unknown keys
+#[test]
+fn test_event_context_retains_unknown_keys() {
+    let json = r#"{
+        "id": "evt_1",
+        "type": "output.message.delta",
+        "ts": "2024-01-15T10:30:00.000Z",
+        "session_id": "sess_1",
+        "data": {},
+        "context": {
+            "turn_id": "turn_1",
+            "trace_id": "turn_1",
+            "span_id": "span_2",
+            "parent_span_id": "span_1",
+            "region": "eu"
+        }
+    }"#;
+
+    let event: Event = serde_json::from_str(json).expect("should deserialize");
+    assert_eq!(event.context.parent_span_id.as_deref(), Some("span_1"));
+    assert_eq!(event.context.extra["region"], "eu");
+    assert!(event.context.exec_id.is_none());
+
+    let reserialized = serde_json::to_value(&event).expect("should serialize");
+    assert_eq!(reserialized["context"]["region"], "eu");
+}
+
+/// Test typed access to tool lifecycle event data
+#[test]
+fn test_tool_lifecycle_event_data() {
+    let event = |event_type: &str, data: serde_json::Value| -> Event {
+        serde_json::from_value(serde_json::json!({
+            "id": "evt_1",
+            "type": event_type,
+            "ts": "2024-01-15T10:30:00.000Z",
+            "session_id": "sess_1",
+            "data": data
+        }))
+        .unwrap()
+    };
+
+    let started: ToolStartedData = event(
+        "tool.started",
+        serde_json::json!({
+            "tool_call": {"id": "call_1", "name": "read_file", "arguments": {"path": "/a.txt"}},
+            "display_name": "Read file"
+        }),
+    )
+    .data_as()
+    .expect("should parse started");
+    assert_eq!(started.tool_call.name, "read_file");
+    assert_eq!(started.tool_call.arguments["path"], "/a.txt");
+
+    let progress: ToolProgressData = event(
+        "tool.progress",
+        serde_json::json!({"tool_call_id": "call_1", "tool_name": "read_file", "message": "Reading"}),
+    )
+    .data_as()
+    .expect("should parse progress");
+    assert_eq!(progress.message, "Reading");
+
+    let completed: ToolCompletedData = event(
+        "tool.completed",
+        serde_json::json!({
+            "tool_call_id": "call_1",
+            "tool_name": "read_file",
+            "success": true,
+            "status": "success",
+            "result": [{"type": "text", "text": "hello"}],
+            "duration_ms": 42
+        }),
+    )
+    .data_as()
+    .expect("should parse completed");
+    assert!(completed.success);
+    assert_eq!(completed.duration_ms, Some(42));
+    assert_eq!(completed.result.map(|r| r.len()), Some(1));
+    assert!(completed.error.is_none());
+}
+
+/// Test that the message builder assembles mixed content parts
+#[test]
+fn test_message_input_builder_serializes_mixed_content() {
+    let path = std::env::temp_dir().join(format!("everruns-builder-{}.png", std::process::id()));
+    std::fs::write(&path, b"PNG!").unwrap();
+
+    let input = MessageInput::builder()
+        .text("look at this")
+        .image_path(&path)
+        .image_file("img_1")
+        .build()
+        .expect("should build");
+    let _ = std::fs::remove_file(&path);
+
+    let json = serde_json::to_value(&input).expect("should serialize");
+    assert_eq!(json["role"], "user");
+    assert_eq!(
+        json["content"],
+        serde_json::json!([
+            {"type": "text", "text": "look at this"},
+            {"type": "image", "url": null, "base64": "UE5HIQ==", "media_type": "image/png"},
+            {"type": "image_file", "image_id": "img_1"}
+        ])
+    );
+
+    assert!(
+        MessageInput::builder()
+            .image_path("spec.pdf")
+            .build()
+            .is_err()
+    );
+}
+
+/// Test TokenUsage aggregation across turns
+#[test]
+fn test_token_usage_add_and_sum() {
+    let usage = |input: u64, output: u64, cache: u64| -> TokenUsage {
+        serde_json::from_value(serde_json::json!({
+            "input_tokens": input,
+            "output_tokens": output,
+            "cache_read_tokens": cache
+        }))
+        .unwrap()
+    };
+
+    let mut total = usage(10, 5, 1) + usage(3, 2, 0);
+    assert_eq!(total, usage(13, 7, 1));
+    total += usage(7, 3, 2);
+    assert_eq!(total, usage(20, 10, 3));
+
+    let summed: TokenUsage = vec![usage(1, 1, 0), usage(2, 2, 1)].into_iter().sum();
+    assert_eq!(summed, usage(3, 3, 1));
+}
+
+/// Test guardrail preset adoption and typed replacement events
+#[test]
+fn test_guardrails_capability_and_replaced_event() {
+    let example: GuardrailExample = serde_json::from_value(serde_json::json!({
+        "name": "secret-detection",
+        "display_name": "Secret & Credential Detection",
+        "description": "Blocks leaked credentials",
+        "tags": ["security"],
+        "check_types": ["regex"],
+        "stages": ["output"],
+        "data_egress": "none",
+        "config": {"checks": [{"type": "regex", "pattern": "sk-[a-z0-9]+"}]}
+    }))
+    .expect("should deserialize");
+    let capability = serde_json::to_value(example.capability_config()).unwrap();
+    assert_eq!(capability["ref"], "guardrails");
+    assert_eq!(capability["config"]["checks"][0]["type"], "regex");
+
+    let event: Event = serde_json::from_value(serde_json::json!({
+        "id": "evt_1",
+        "type": "output.message.replaced",
+        "ts": "2024-01-15T10:30:00.000Z",
+        "session_id": "sess_1",
+        "data": {
+            "turn_id": "turn_1",
+            "guardrail_capability_id": "prompt_canary_guardrail",
+            "guardrail_id": "prompt_canary",
+            "reason_code": "system_prompt_leak",
+            "replacement": "I can't share that."
+        }
+    }))
+    .expect("should deserialize");
+    let data: OutputMessageReplacedData = event.data_as().expect("should parse data");
+    assert_eq!(data.reason_code, "system_prompt_leak");
+    assert_eq!(data.replacement, "I can't share that.");
+}
+
+#[test]
+fn test_secrets_stay_out_of_debug_output() {
+    let mut secrets = std::collections::HashMap::new();
+    secrets.insert("GITHUB_TOKEN".to_string(), "ghp_secret".to_string());
+    let req = SetSecretsRequest::new(secrets);
+    let debug = format!("{req:?}");
+    assert!(debug.contains("GITHUB_TOKEN"));
+    assert!(!debug.contains("ghp_secret"));
+    assert_eq!(
+        serde_json::to_value(&req).unwrap(),
+        serde_json::json!({"secrets": {"GITHUB_TOKEN": "ghp_secret"}})
+    );
+
+    let capability =
+        AgentCapabilityConfig::new("github").config(serde_json::json!({"token": "ghp_secret"}));
+    assert!(!format!("{capability:?}").contains("ghp_secret"));
+}
@@ -0,0 +1,116 @@
+//! Tests for the JSONL SessionLogger.
+
+use everruns_sdk::Event;
+use everruns_sdk::session_log::{LogRecord, RecordKind, SessionLogger};
+
+fn event(id: &str, event_type: &str, data: serde_json::Value) -> Event {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "type": event_type,
+        "ts": "2024-01-01T00:00:00Z",
+        "session_id": "sess_1",
+        "data": data,
+        "context": {"turn_id": "turn_1"}
+    }))
+    .unwrap()
+}
+
+fn session_events() -> Vec<Event> {
+    vec![
+        event(
+            "evt_1",
+            "turn.started",
+            serde_json::json!({"turn_id": "turn_1"}),
+        ),
+        event(
+            "evt_2",
+            "output.message.delta",
+            serde_json::json!({"delta": "Hel"}),
+        ),
+        event(
+            "evt_3",
+            "tool.completed",
+            serde_json::json!({
+                "tool_call_id": "call_1",
+                "tool_name": "search",
+                "success": true,
+                "status": "success",
+                "duration_ms": 30
+            }),
+        ),
+        event(
+            "evt_4",
+            "output.message.completed",
+            serde_json::json!({
+                "message": {
+                    "id": "msg_1",
+                    "session_id": "sess_1",
+                    "sequence": 2,
+                    "role": "agent",
+                    "content": [{"type": "text", "text": "Hello"}],
+                    "created_at": "2024-01-01T00:00:00Z"
+                },
+                "usage": {"input_tokens": 5, "output_tokens": 2}
+            }),
+        ),
+    ]
+}
+
+fn read_records(path: &std::path::Path) -> Vec<LogRecord> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_session_logger_writes_normalized_records() {
+    let path = std::env::temp_dir().join(format!("everruns-log-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let stream = futures::stream::iter(session_events().into_iter().map(Ok));
+    SessionLogger::new(&path)
+        .redact_text(true)
+        .run(stream)
+        .await
+        .unwrap();
+
+    let records = read_records(&path);
+    let kinds: Vec<_> = records.iter().map(|r| r.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![RecordKind::Turn, RecordKind::Tool, RecordKind::Message]
+    );
+    assert_eq!(records[0].status.as_deref(), Some("started"));
+    assert_eq!(records[1].tool_name.as_deref(), Some("search"));
+    assert_eq!(records[1].duration_ms, Some(30));
+    assert_eq!(records[2].role.as_deref(), Some("agent"));
+    assert_eq!(records[2].text.as_deref(), Some("[redacted]"));
+    assert_eq!(records[2].usage.as_ref().unwrap().output_tokens, 2);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_session_logger_rotates_by_size() {
+    let path = std::env::temp_dir().join(format!("everruns-rotate-{}.jsonl", std::process::id()));
+    let rotated = |n: usize| std::path::PathBuf::from(format!("{}.{n}", path.display()));
+    for p in [path.clone(), rotated(1), rotated(2)] {
+        let _ = std::fs::remove_file(p);
+    }
+
+    // Small enough that every record goes to a fresh file.
+    let mut logger = SessionLogger::new(&path).rotate_at(10, 1);
+    for event in session_events() {
+        logger.log(&event).await.unwrap();
+    }
+
+    assert_eq!(read_records(&path)[0].event_id, "evt_4");
+    assert_eq!(read_records(&rotated(1))[0].event_id, "evt_3");
+    assert!(!rotated(2).exists());
+
+    for p in [path.clone(), rotated(1)] {
+        let _ = std::fs::remove_file(p);
+    }
+}
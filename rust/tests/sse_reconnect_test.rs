@@ -7,6 +7,8 @@
 //! - Bug 3: HTTP client reused across reconnections (verified via request count)
 //! - Bug 4: Idle timeout triggers reconnection on silent half-open connections
 
+#![cfg(feature = "sse")]
+
 use everruns_sdk::Everruns;
 use futures::StreamExt;
 use std::sync::Arc;
@@ -190,6 +192,7 @@ async fn test_connected_event_resets_backoff_after_error() {
 /// Uses a raw TCP server because wiremock returns bodies immediately (stream
 /// ends with Poll::Ready(None)) and can't simulate a hanging connection.
 #[tokio::test]
+#[allow(clippy::useless_format)]
 async fn test_idle_timeout_triggers_reconnect_on_silent_connection() {
     use tokio::io::AsyncWriteExt;
     use tokio::net::TcpListener;
@@ -265,3 +268,76 @@ async fn test_idle_timeout_triggers_reconnect_on_silent_connection() {
 
     stream.stop();
 }
+
+/// Heartbeat comments keep a quiet connection alive; silence past the
+/// declared interval yields HeartbeatMissed and reconnects.
+#[tokio::test]
+async fn test_heartbeat_missed_triggers_reconnect() {
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let connection_count = Arc::new(AtomicUsize::new(0));
+    let count = connection_count.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let n = count.fetch_add(1, Ordering::SeqCst);
+
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 4096];
+                let _ = tokio::io::AsyncReadExt::read(&mut socket, &mut buf).await;
+                let header = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\nevent: connected\ndata: {}\n\n";
+                let _ = socket.write_all(header.as_bytes()).await;
+
+                if n == 0 {
+                    // Heartbeats for a while, then silence (half-open)
+                    for _ in 0..6 {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        let _ = socket.write_all(b": heartbeat\n\n").await;
+                    }
+                    tokio::time::sleep(Duration::from_secs(300)).await;
+                } else {
+                    let event = sse_event(
+                        "session.idled",
+                        &make_event_json("evt_hb_1", "session.idled"),
+                    );
+                    let _ = socket.write_all(event.as_bytes()).await;
+                    tokio::time::sleep(Duration::from_secs(300)).await;
+                }
+            });
+        }
+    });
+
+    let client = Everruns::with_base_url("test_key", &format!("http://{}", addr)).unwrap();
+    let opts = everruns_sdk::sse::StreamOptions::default()
+        .with_heartbeat_interval(Duration::from_millis(200))
+        .with_max_retries(5);
+    let mut stream = client.events().stream_with_options("sess_hb", opts);
+
+    let started = std::time::Instant::now();
+    let first = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("should not timeout")
+        .expect("stream should yield an item");
+    assert!(matches!(
+        first,
+        Err(everruns_sdk::Error::HeartbeatMissed {
+            interval_ms: 200,
+            ..
+        })
+    ));
+    assert!(started.elapsed() >= Duration::from_millis(600));
+
+    let event = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("should not timeout")
+        .expect("stream should yield an item")
+        .expect("item should be Ok");
+    assert_eq!(event.id, "evt_hb_1");
+    assert_eq!(connection_count.load(Ordering::SeqCst), 2);
+
+    stream.stop();
+}
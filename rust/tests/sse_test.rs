@@ -1,5 +1,7 @@
 //! Tests for SSE streaming and retry logic
 
+#![cfg(feature = "sse")]
+
 use everruns_sdk::sse::{
     DEFAULT_IDLE_TIMEOUT_SECS, DisconnectingData, READ_TIMEOUT_SECS, StreamOptions,
 };
@@ -66,6 +68,7 @@ fn test_stream_options_default_idle_timeout() {
 }
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn test_idle_timeout_constant_above_heartbeat_interval() {
     // Server heartbeats every 30s. Idle timeout must be above that.
     assert_eq!(DEFAULT_IDLE_TIMEOUT_SECS, 45);
@@ -100,6 +103,7 @@ fn test_disconnecting_data_parse_zero_retry() {
 }
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn test_read_timeout_above_heartbeat_interval() {
     // Server sends heartbeat comments every 30s. Read timeout must be
     // above that to avoid false positives, but close enough to quickly
@@ -198,3 +202,328 @@ mod graceful_disconnect_tests {
         assert!(!was_connected, "Signal should be false initially");
     }
 }
+
+#[tokio::test]
+async fn test_thinking_deltas_yields_only_typed_thinking() {
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let event = |id: &str, event_type: &str, data: serde_json::Value| {
+        let json = serde_json::json!({
+            "id": id,
+            "type": event_type,
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "data": data
+        });
+        format!("event: {event_type}\ndata: {json}\n\n")
+    };
+    let body = [
+        event(
+            "evt_1",
+            "reason.thinking.delta",
+            serde_json::json!({"turn_id": "turn_1", "delta": "Let me", "accumulated": "Let me"}),
+        ),
+        event(
+            "evt_2",
+            "output.message.delta",
+            serde_json::json!({"turn_id": "turn_1", "delta": "Hi", "accumulated": "Hi"}),
+        ),
+        event(
+            "evt_3",
+            "reason.thinking.delta",
+            serde_json::json!({"turn_id": "turn_1", "delta": " think", "accumulated": "Let me think"}),
+        ),
+    ]
+    .concat();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    let client = everruns_sdk::Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let deltas: Vec<_> = client
+        .events()
+        .stream("sess_1")
+        .thinking_deltas()
+        .take(2)
+        .collect()
+        .await;
+    let deltas: Vec<_> = deltas.into_iter().map(|d| d.unwrap()).collect();
+    assert_eq!(deltas[0].delta, "Let me");
+    assert_eq!(deltas[1].accumulated, "Let me think");
+    assert_eq!(deltas[1].turn_id, "turn_1");
+}
+
+#[tokio::test]
+async fn test_stream_cursor_resumes_after_last_event() {
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let event = |id: &str| {
+        let json = serde_json::json!({
+            "id": id,
+            "type": "turn.started",
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "data": {"turn_id": "turn_1"}
+        });
+        format!("event: turn.started\ndata: {json}\n\n")
+    };
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .and(query_param("since_id", "evt_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(event("evt_2"), "text/event-stream"))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(event("evt_1"), "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    let client = everruns_sdk::Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let mut stream = client.events().stream("sess_1");
+    assert_eq!(stream.cursor().last_event_id, None);
+    stream.next().await.unwrap().unwrap();
+
+    let saved = serde_json::to_string(&stream.cursor()).unwrap();
+    let cursor: everruns_sdk::sse::StreamCursor = serde_json::from_str(&saved).unwrap();
+    assert_eq!(cursor.session_id, "sess_1");
+    assert_eq!(cursor.last_event_id.as_deref(), Some("evt_1"));
+
+    let mut resumed = client.events().resume(&cursor);
+    let next = resumed.next().await.unwrap().unwrap();
+    assert_eq!(next.id, "evt_2");
+    assert_eq!(resumed.cursor().last_event_id.as_deref(), Some("evt_2"));
+}
+
+#[tokio::test]
+async fn test_for_ui_dedups_and_notifies_reconnect() {
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let event = |id: &str| {
+        let json = serde_json::json!({
+            "id": id,
+            "type": "output.message.delta",
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "data": {"delta": "Hi"}
+        });
+        format!("event: output.message.delta\ndata: {json}\n\n")
+    };
+    let connected = "event: connected\ndata: {}\n\n".to_string();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            format!("{connected}{}", event("evt_1")),
+            "text/event-stream",
+        ))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            format!("{connected}{}{}", event("evt_1"), event("evt_2")),
+            "text/event-stream",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = everruns_sdk::Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let events: Vec<_> = client
+        .events()
+        .stream_with_options("sess_1", StreamOptions::for_ui())
+        .take(3)
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(
+        types,
+        [
+            "output.message.delta",
+            everruns_sdk::sse::RECONNECTED_EVENT,
+            "output.message.delta"
+        ]
+    );
+    assert_eq!(events[1].data["attempts"], 1);
+    assert_eq!(events[2].id, "evt_2");
+}
+
+#[tokio::test]
+async fn test_stream_from_start_replays_then_follows() {
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let event = |id: &str| {
+        serde_json::json!({
+            "id": id,
+            "type": "turn.started",
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "data": {}
+        })
+    };
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .and(query_param_is_missing("since_id"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"data": [event("evt_1"), event("evt_2")]})),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .and(query_param("since_id", "evt_2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .and(query_param("since_id", "evt_2"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            format!("event: turn.started\ndata: {}\n\n", event("evt_3")),
+            "text/event-stream",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = everruns_sdk::Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let ids: Vec<_> = client
+        .events()
+        .stream_from_start("sess_1")
+        .take(3)
+        .map(|event| event.unwrap().id)
+        .collect()
+        .await;
+    assert_eq!(ids, ["evt_1", "evt_2", "evt_3"]);
+}
+
+#[tokio::test]
+async fn test_for_turn_filters_other_turns() {
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let event = |id: &str, turn_id: &str| {
+        let json = serde_json::json!({
+            "id": id,
+            "type": "output.message.delta",
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "context": {"turn_id": turn_id},
+            "data": {"delta": "Hi"}
+        });
+        format!("event: output.message.delta\ndata: {json}\n\n")
+    };
+    let body = [
+        event("evt_1", "turn_a"),
+        event("evt_2", "turn_b"),
+        event("evt_3", "turn_a"),
+    ]
+    .concat();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    let client = everruns_sdk::Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let mut stream = client
+        .events()
+        .stream_with_options("sess_1", StreamOptions::for_turn("turn_b"));
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.id, "evt_2");
+    assert_eq!(stream.last_event_id(), Some("evt_2"));
+}
+
+#[tokio::test]
+async fn test_gap_detection_and_backfill() {
+    use everruns_sdk::sse::{GAP_DETECTED_EVENT, GapDetected};
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let event = |seq: i32| {
+        serde_json::json!({
+            "id": format!("evt_{seq}"),
+            "type": "tool.completed",
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "sequence": seq,
+            "data": {}
+        })
+    };
+    let body: String = [1, 2, 5]
+        .map(|seq| format!("event: tool.completed\ndata: {}\n\n", event(seq)))
+        .concat();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .and(query_param("after_sequence", "2"))
+        .and(query_param("before_sequence", "5"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"data": [event(3), event(4)]})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = everruns_sdk::Everruns::with_base_url("test_key", &server.uri()).unwrap();
+
+    let detected: Vec<_> = client
+        .events()
+        .stream_with_options("sess_1", StreamOptions::new().with_gap_detection(true))
+        .take(4)
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    let types: Vec<_> = detected.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types[2], GAP_DETECTED_EVENT);
+    let gap: GapDetected = detected[2].data_as().unwrap();
+    assert_eq!(
+        gap,
+        GapDetected {
+            expected: 3,
+            got: 5
+        }
+    );
+    assert_eq!(detected[3].id, "evt_5");
+
+    let backfilled: Vec<_> = client
+        .events()
+        .stream_with_options("sess_1", StreamOptions::new().with_gap_backfill(true))
+        .take(5)
+        .map(|event| event.unwrap().id)
+        .collect()
+        .await;
+    assert_eq!(backfilled, ["evt_1", "evt_2", "evt_3", "evt_4", "evt_5"]);
+}
@@ -0,0 +1,106 @@
+//! Tests for the SQLite session mirror against a mock API.
+#![cfg(feature = "sqlite")]
+
+use everruns_sdk::Everruns;
+use everruns_sdk::store::LocalStore;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn message(id: &str, sequence: u64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "session_id": "sess_1",
+        "sequence": sequence,
+        "role": "user",
+        "content": [{"type": "text", "text": id}],
+        "created_at": "2026-04-15T00:00:00Z"
+    })
+}
+
+fn event(id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "type": "turn.completed",
+        "ts": "2026-04-15T00:00:00Z",
+        "session_id": "sess_1",
+        "data": {}
+    })
+}
+
+#[tokio::test]
+async fn test_sync_session_fetches_only_new_history() {
+    let server = MockServer::start().await;
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).expect("client");
+    let store = LocalStore::in_memory().expect("store");
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_1",
+            "organization_id": "org_1",
+            "harness_id": "harness_1",
+            "title": "Support chat",
+            "status": "idle",
+            "created_at": "2026-04-15T00:00:00Z",
+            "updated_at": "2026-04-15T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+    let first_messages = Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [message("msg_1", 1), message("msg_2", 2)]
+        })))
+        .mount_as_scoped(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .and(query_param_is_missing("since_id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [event("event_1"), event("event_2")]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = store.sync_session(&client, "sess_1").await.unwrap();
+    assert_eq!((report.messages, report.events), (2, 2));
+    drop(first_messages);
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [message("msg_1", 1), message("msg_2", 2), message("msg_3", 3)]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .and(query_param("since_id", "event_2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [event("event_3")]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = store.sync_session(&client, "sess_1").await.unwrap();
+    assert_eq!((report.messages, report.events), (1, 1));
+
+    let ids: Vec<String> = store
+        .messages("sess_1")
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(ids, vec!["msg_1", "msg_2", "msg_3"]);
+    assert_eq!(store.events("sess_1").unwrap().len(), 3);
+    assert_eq!(
+        store.session("sess_1").unwrap().unwrap().title.as_deref(),
+        Some("Support chat")
+    );
+
+    store.remove_session("sess_1").unwrap();
+    assert!(store.sessions().unwrap().is_empty());
+    assert!(store.messages("sess_1").unwrap().is_empty());
+}
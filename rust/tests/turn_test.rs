@@ -0,0 +1,583 @@
+//! Tests for TurnHandle lifecycle tracking against a mock SSE server.
+
+#![cfg(feature = "sse")]
+
+use everruns_sdk::turn::{RunOptions, StopReason, TurnStatus};
+use everruns_sdk::{CreateMessageRequest, Everruns};
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn sse_event(event_type: &str, data: &str) -> String {
+    format!("event: {}\ndata: {}\n\n", event_type, data)
+}
+
+fn turn_event(id: &str, event_type: &str, turn_id: &str, data: serde_json::Value) -> String {
+    let event = serde_json::json!({
+        "id": id,
+        "type": event_type,
+        "ts": "2024-01-01T00:00:00Z",
+        "session_id": "sess_1",
+        "data": data,
+        "context": {
+            "turn_id": turn_id,
+            "input_message_id": input_message_id(turn_id),
+            "trace_id": turn_id
+        }
+    });
+    sse_event(event_type, &event.to_string())
+}
+
+fn input_message_id(turn_id: &str) -> &'static str {
+    if turn_id == "turn_1" {
+        "msg_in"
+    } else {
+        "msg_other"
+    }
+}
+
+/// `llm.generation` data as in the spec's `LlmGenerationData`
+fn llm_generation(finish_reason: &str) -> serde_json::Value {
+    serde_json::json!({
+        "messages": [],
+        "output": {"text": "..."},
+        "metadata": {
+            "model": "claude-sonnet-4-5",
+            "success": true,
+            "finish_reasons": [finish_reason]
+        }
+    })
+}
+
+fn output_message(id: &str, text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "session_id": "sess_1",
+        "sequence": 2,
+        "role": "agent",
+        "content": [{"type": "text", "text": text}],
+        "created_at": "2024-01-01T00:00:00Z"
+    })
+}
+
+async fn mock_send(server: &MockServer, sse_body: String) {
+    mock_send_with_sse(
+        server,
+        ResponseTemplate::new(200).set_body_raw(sse_body.into_bytes(), "text/event-stream"),
+    )
+    .await;
+}
+
+async fn mock_send_with_sse(server: &MockServer, sse: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .and(query_param("limit", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{
+                "id": "evt_prev",
+                "type": "turn.completed",
+                "ts": "2024-01-01T00:00:00Z",
+                "session_id": "sess_1",
+                "data": {}
+            }],
+            "total": 1,
+            "offset": 0,
+            "limit": 1
+        })))
+        .mount(server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "msg_in",
+            "session_id": "sess_1",
+            "sequence": 1,
+            "role": "user",
+            "content": [{"type": "text", "text": "Hi"}],
+            "created_at": "2024-01-01T00:00:00Z"
+        })))
+        .mount(server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path_regex("/v1/sessions/sess_1/sse"))
+        .and(query_param("since_id", "evt_prev"))
+        .respond_with(sse)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_turn_handle_completes_with_messages_and_usage() {
+    let server = MockServer::start().await;
+    let other_turn = serde_json::json!({"turn_id": "turn_other"});
+    let body = [
+        sse_event("connected", "{}"),
+        turn_event("evt_0", "turn.started", "turn_other", other_turn),
+        turn_event(
+            "evt_1",
+            "turn.started",
+            "turn_1",
+            serde_json::json!({"turn_id": "turn_1", "input_message_id": "msg_in"}),
+        ),
+        turn_event(
+            "evt_2",
+            "output.message.completed",
+            "turn_1",
+            serde_json::json!({
+                "message": output_message("msg_out_1", "Hello"),
+                "usage": {"input_tokens": 10, "output_tokens": 5}
+            }),
+        ),
+        turn_event(
+            "evt_3",
+            "output.message.completed",
+            "turn_1",
+            serde_json::json!({
+                "message": output_message("msg_out_2", "again"),
+                "usage": {"input_tokens": 3, "output_tokens": 2, "cache_read_tokens": 1}
+            }),
+        ),
+        turn_event(
+            "evt_4",
+            "llm.generation",
+            "turn_1",
+            llm_generation("max_tokens"),
+        ),
+        turn_event(
+            "evt_5",
+            "turn.completed",
+            "turn_1",
+            serde_json::json!({
+                "turn_id": "turn_1",
+                "iterations": 2,
+                "usage": {"input_tokens": 20, "output_tokens": 9, "cache_read_tokens": 4}
+            }),
+        ),
+    ]
+    .concat();
+    mock_send(&server, body).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let mut turn = client
+        .messages()
+        .send("sess_1", CreateMessageRequest::user_text("Hi"))
+        .await
+        .unwrap();
+    assert_eq!(turn.status(), TurnStatus::Pending);
+    assert_eq!(turn.input_message_id(), "msg_in");
+
+    let first = turn.next_event().await.unwrap().unwrap();
+    assert_eq!(first.id, "evt_1");
+    assert_eq!(turn.status(), TurnStatus::Running);
+    assert_eq!(turn.turn_id(), Some("turn_1"));
+
+    turn.next_event().await.unwrap().unwrap();
+    turn.next_event().await.unwrap().unwrap();
+    let running = turn.usage().unwrap();
+    assert_eq!(running.input_tokens, 13);
+    assert_eq!(running.output_tokens, 7);
+    assert_eq!(running.cache_read_tokens, 1);
+
+    let outcome = turn.await_completion().await.unwrap();
+    assert_eq!(outcome.status, TurnStatus::Completed);
+    assert_eq!(outcome.turn_id.as_deref(), Some("turn_1"));
+    assert_eq!(outcome.stop_reason, Some(StopReason::MaxTokens));
+    assert_eq!(outcome.iterations, Some(2));
+    assert_eq!(outcome.trace_id.as_deref(), Some("turn_1"));
+    let ids: Vec<_> = outcome.messages.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["msg_out_1", "msg_out_2"]);
+    // turn.completed carries the authoritative total for the turn.
+    let usage = outcome.usage.unwrap();
+    assert_eq!(usage.input_tokens, 20);
+    assert_eq!(usage.output_tokens, 9);
+    assert_eq!(usage.cache_read_tokens, 4);
+}
+
+#[tokio::test]
+async fn test_turn_handle_reports_failure() {
+    let server = MockServer::start().await;
+    let body = [
+        sse_event("connected", "{}"),
+        turn_event(
+            "evt_1",
+            "turn.started",
+            "turn_1",
+            serde_json::json!({"turn_id": "turn_1", "input_message_id": "msg_in"}),
+        ),
+        turn_event(
+            "evt_2",
+            "turn.failed",
+            "turn_1",
+            serde_json::json!({"turn_id": "turn_1", "error": "model unavailable"}),
+        ),
+    ]
+    .concat();
+    mock_send(&server, body).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let turn = client
+        .messages()
+        .send("sess_1", CreateMessageRequest::user_text("Hi"))
+        .await
+        .unwrap();
+    let outcome = turn.await_completion().await.unwrap();
+    assert_eq!(outcome.status, TurnStatus::Failed);
+    assert_eq!(outcome.error.as_deref(), Some("model unavailable"));
+    assert!(outcome.messages.is_empty());
+}
+
+#[tokio::test]
+async fn test_turn_handle_cancel_posts_to_session() {
+    let server = MockServer::start().await;
+    let body = [
+        sse_event("connected", "{}"),
+        turn_event(
+            "evt_1",
+            "turn.started",
+            "turn_1",
+            serde_json::json!({"turn_id": "turn_1", "input_message_id": "msg_in"}),
+        ),
+        turn_event(
+            "evt_2",
+            "turn.cancelled",
+            "turn_1",
+            serde_json::json!({"turn_id": "turn_1", "reason": "user"}),
+        ),
+    ]
+    .concat();
+    mock_send(&server, body).await;
+    mock_cancel(&server).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let turn = client
+        .messages()
+        .send("sess_1", CreateMessageRequest::user_text("Hi"))
+        .await
+        .unwrap();
+    turn.cancel().await.unwrap();
+    let outcome = turn.await_completion().await.unwrap();
+    assert_eq!(outcome.status, TurnStatus::Cancelled);
+    assert_eq!(outcome.stop_reason, Some(StopReason::Cancelled));
+}
+
+async fn mock_cancel(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/cancel"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "cancelled",
+            "message": "Turn cancelled"
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+fn stalled_sse() -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .set_body_raw(
+            sse_event("connected", "{}").into_bytes(),
+            "text/event-stream",
+        )
+        .set_delay(Duration::from_secs(30))
+}
+
+#[tokio::test]
+async fn test_turn_handle_signal_cancels_turn() {
+    let server = MockServer::start().await;
+    mock_send_with_sse(&server, stalled_sse()).await;
+    mock_cancel(&server).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let turn = client
+        .messages()
+        .send("sess_1", CreateMessageRequest::user_text("Hi"))
+        .await
+        .unwrap();
+    let outcome = turn
+        .await_completion_or_cancel(tokio::time::sleep(Duration::from_millis(50)))
+        .await
+        .unwrap();
+    assert_eq!(outcome.status, TurnStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn test_run_with_options_cancels_on_caller_signal() {
+    let server = MockServer::start().await;
+    mock_send_with_sse(&server, stalled_sse()).await;
+    mock_cancel(&server).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let outcome = client
+        .messages()
+        .run_with_options(
+            "sess_1",
+            CreateMessageRequest::user_text("Hi"),
+            RunOptions::new()
+                .auto_continue(3)
+                .cancel_on(tokio::time::sleep(Duration::from_millis(50))),
+        )
+        .await
+        .unwrap();
+    assert_eq!(outcome.status, TurnStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn test_turn_handle_dropped_future_cancels_turn() {
+    let server = MockServer::start().await;
+    mock_send_with_sse(&server, stalled_sse()).await;
+    mock_cancel(&server).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let turn = client
+        .messages()
+        .send("sess_1", CreateMessageRequest::user_text("Hi"))
+        .await
+        .unwrap();
+    let aborted = tokio::time::timeout(
+        Duration::from_millis(50),
+        turn.await_completion_or_cancel(std::future::pending()),
+    )
+    .await;
+    assert!(aborted.is_err());
+
+    // The cancel request is sent from a background task after the drop.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn test_turn_handle_survives_missed_heartbeat() {
+    let server = MockServer::start().await;
+    // The first connection never sends a byte; the reconnect gets the turn.
+    Mock::given(method("GET"))
+        .and(path_regex("/v1/sessions/sess_1/sse"))
+        .respond_with(stalled_sse())
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    mock_send(&server, sse_turn("turn_1", "msg_in", "Done", &["stop"])).await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let options = everruns_sdk::sse::StreamOptions::default()
+        .with_heartbeat_interval(Duration::from_millis(100));
+    let turn = client
+        .messages()
+        .send_with_stream_options("sess_1", CreateMessageRequest::user_text("Hi"), options)
+        .await
+        .unwrap();
+    let outcome = tokio::time::timeout(Duration::from_secs(10), turn.await_completion())
+        .await
+        .expect("turn should complete after reconnecting")
+        .unwrap();
+    assert_eq!(outcome.status, TurnStatus::Completed);
+    assert_eq!(outcome.stop_reason, Some(StopReason::EndTurn));
+}
+
+/// A complete turn whose LLM generations stop with `finish_reasons`, in order.
+fn sse_turn(turn_id: &str, input_message_id: &str, text: &str, finish_reasons: &[&str]) -> String {
+    let event = |id: &str, event_type: &str, data: serde_json::Value| {
+        let event = serde_json::json!({
+            "id": format!("{turn_id}_{id}"),
+            "type": event_type,
+            "ts": "2024-01-01T00:00:00Z",
+            "session_id": "sess_1",
+            "data": data,
+            "context": {"turn_id": turn_id, "input_message_id": input_message_id}
+        });
+        sse_event(event_type, &event.to_string())
+    };
+    let generations: String = finish_reasons
+        .iter()
+        .enumerate()
+        .map(|(i, reason)| event(&format!("gen{i}"), "llm.generation", llm_generation(reason)))
+        .collect();
+    [
+        sse_event("connected", "{}"),
+        event(
+            "start",
+            "turn.started",
+            serde_json::json!({"turn_id": turn_id}),
+        ),
+        event(
+            "msg",
+            "output.message.completed",
+            serde_json::json!({
+                "message": output_message(&format!("{turn_id}_out"), text),
+                "usage": {"input_tokens": 10, "output_tokens": 100}
+            }),
+        ),
+        generations,
+        event(
+            "end",
+            "turn.completed",
+            serde_json::json!({"turn_id": turn_id}),
+        ),
+    ]
+    .concat()
+}
+
+#[tokio::test]
+async fn test_run_with_options_auto_continues_truncated_output() {
+    let server = MockServer::start().await;
+    let latest = |id: &str| {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{
+                "id": id,
+                "type": "turn.completed",
+                "ts": "2024-01-01T00:00:00Z",
+                "session_id": "sess_1",
+                "data": {}
+            }]
+        }))
+    };
+    let created = |id: &str| {
+        ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": id,
+            "session_id": "sess_1",
+            "sequence": 1,
+            "role": "user",
+            "content": [{"type": "text", "text": "Hi"}],
+            "created_at": "2024-01-01T00:00:00Z"
+        }))
+    };
+    let sse = |body: String| {
+        ResponseTemplate::new(200).set_body_raw(body.into_bytes(), "text/event-stream")
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .respond_with(latest("evt_prev"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/events"))
+        .respond_with(latest("turn_1_end"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .and(body_partial_json(serde_json::json!({
+            "message": {"content": [{"type": "text", "text": "Continue exactly where you left off."}]},
+            "controls": {"max_tokens": 100}
+        })))
+        .respond_with(created("msg_in_2"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_1/messages"))
+        .respond_with(created("msg_in"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .and(query_param("since_id", "evt_prev"))
+        .respond_with(sse(sse_turn(
+            "turn_1",
+            "msg_in",
+            "Once upon ",
+            &["tool_calls", "length"],
+        )))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_1/sse"))
+        .and(query_param("since_id", "turn_1_end"))
+        .respond_with(sse(sse_turn("turn_2", "msg_in_2", "a time.", &["stop"])))
+        .mount(&server)
+        .await;
+
+    let client = Everruns::with_base_url("test_key", &server.uri()).unwrap();
+    let outcome = client
+        .messages()
+        .run_with_options(
+            "sess_1",
+            CreateMessageRequest::user_text("Tell a story")
+                .controls(everruns_sdk::Controls::new().max_tokens(100)),
+            RunOptions::new().auto_continue(3),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.status, TurnStatus::Completed);
+    assert_eq!(outcome.stop_reason, Some(StopReason::EndTurn));
+    assert_eq!(outcome.text(), "Once upon a time.");
+    assert_eq!(outcome.input_message_id, "msg_in");
+    assert_eq!(outcome.turn_id.as_deref(), Some("turn_2"));
+    assert_eq!(outcome.usage.unwrap().output_tokens, 200);
+}
+
+#[tokio::test]
+async fn test_spawn_subagent_links_child_session_to_parent_turn() {
+    let server = MockServer::start().await;
+    let body = [
+        sse_event("connected", "{}"),
+        turn_event(
+            "evt_1",
+            "turn.started",
+            "turn_1",
+            serde_json::json!({"turn_id": "turn_1"}),
+        ),
+    ]
+    .concat();
+    mock_send(&server, body).await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions"))
+        .and(body_partial_json(serde_json::json!({
+            "agent_id": "agent_worker",
+            "tags": ["parent_session:sess_1", "parent_turn:turn_1"]
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "sess_child",
+            "organization_id": "org_1",
+            "harness_id": "harness_1",
+            "status": "started",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/sessions/sess_child/events"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/sessions/sess_child/messages"))
+        .and(body_partial_json(serde_json::json!({
+            "message": {"content": [{"type": "text", "text": "Check the logs"}]}
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "msg_child",
+            "session_id": "sess_child",
+            "sequence": 1,
+            "role": "user",
+            "content": [{"type": "text", "text": "Check the logs"}],
+            "created_at": "2024-01-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Everruns::with_base_url("evr_test_key", &server.uri()).unwrap();
+    let mut parent = client
+        .messages()
+        .send("sess_1", CreateMessageRequest::user_text("Hi"))
+        .await
+        .unwrap();
+    parent.next_event().await.unwrap().unwrap();
+    assert_eq!(parent.turn_id(), Some("turn_1"));
+
+    let child = parent
+        .spawn_subagent("agent_worker", "Check the logs")
+        .await
+        .unwrap();
+    assert_eq!(child.session_id(), "sess_child");
+    assert_eq!(child.input_message_id(), "msg_child");
+}